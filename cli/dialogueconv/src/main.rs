@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+use quicli::prelude::*;
+use structopt::StructOpt;
+
+use rust_rpg_toolkit::{prelude::*, serde_json};
+
+#[derive(StructOpt, Debug)]
+#[structopt(name = "dialogueconv")]
+struct Cli {
+    #[structopt(name = "FILE", parse(from_os_str))]
+    file: PathBuf,
+    #[structopt(name = "OUT", parse(from_os_str))]
+    output: PathBuf,
+    /// The format of the script, `yarn` or `ink`. If this is not specified, the format is
+    /// determined by the extension of the script file.
+    #[structopt(long)]
+    format: Option<DialogueScriptFormat>,
+}
+
+fn main() -> CliResult {
+    let args = Cli::from_args();
+
+    let format = args
+        .format
+        .or_else(|| DialogueScriptFormat::from_path(&args.file))
+        .expect("Unable to determine the script format from the file extension");
+
+    let script = read_file(args.file)?;
+    let dialogue = import_dialogue_script(&script, format)?;
+    let json = serde_json::to_string_pretty(&dialogue)?;
+    write_to_file(args.output, &json)?;
+
+    println!("Success!");
+
+    Ok(())
+}
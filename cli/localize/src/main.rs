@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+use quicli::prelude::*;
+use structopt::StructOpt;
+
+use rust_rpg_toolkit::{localization::get_missing_strings, serde_json};
+
+#[derive(StructOpt, Debug)]
+#[structopt(name = "localize")]
+struct Cli {
+    /// The data directory of the game, containing `locales.json`
+    #[structopt(name = "DATA", parse(from_os_str))]
+    data_path: PathBuf,
+    /// The id of the locale to check for missing strings
+    #[structopt(name = "LOCALE")]
+    locale_id: String,
+    #[structopt(name = "OUT", parse(from_os_str))]
+    output: PathBuf,
+}
+
+fn main() -> CliResult {
+    let args = Cli::from_args();
+
+    let missing = get_missing_strings(&args.data_path, &args.locale_id)?;
+
+    // Missing strings are written with empty values, which are ignored until they are translated
+    let table: serde_json::Map<String, serde_json::Value> = missing
+        .into_iter()
+        .map(|key| (key, serde_json::Value::String("".to_string())))
+        .collect();
+    let json = serde_json::to_string_pretty(&table)?;
+    write_to_file(args.output, &json)?;
+
+    println!("Found {} missing strings!", table.len());
+
+    Ok(())
+}
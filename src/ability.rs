@@ -1,7 +1,7 @@
 use crate::nodes::projectiles::ProjectileParams;
 use crate::prelude::*;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum DamageType {
     Piercing,
@@ -11,13 +11,111 @@ pub enum DamageType {
     Heat,
 }
 
+impl DamageType {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            DamageType::Piercing => "piercing",
+            DamageType::Slashing => "slashing",
+            DamageType::Blunt => "blunt",
+            DamageType::Energy => "energy",
+            DamageType::Heat => "heat",
+        }
+    }
+
+    // The id of the stat that holds an actor's resistance to this damage type, as a percentage
+    pub fn get_resistance_stat(&self) -> String {
+        format!("{}_resistance", self.to_str())
+    }
+
+    // The id of the stat that holds an actor's armor against this damage type, which is
+    // subtracted from the damage after resistance
+    pub fn get_armor_stat(&self) -> String {
+        format!("{}_armor", self.to_str())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum Effect {
     Damage {
         damage_type: DamageType,
         amount: f32,
+        // This is set when an ability is activated, if the activating actor rolls a critical hit
+        #[serde(skip)]
+        critical_multiplier: Option<f32>,
+    },
+    Knockback {
+        force: f32,
+    },
+    ApplyStatus {
+        status: StatusEffectParams,
+    },
+    Heal {
+        amount: f32,
+    },
+    RestoreStamina {
+        amount: f32,
     },
+    RestoreEnergy {
+        amount: f32,
+    },
+    // This moves the target up to `distance` in the direction of the effect, without passing
+    // through walls. When targeting `self`, this is the direction the actor is aiming in.
+    Teleport {
+        distance: f32,
+    },
+    // This spawns an actor from a prototype, at the position of the target. If `inherit_factions`
+    // is `true`, the spawned actor will also join the factions of the source of the effect.
+    SpawnActor {
+        #[serde(rename = "actor")]
+        prototype_id: String,
+        #[serde(default, skip_serializing_if = "helpers::is_false")]
+        inherit_factions: bool,
+    },
+    SpawnItem {
+        #[serde(rename = "item")]
+        prototype_id: String,
+    },
+}
+
+// This determines which actors an effect can be applied to, based on the factions of the source
+// of the effect
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EffectTarget {
+    // The actor that activated the ability. These effects are applied when it is activated, no
+    // matter how the ability is delivered.
+    #[serde(rename = "self")]
+    Source,
+    // Any actor, other than the source, that shares a faction with the source
+    Allies,
+    // Any actor that shares no factions with the source
+    Enemies,
+    // Any actor, other than the source
+    All,
+}
+
+impl Default for EffectTarget {
+    fn default() -> Self {
+        EffectTarget::Enemies
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AbilityEffect {
+    #[serde(default)]
+    pub target: EffectTarget,
+    #[serde(flatten)]
+    pub effect: Effect,
+}
+
+impl From<Effect> for AbilityEffect {
+    fn from(effect: Effect) -> Self {
+        AbilityEffect {
+            target: EffectTarget::default(),
+            effect,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -27,9 +125,17 @@ pub enum AbilityDelivery {
         projectile_kind: ProjectileKind,
         spread: f32,
         speed: f32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        collision_mask: Option<Vec<String>>,
     },
     Melee,
-    ContinuousBeam,
+    ContinuousBeam {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        collision_mask: Option<Vec<String>>,
+    },
+    // The ability is not delivered to other actors, so only effects that target `self` are
+    // applied. This is used for things like potions and self buffs.
+    Instant,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -59,7 +165,7 @@ pub struct AbilityParams {
     #[serde(default)]
     pub energy_cost: f32,
     pub range: f32,
-    pub effects: Vec<Effect>,
+    pub effects: Vec<AbilityEffect>,
     #[serde(
         default,
         with = "json::opt_color",
@@ -81,6 +187,7 @@ impl Default for AbilityParams {
                 projectile_kind: ProjectileKind::Bullet,
                 speed: 8.0,
                 spread: 5.0,
+                collision_mask: None,
             },
             cooldown: 0.0,
             health_cost: 0.0,
@@ -96,6 +203,7 @@ impl Default for AbilityParams {
 
 #[derive(Clone)]
 pub struct Ability {
+    pub id: String,
     pub noise_level: NoiseLevel,
     pub delivery: AbilityDelivery,
     pub sound_effect: Option<Sound>,
@@ -106,7 +214,7 @@ pub struct Ability {
     pub stamina_cost: f32,
     pub energy_cost: f32,
     pub range: f32,
-    pub effects: Vec<Effect>,
+    pub effects: Vec<AbilityEffect>,
     pub color_override: Option<Color>,
     pub size_override: Option<f32>,
 }
@@ -140,6 +248,7 @@ impl Ability {
         }
 
         Ability {
+            id: params.id,
             sound_effect,
             on_hit_sound_effect,
             noise_level: params.noise_level,
@@ -156,7 +265,29 @@ impl Ability {
         }
     }
 
-    pub fn activate(&mut self, node: &mut RefMut<Actor>, origin: Vec2, direction: Vec2) {
+    // This rolls for a critical hit, using the stats of the activating actor, and returns the
+    // ability's effects, with the critical multiplier set on any damage, if it was a success
+    fn get_effects(&self, stats: &ActorStats) -> Vec<AbilityEffect> {
+        let multiplier = roll_critical_hit(stats);
+        self.effects
+            .iter()
+            .cloned()
+            .map(|mut ability_effect| {
+                if let Effect::Damage {
+                    critical_multiplier,
+                    ..
+                } = &mut ability_effect.effect
+                {
+                    *critical_multiplier = multiplier;
+                }
+                ability_effect
+            })
+            .collect()
+    }
+
+    // Returns `true` if the ability was activated, or `false` if it is on cooldown, or the actor
+    // can not afford its costs
+    pub fn activate(&mut self, node: &mut RefMut<Actor>, origin: Vec2, direction: Vec2) -> bool {
         if self.cooldown_timer >= self.cooldown
             && (self.health_cost == 0.0 || node.stats.current_health >= self.health_cost)
             && (self.stamina_cost == 0.0 || node.stats.current_stamina >= self.stamina_cost)
@@ -169,9 +300,28 @@ impl Ability {
             node.stats.current_stamina -= self.stamina_cost;
             node.stats.current_energy -= self.energy_cost;
 
+            let (self_effects, effects): (Vec<AbilityEffect>, Vec<AbilityEffect>) = self
+                .get_effects(&node.stats)
+                .into_iter()
+                .partition(|effect| effect.target == EffectTarget::Source);
+
+            let actor_id = node.id.clone();
+            let handle = node.handle();
+            let factions = node.factions.clone();
+            for effect in self_effects {
+                node.apply_effect(&actor_id, handle, &factions, direction, effect, false);
+            }
+
+            // Projectiles and beams of abilities without a collision mask pass through actors on
+            // the same collision layer as the source
+            let friendly_layer = get_friendly_collision_layer(&node.body.collision_layer);
+
             match self.delivery.clone() {
-                AbilityDelivery::ContinuousBeam => {
+                AbilityDelivery::ContinuousBeam { collision_mask } => {
                     let end = node.body.position + direction * self.range;
+                    let friendly_layer = friendly_layer.filter(|_| collision_mask.is_none());
+                    let collision_mask =
+                        collision_mask.unwrap_or_else(default_projectile_collision_mask);
 
                     let mut continuous_beams =
                         scene::find_node_by_type::<ContinuousBeams>().unwrap();
@@ -179,9 +329,11 @@ impl Ability {
                         &node.id,
                         node.handle(),
                         &node.factions,
-                        &self.effects,
+                        &effects,
                         self.color_override,
                         self.size_override,
+                        &collision_mask,
+                        friendly_layer,
                         origin,
                         end,
                     );
@@ -190,10 +342,14 @@ impl Ability {
                     projectile_kind,
                     spread,
                     speed,
+                    collision_mask,
                 } => {
                     let params = ProjectileParams {
                         kind: projectile_kind,
-                        effects: self.effects.clone(),
+                        effects,
+                        friendly_layer: friendly_layer.filter(|_| collision_mask.is_none()),
+                        collision_mask: collision_mask
+                            .unwrap_or_else(default_projectile_collision_mask),
                         color: self
                             .color_override
                             .unwrap_or(Projectiles::DEFAULT_PROJECTILE_COLOR),
@@ -228,14 +384,14 @@ impl Ability {
                             };
 
                         if hit_success {
-                            for effect in self.effects.clone() {
-                                other_actor.apply_effect(
-                                    &node.id,
-                                    node.handle(),
-                                    &node.factions,
-                                    effect,
-                                );
-                            }
+                            let direction = other_actor.body.position - origin;
+                            other_actor.apply_hit(
+                                &node.id,
+                                node.handle(),
+                                &node.factions,
+                                direction,
+                                &effects,
+                            );
                         }
                     }
 
@@ -249,11 +405,54 @@ impl Ability {
                         play_sound(sound_effect, false);
                     }
                 }
+                AbilityDelivery::Instant => {
+                    if let Some(sound_effect) = self.sound_effect {
+                        play_sound(sound_effect, false);
+                    }
+                }
             }
+
+            return true;
         }
+
+        false
     }
 
     pub fn update(&mut self) {
         self.cooldown_timer += get_frame_time();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effect_targets_from_json() {
+        let json = r#"[
+            { "type": "damage", "damage_type": "heat", "amount": 10.0 },
+            { "type": "heal", "target": "self", "amount": 5.0 },
+            { "type": "spawn_actor", "target": "allies", "actor": "drone", "inherit_factions": true }
+        ]"#;
+        let effects: Vec<AbilityEffect> = serde_json::from_str(json).unwrap();
+        assert_eq!(effects[0].target, EffectTarget::Enemies);
+        assert_eq!(
+            effects[1],
+            AbilityEffect {
+                target: EffectTarget::Source,
+                effect: Effect::Heal { amount: 5.0 },
+            }
+        );
+        assert_eq!(
+            effects[2].effect,
+            Effect::SpawnActor {
+                prototype_id: "drone".to_string(),
+                inherit_factions: true,
+            }
+        );
+
+        let json = serde_json::to_string(&effects[1]).unwrap();
+        let res: AbilityEffect = serde_json::from_str(&json).unwrap();
+        assert_eq!(res, effects[1]);
+    }
+}
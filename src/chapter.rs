@@ -16,6 +16,13 @@ pub struct ChapterParams {
     pub description: String,
     pub initial_map_id: String,
     pub maps: Vec<MapParams>,
+    // If this is `true`, the title and description of the chapter will be shown on a separate
+    // screen, when the chapter is started
+    #[serde(default, skip_serializing_if = "helpers::is_false")]
+    pub has_intro: bool,
+    // Text that is shown on a separate screen, when the chapter is completed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outro: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -24,6 +31,8 @@ pub struct Chapter {
     pub description: String,
     pub initial_map_id: String,
     pub maps: HashMap<String, Map>,
+    pub has_intro: bool,
+    pub outro: Option<String>,
 }
 
 impl Chapter {
@@ -47,6 +56,8 @@ impl Chapter {
             description: params.description,
             initial_map_id: params.initial_map_id,
             maps,
+            has_intro: params.has_intro,
+            outro: params.outro,
         };
 
         Ok(chapter)
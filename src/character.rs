@@ -12,6 +12,18 @@ pub struct Character {
     pub chapter_index: usize,
     pub map_id: String,
     pub is_permadeath: bool,
+    // The indices of the chapters that the character has completed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub completed_chapters: Vec<usize>,
+    // World variables and flags, which are set by dialogue actions
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub variables: HashMap<String, i32>,
+    // The saved contents of containers, by map id
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub containers: HashMap<String, Vec<ContainerState>>,
+    // The saved stock of vendors, by vendor id
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub vendors: HashMap<String, Vendor>,
 }
 
 impl Character {
@@ -23,6 +35,15 @@ impl Character {
         }
     }
 
+    // A chapter is unlocked if it is the first chapter, the current chapter, a completed chapter
+    // or the chapter following a completed one
+    pub fn is_chapter_unlocked(&self, chapter_index: usize) -> bool {
+        chapter_index == 0
+            || chapter_index == self.chapter_index
+            || self.completed_chapters.contains(&chapter_index)
+            || self.completed_chapters.contains(&(chapter_index - 1))
+    }
+
     #[cfg(not(any(target_family = "wasm", target_os = "android")))]
     pub fn save(&self) -> Result<()> {
         let json = serde_json::to_string_pretty(self)?;
@@ -114,3 +135,33 @@ pub fn delete_character(name: &str) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_chapter_unlocked() {
+        let character = Character {
+            game_version: "0.1.0".to_string(),
+            actor: Default::default(),
+            items: Vec::new(),
+            active_missions: Vec::new(),
+            completed_missions: Vec::new(),
+            chapter_index: 1,
+            map_id: "map".to_string(),
+            is_permadeath: false,
+            completed_chapters: vec![0, 3],
+            variables: HashMap::new(),
+            containers: HashMap::new(),
+            vendors: HashMap::new(),
+        };
+
+        assert!(character.is_chapter_unlocked(0));
+        assert!(character.is_chapter_unlocked(1));
+        assert!(!character.is_chapter_unlocked(2));
+        assert!(character.is_chapter_unlocked(3));
+        assert!(character.is_chapter_unlocked(4));
+        assert!(!character.is_chapter_unlocked(5));
+    }
+}
@@ -18,6 +18,9 @@ pub struct Config {
     pub sound_effects_volume: u8,
     #[serde(default)]
     pub music_volume: u8,
+    // The id of the locale that is used to translate text shown to the player
+    #[serde(default = "Config::default_locale")]
+    pub locale: String,
 
     #[serde(default, skip)]
     path: String,
@@ -33,6 +36,7 @@ impl Default for Config {
             master_volume: 100,
             sound_effects_volume: 100,
             music_volume: 100,
+            locale: Config::default_locale(),
             path: "config.json".to_string(),
         }
     }
@@ -42,6 +46,11 @@ impl Config {
     pub const MIN_GUI_SCALE: f32 = 0.1;
     pub const MAX_GUI_SCALE: f32 = 5.0;
     pub const GUI_SCALE_STEP: f32 = 0.1;
+    pub const DEFAULT_LOCALE: &'static str = "en";
+
+    pub fn default_locale() -> String {
+        Self::DEFAULT_LOCALE.to_string()
+    }
 
     #[cfg(any(target_family = "unix", target_family = "windows"))]
     pub fn load(path: &str) -> Self {
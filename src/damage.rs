@@ -0,0 +1,159 @@
+use crate::prelude::*;
+
+// Resistances are percentages, so a resistance of `100` makes an actor immune to a damage type,
+// while a negative resistance will increase the damage taken
+pub const MAX_RESISTANCE: f32 = 100.0;
+pub const MIN_RESISTANCE: f32 = -100.0;
+
+pub const MAX_EVASION_CHANCE: f32 = 75.0;
+
+pub const DEFAULT_CRITICAL_MULTIPLIER: f32 = 1.5;
+
+// The outcome of damage taken by an actor, which is dispatched with `Event::DamageTaken`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DamageResult {
+    pub damage_type: DamageType,
+    // The amount before resistance and armor, including any critical multiplier
+    pub base_amount: f32,
+    // The amount that was actually taken
+    pub amount: f32,
+    // The amount that was mitigated by resistance and armor. This will be negative if the actor
+    // is vulnerable to the damage type.
+    pub resisted: f32,
+    pub is_critical: bool,
+    pub is_evaded: bool,
+}
+
+impl DamageResult {
+    // This calculates the damage taken by an actor with `stats`, without rolling for evasion.
+    // Resistance is applied first, as a percentage, and armor is then subtracted from the rest.
+    pub fn new(
+        stats: &ActorStats,
+        damage_type: DamageType,
+        amount: f32,
+        critical_multiplier: Option<f32>,
+    ) -> Self {
+        let base_amount = amount * critical_multiplier.unwrap_or(1.0);
+
+        let resistance = stats
+            .get_stat(&damage_type.get_resistance_stat())
+            .unwrap_or(0.0)
+            .clamp(MIN_RESISTANCE, MAX_RESISTANCE);
+        let armor = stats
+            .get_stat(&damage_type.get_armor_stat())
+            .unwrap_or(0.0)
+            .max(0.0);
+
+        let amount = (base_amount * (1.0 - resistance / 100.0) - armor).max(0.0);
+
+        DamageResult {
+            damage_type,
+            base_amount,
+            amount,
+            resisted: base_amount - amount,
+            is_critical: critical_multiplier.is_some(),
+            is_evaded: false,
+        }
+    }
+
+    pub fn evaded(damage_type: DamageType, amount: f32, critical_multiplier: Option<f32>) -> Self {
+        DamageResult {
+            damage_type,
+            base_amount: amount * critical_multiplier.unwrap_or(1.0),
+            amount: 0.0,
+            resisted: 0.0,
+            is_critical: critical_multiplier.is_some(),
+            is_evaded: true,
+        }
+    }
+}
+
+pub fn get_evasion_chance(stats: &ActorStats) -> f32 {
+    stats
+        .get_stat("evasion_chance")
+        .unwrap_or(0.0)
+        .clamp(0.0, MAX_EVASION_CHANCE)
+}
+
+pub fn roll_evasion(stats: &ActorStats) -> bool {
+    rand::gen_range(0.0, 100.0) < get_evasion_chance(stats)
+}
+
+// This rolls for a critical hit, using the stats of the attacker, and returns the critical
+// multiplier, if it was a success
+pub fn roll_critical_hit(stats: &ActorStats) -> Option<f32> {
+    let critical_chance = stats.get_stat("critical_chance").unwrap_or(0.0);
+    if rand::gen_range(0.0, 100.0) < critical_chance {
+        let multiplier = stats
+            .get_stat("critical_multiplier")
+            .unwrap_or(DEFAULT_CRITICAL_MULTIPLIER);
+        Some(multiplier)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_stats(modifiers: &[StatModifier]) -> ActorStats {
+        let mut stats = ActorStats::default();
+        stats.modifiers.add("test", modifiers);
+        stats.recalculate_derived_with(&StatFormulas::default());
+        stats
+    }
+
+    #[test]
+    fn test_resistance_and_armor() {
+        let stats = get_stats(&[
+            StatModifier::new("heat_resistance", StatModifierKind::Flat, 50.0),
+            StatModifier::new("heat_armor", StatModifierKind::Flat, 10.0),
+        ]);
+
+        let res = DamageResult::new(&stats, DamageType::Heat, 100.0, None);
+        assert_eq!(res.amount, 40.0);
+        assert_eq!(res.resisted, 60.0);
+
+        let res = DamageResult::new(&stats, DamageType::Heat, 100.0, Some(2.0));
+        assert!(res.is_critical);
+        assert_eq!(res.base_amount, 200.0);
+        assert_eq!(res.amount, 90.0);
+
+        let res = DamageResult::new(&stats, DamageType::Heat, 10.0, None);
+        assert_eq!(res.amount, 0.0);
+
+        let res = DamageResult::new(&stats, DamageType::Piercing, 100.0, None);
+        assert_eq!(res.amount, 100.0);
+        assert_eq!(res.resisted, 0.0);
+    }
+
+    #[test]
+    fn test_resistance_limits() {
+        let stats = get_stats(&[
+            StatModifier::new("energy_resistance", StatModifierKind::Flat, 150.0),
+            StatModifier::new("blunt_resistance", StatModifierKind::Flat, -50.0),
+        ]);
+
+        let res = DamageResult::new(&stats, DamageType::Energy, 100.0, None);
+        assert_eq!(res.amount, 0.0);
+
+        let res = DamageResult::new(&stats, DamageType::Blunt, 100.0, None);
+        assert_eq!(res.amount, 150.0);
+        assert_eq!(res.resisted, -50.0);
+    }
+
+    #[test]
+    fn test_evasion_chance() {
+        let mut stats = ActorStats {
+            dexterity: 10,
+            ..Default::default()
+        };
+        stats.recalculate_derived_with(&StatFormulas::default());
+        assert_eq!(get_evasion_chance(&stats), 5.0);
+
+        stats.dexterity = 1000;
+        stats.recalculate_derived_with(&StatFormulas::default());
+        assert_eq!(get_evasion_chance(&stats), MAX_EVASION_CHANCE);
+    }
+}
@@ -0,0 +1,948 @@
+mod script;
+
+use crate::prelude::*;
+pub use script::{import_dialogue_script, DialogueScriptFormat};
+
+// The state that dialogue requirements are checked against
+pub struct DialogueContext<'a> {
+    pub level: u32,
+    pub stats: &'a ActorStats,
+    pub inventory: &'a Inventory,
+    pub factions: &'a [String],
+    pub reputation: &'a HashMap<String, i32>,
+    pub active_missions: Vec<String>,
+    pub completed_missions: Vec<String>,
+    // The world variables of the game, which include flags
+    pub variables: &'a HashMap<String, i32>,
+}
+
+impl<'a> DialogueContext<'a> {
+    pub fn new(actor: &'a Actor, variables: &'a HashMap<String, i32>) -> Self {
+        DialogueContext {
+            level: actor.level,
+            stats: &actor.stats,
+            inventory: &actor.inventory,
+            factions: &actor.factions,
+            reputation: &actor.reputation,
+            active_missions: actor
+                .active_missions
+                .iter()
+                .map(|mission| mission.id.clone())
+                .collect(),
+            completed_missions: actor
+                .completed_missions
+                .iter()
+                .map(|mission| mission.id.clone())
+                .collect(),
+            variables,
+        }
+    }
+
+    pub fn get_variable(&self, variable: &str) -> i32 {
+        self.variables.get(variable).cloned().unwrap_or(0)
+    }
+}
+
+fn is_in_range<T: PartialOrd>(value: T, min: Option<T>, max: Option<T>) -> bool {
+    min.map(|min| value >= min).unwrap_or(true) && max.map(|max| value <= max).unwrap_or(true)
+}
+
+// Requirements can be combined with `all`, `any` and `not`. Ranges, like the `min` and `max` of
+// `stat`, are inclusive and both ends are optional.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DialogueRequirement {
+    All {
+        requirements: Vec<DialogueRequirement>,
+    },
+    Any {
+        requirements: Vec<DialogueRequirement>,
+    },
+    Not {
+        requirement: Box<DialogueRequirement>,
+    },
+    ActiveMission {
+        mission_id: String,
+    },
+    CompletedMission {
+        mission_id: String,
+    },
+    IsInFaction {
+        faction_id: String,
+    },
+    Reputation {
+        faction_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<i32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<i32>,
+    },
+    Level {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<u32>,
+    },
+    // This checks the modified value of an attribute, or a derived stat
+    Stat {
+        stat: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<f32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<f32>,
+    },
+    // This checks the total quantity of items of a prototype
+    HasItem {
+        prototype_id: String,
+        #[serde(default = "DialogueRequirement::default_quantity")]
+        quantity: u32,
+    },
+    Credits {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<u32>,
+    },
+    // A flag is set if its variable has a value other than zero
+    Flag {
+        flag: String,
+    },
+    Variable {
+        variable: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<i32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<i32>,
+    },
+}
+
+impl DialogueRequirement {
+    pub fn default_quantity() -> u32 {
+        1
+    }
+
+    pub fn is_met(&self, context: &DialogueContext) -> bool {
+        match self {
+            DialogueRequirement::All { requirements } => requirements
+                .iter()
+                .all(|requirement| requirement.is_met(context)),
+            DialogueRequirement::Any { requirements } => requirements
+                .iter()
+                .any(|requirement| requirement.is_met(context)),
+            DialogueRequirement::Not { requirement } => !requirement.is_met(context),
+            DialogueRequirement::ActiveMission { mission_id } => {
+                context.active_missions.contains(mission_id)
+            }
+            DialogueRequirement::CompletedMission { mission_id } => {
+                context.completed_missions.contains(mission_id)
+            }
+            DialogueRequirement::IsInFaction { faction_id } => {
+                context.factions.contains(faction_id)
+            }
+            DialogueRequirement::Reputation {
+                faction_id,
+                min,
+                max,
+            } => {
+                let reputation = context.reputation.get(faction_id).cloned().unwrap_or(0);
+                is_in_range(reputation, *min, *max)
+            }
+            DialogueRequirement::Level { min, max } => is_in_range(context.level, *min, *max),
+            DialogueRequirement::Stat { stat, min, max } => {
+                let value = context.stats.get_stat(stat).unwrap_or(0.0);
+                is_in_range(value, *min, *max)
+            }
+            DialogueRequirement::HasItem {
+                prototype_id,
+                quantity,
+            } => context.inventory.get_quantity(prototype_id) >= *quantity,
+            DialogueRequirement::Credits { min, max } => {
+                is_in_range(context.inventory.credits, *min, *max)
+            }
+            DialogueRequirement::Flag { flag } => context.get_variable(flag) != 0,
+            DialogueRequirement::Variable { variable, min, max } => {
+                is_in_range(context.get_variable(variable), *min, *max)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DialogueAction {
+    OpenTrade,
+    StartMission {
+        mission_id: String,
+    },
+    CompleteMission {
+        mission_id: String,
+    },
+    MapTransition {
+        chapter_index: usize,
+        map_id: String,
+    },
+    CompleteChapter,
+    SetVariable {
+        variable: String,
+        value: i32,
+    },
+    ModifyVariable {
+        variable: String,
+        amount: i32,
+    },
+    ModifyReputation {
+        faction_id: String,
+        amount: i32,
+    },
+    JoinFaction {
+        faction_id: String,
+    },
+    LeaveFaction {
+        faction_id: String,
+    },
+    SetFlag {
+        flag: String,
+    },
+    ClearFlag {
+        flag: String,
+    },
+    // Items that don't fit in the player's inventory are dropped on the ground
+    GiveItem {
+        prototype_id: String,
+        #[serde(default = "DialogueRequirement::default_quantity")]
+        quantity: u32,
+    },
+    // If the player doesn't have enough unequipped items of the prototype, none of the actions of
+    // the option are applied
+    TakeItem {
+        prototype_id: String,
+        #[serde(default = "DialogueRequirement::default_quantity")]
+        quantity: u32,
+    },
+    GiveCredits {
+        amount: u32,
+    },
+    // If the player doesn't have enough credits, none of the actions of the option are applied
+    TakeCredits {
+        amount: u32,
+    },
+    GrantExperience {
+        amount: u32,
+    },
+    // This makes the actor that holds the dialogue hostile to the player and closes the dialogue
+    StartCombat,
+    PlaySound {
+        sound_effect_id: String,
+    },
+    // This moves the dialogue to another dialogue node, with the id `dialogue_id`
+    Jump {
+        dialogue_id: String,
+    },
+}
+
+// A check against a stat of the player, that moves the dialogue to the `success` option, or the
+// `failure` option, depending on the outcome. The outcome is recorded in a world variable, so that
+// the check can not be re-rolled by reopening the dialogue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DialogueCheck {
+    pub stat: String,
+    pub difficulty: f32,
+    pub success: String,
+    pub failure: String,
+    // If this is `true`, the chance of success is shown in the title of the option
+    #[serde(default, skip_serializing_if = "helpers::is_false")]
+    pub show_chance: bool,
+}
+
+impl DialogueCheck {
+    // Every point of the stat above, or below, the difficulty changes the chance of success by
+    // this many percent, from a base chance of 50%
+    pub const CHANCE_PER_POINT: f32 = 5.0;
+
+    pub const MIN_CHANCE: f32 = 5.0;
+    pub const MAX_CHANCE: f32 = 95.0;
+
+    pub const SUCCESS_VALUE: i32 = 1;
+    pub const FAILURE_VALUE: i32 = -1;
+
+    // The name of the world variable that the outcome of the check of the dialogue option with
+    // `dialogue_id` is recorded in
+    pub fn get_variable_name(dialogue_id: &str) -> String {
+        format!("check_{}", dialogue_id)
+    }
+
+    // Returns the chance of success, in percent
+    pub fn get_chance(&self, stats: &ActorStats) -> f32 {
+        let value = stats.get_stat(&self.stat).unwrap_or(0.0);
+        (50.0 + (value - self.difficulty) * Self::CHANCE_PER_POINT)
+            .clamp(Self::MIN_CHANCE, Self::MAX_CHANCE)
+    }
+
+    pub fn roll(&self, stats: &ActorStats) -> bool {
+        rand::gen_range(0.0, 100.0) < self.get_chance(stats)
+    }
+
+    // This returns the outcome of the check of the dialogue option with `dialogue_id`. If an
+    // outcome has already been recorded in `variables`, that is returned, and if not, the check is
+    // rolled with `roll` and the outcome is recorded.
+    pub fn resolve<F>(
+        &self,
+        dialogue_id: &str,
+        variables: &mut HashMap<String, i32>,
+        roll: F,
+    ) -> bool
+    where
+        F: FnOnce() -> bool,
+    {
+        let value = *variables
+            .entry(Self::get_variable_name(dialogue_id))
+            .or_insert_with(|| {
+                if roll() {
+                    Self::SUCCESS_VALUE
+                } else {
+                    Self::FAILURE_VALUE
+                }
+            });
+        value == Self::SUCCESS_VALUE
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dialogue {
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub body: Vec<String>,
+    #[serde(default)]
+    pub response: Vec<String>,
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub requirements: Vec<DialogueRequirement>,
+    #[serde(default)]
+    pub exclusions: Vec<DialogueRequirement>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<DialogueAction>,
+    // Actions that are applied in order, after `action`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<DialogueAction>,
+    // A check that is rolled after the actions have been applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check: Option<DialogueCheck>,
+    // The id of the actor that holds the dialogue
+    #[serde(skip)]
+    pub actor_id: String,
+    #[serde(skip)]
+    pub actor_name: String,
+    // The vendor of the actor that holds the dialogue, which is opened by the `open_trade` action
+    #[serde(skip)]
+    pub vendor_id: Option<String>,
+    #[serde(skip)]
+    pub should_apply: bool,
+}
+
+impl Dialogue {
+    // An option is available if all of its requirements are met and none of its exclusions are
+    pub fn is_available(&self, context: &DialogueContext) -> bool {
+        self.requirements
+            .iter()
+            .all(|requirement| requirement.is_met(context))
+            && !self
+                .exclusions
+                .iter()
+                .any(|exclusion| exclusion.is_met(context))
+    }
+
+    pub fn get_options(&self, actor: &Actor) -> Vec<Self> {
+        let resources = storage::get::<Resources>();
+        let game_state = scene::get_node(actor.game_state);
+        let context = DialogueContext::new(actor, &game_state.variables);
+
+        let mut dialogue = Vec::new();
+        for option_id in &self.options {
+            let option = resources.dialogue.get(option_id).unwrap();
+            if option.is_available(&context) {
+                let mut option = option.clone();
+                option.actor_id = self.actor_id.clone();
+                option.actor_name = self.actor_name.clone();
+                option.vendor_id = self.vendor_id.clone();
+                option.title = tr(&option.title);
+                if let Some(check) = &option.check {
+                    let variable = DialogueCheck::get_variable_name(&option.id);
+                    if check.show_chance && !context.variables.contains_key(&variable) {
+                        option.title = format!(
+                            "[{} {}%] {}",
+                            tr(&check.stat),
+                            check.get_chance(context.stats).round(),
+                            option.title
+                        );
+                    }
+                }
+                dialogue.push(option);
+            }
+        }
+        dialogue
+    }
+
+    // Returns `true` if `inventory` holds all the items and credits that are taken by the actions
+    // of the option. Takes of the same item prototype, or of credits, are added together. Like the
+    // `has_item` requirement, this counts equipped items, which are unequipped when taken.
+    pub fn can_take(&self, inventory: &Inventory) -> bool {
+        let mut items: HashMap<&str, u32> = HashMap::new();
+        let mut credits = 0;
+        for action in self.action.iter().chain(self.actions.iter()) {
+            match action {
+                DialogueAction::TakeItem {
+                    prototype_id,
+                    quantity,
+                } => *items.entry(prototype_id).or_insert(0) += quantity,
+                DialogueAction::TakeCredits { amount } => credits += amount,
+                _ => {}
+            }
+        }
+
+        inventory.credits >= credits
+            && items
+                .into_iter()
+                .all(|(prototype_id, quantity)| inventory.get_quantity(prototype_id) >= quantity)
+    }
+
+    // This applies `action` and then all the entries in `actions`, in order, before resolving the
+    // check, if there is one. The items and credits that are taken by the actions are validated
+    // first, and if the player doesn't have them, the whole chain is aborted and `false` is
+    // returned.
+    pub fn apply_actions(&self, actor: &mut RefMut<Actor>) -> bool {
+        if !self.can_take(&actor.inventory) {
+            return false;
+        }
+
+        for action in self.action.iter().chain(self.actions.iter()) {
+            self.apply_action(action.clone(), actor);
+        }
+
+        if let Some(check) = &self.check {
+            let is_success = {
+                let mut game_state = scene::get_node(actor.game_state);
+                let stats = &actor.stats;
+                check.resolve(&self.id, &mut game_state.variables, || check.roll(stats))
+            };
+
+            let dialogue_id = if is_success {
+                &check.success
+            } else {
+                &check.failure
+            };
+
+            // Unlike `jump`, the outcome of a check is applied, as if it had been selected by the
+            // player, so that its actions are applied
+            let mut dialogue = self.get_linked_dialogue(dialogue_id);
+            dialogue.should_apply = true;
+            actor.current_dialogue = Some(dialogue);
+        }
+
+        true
+    }
+
+    // This returns the dialogue option with `dialogue_id`, held by the same actor as this one
+    fn get_linked_dialogue(&self, dialogue_id: &str) -> Dialogue {
+        let resources = storage::get::<Resources>();
+        let mut dialogue = resources
+            .dialogue
+            .get(dialogue_id)
+            .cloned()
+            .unwrap_or_else(|| panic!("Unable to find dialogue with id '{}'", dialogue_id));
+        dialogue.actor_id = self.actor_id.clone();
+        dialogue.actor_name = self.actor_name.clone();
+        dialogue.vendor_id = self.vendor_id.clone();
+        dialogue
+    }
+
+    fn apply_action(&self, action: DialogueAction, actor: &mut RefMut<Actor>) {
+        let resources = storage::get::<Resources>();
+        match action {
+            DialogueAction::OpenTrade => {
+                if let Some(vendor_id) = &self.vendor_id {
+                    actor.current_dialogue = None;
+                    actor.current_vendor = Some(vendor_id.clone());
+                }
+            }
+            DialogueAction::CompleteMission { mission_id } => {
+                complete_mission(&mut actor.active_missions, &mission_id);
+            }
+            DialogueAction::StartMission { mission_id } => {
+                let params = resources.missions.get(&mission_id).cloned().unwrap();
+                actor.active_missions.push(Mission::new(params));
+            }
+            DialogueAction::MapTransition {
+                chapter_index,
+                map_id,
+            } => {
+                actor.current_dialogue = None;
+                dispatch_event(Event::ChangeMap {
+                    chapter_index,
+                    map_id,
+                });
+            }
+            DialogueAction::CompleteChapter => {
+                actor.current_dialogue = None;
+                dispatch_event(Event::CompleteChapter);
+            }
+            DialogueAction::SetVariable { variable, value } => {
+                let mut game_state = scene::get_node(actor.game_state);
+                game_state.variables.insert(variable, value);
+            }
+            DialogueAction::ModifyVariable { variable, amount } => {
+                let mut game_state = scene::get_node(actor.game_state);
+                *game_state.variables.entry(variable).or_insert(0) += amount;
+            }
+            DialogueAction::ModifyReputation { faction_id, amount } => {
+                *actor.reputation.entry(faction_id).or_insert(0) += amount;
+            }
+            DialogueAction::JoinFaction { faction_id } => {
+                if !actor.factions.contains(&faction_id) {
+                    actor.factions.push(faction_id);
+                }
+            }
+            DialogueAction::LeaveFaction { faction_id } => {
+                actor.factions.retain(|id| *id != faction_id);
+            }
+            DialogueAction::SetFlag { flag } => {
+                let mut game_state = scene::get_node(actor.game_state);
+                game_state.variables.insert(flag, 1);
+            }
+            DialogueAction::ClearFlag { flag } => {
+                let mut game_state = scene::get_node(actor.game_state);
+                game_state.variables.remove(&flag);
+            }
+            DialogueAction::GiveItem {
+                prototype_id,
+                quantity,
+            } => {
+                let mut loot = Loot::default();
+                loot.add_item(&prototype_id, quantity);
+                let mut rng = LootRng::from_global();
+                for params in loot.instantiate(&resources.items, &resources.affixes, &mut rng) {
+                    if let Some(remainder) = actor.inventory.add_item(params) {
+                        Item::add_node(ItemParams {
+                            position: Some(Inventory::randomize_drop_position(actor.body.position)),
+                            ..remainder
+                        });
+                    }
+                }
+            }
+            DialogueAction::TakeItem {
+                prototype_id,
+                quantity,
+            } => {
+                // This has been validated by `can_take`, before any actions were applied. Equipped
+                // items are only unequipped, and taken, if there are not enough unequipped items.
+                for item_id in actor.inventory.get_equipped_item_ids(&prototype_id) {
+                    if actor.inventory.get_unequipped_quantity(&prototype_id) >= quantity {
+                        break;
+                    }
+                    actor.unequip_item(&item_id);
+                }
+
+                if let Some(removed) = actor.inventory.remove_quantity(&prototype_id, quantity) {
+                    for item_id in removed {
+                        actor.assign_to_hotbar(&item_id, None);
+                    }
+                }
+            }
+            DialogueAction::GiveCredits { amount } => {
+                actor.inventory.add_credits(amount);
+            }
+            DialogueAction::TakeCredits { amount } => {
+                actor.inventory.credits -= amount;
+            }
+            DialogueAction::GrantExperience { amount } => {
+                actor.add_experience(amount);
+            }
+            DialogueAction::StartCombat => {
+                actor.current_dialogue = None;
+                for mut other in scene::find_nodes_by_type::<Actor>() {
+                    if other.id == self.actor_id {
+                        other.behavior.aggression = ActorAggression::Aggressive;
+                        other
+                            .behavior
+                            .attackers
+                            .insert(actor.id.clone(), actor.handle());
+                    }
+                }
+            }
+            DialogueAction::PlaySound { sound_effect_id } => {
+                let sound_effect = resources
+                    .sound_effects
+                    .get(&sound_effect_id)
+                    .cloned()
+                    .unwrap_or_else(|| {
+                        panic!("Unable to find sound effect with id '{}'", &sound_effect_id)
+                    });
+                play_sound(sound_effect, false);
+            }
+            DialogueAction::Jump { dialogue_id } => {
+                actor.current_dialogue = Some(self.get_linked_dialogue(&dialogue_id));
+            }
+        }
+    }
+}
+
+impl Default for Dialogue {
+    fn default() -> Self {
+        Dialogue {
+            id: "".to_string(),
+            actor_id: "".to_string(),
+            actor_name: "".to_string(),
+            vendor_id: None,
+            title: "...".to_string(),
+            body: Vec::new(),
+            response: Vec::new(),
+            options: Vec::new(),
+            requirements: Vec::new(),
+            exclusions: Vec::new(),
+            action: None,
+            actions: Vec::new(),
+            check: None,
+            should_apply: false,
+        }
+    }
+}
+
+// This completes all the objectives of the mission with `mission_id`, in `missions`, and marks it
+// as completed
+fn complete_mission(missions: &mut [Mission], mission_id: &str) {
+    for mission in missions
+        .iter_mut()
+        .filter(|mission| mission.id == mission_id)
+    {
+        for (_, is_completed) in &mut mission.objectives {
+            *is_completed = true;
+        }
+        mission.is_completed = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestState {
+        stats: ActorStats,
+        inventory: Inventory,
+        factions: Vec<String>,
+        reputation: HashMap<String, i32>,
+        variables: HashMap<String, i32>,
+    }
+
+    impl TestState {
+        fn new() -> Self {
+            let mut inventory = Inventory::new();
+            inventory.add_credits(50);
+            inventory.add_item(ItemParams {
+                id: "ammo".to_string(),
+                max_stack: 10,
+                quantity: 3,
+                ..Default::default()
+            });
+
+            let mut reputation = HashMap::new();
+            reputation.insert("guards".to_string(), 10);
+
+            let mut variables = HashMap::new();
+            variables.insert("met_boss".to_string(), 1);
+            variables.insert("thugs_killed".to_string(), 2);
+
+            TestState {
+                stats: ActorStats {
+                    charisma: 12,
+                    ..Default::default()
+                },
+                inventory,
+                factions: vec!["guards".to_string()],
+                reputation,
+                variables,
+            }
+        }
+
+        fn get_context(&self) -> DialogueContext {
+            DialogueContext {
+                level: 3,
+                stats: &self.stats,
+                inventory: &self.inventory,
+                factions: &self.factions,
+                reputation: &self.reputation,
+                active_missions: vec!["find_gun".to_string()],
+                completed_missions: vec!["kill_thugs".to_string()],
+                variables: &self.variables,
+            }
+        }
+    }
+
+    fn parse(json: &str) -> DialogueRequirement {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_requirements() {
+        let state = TestState::new();
+        let context = state.get_context();
+
+        let met = [
+            r#"{ "type": "active_mission", "mission_id": "find_gun" }"#,
+            r#"{ "type": "completed_mission", "mission_id": "kill_thugs" }"#,
+            r#"{ "type": "is_in_faction", "faction_id": "guards" }"#,
+            r#"{ "type": "reputation", "faction_id": "guards", "min": 10 }"#,
+            r#"{ "type": "reputation", "faction_id": "thugs", "max": 0 }"#,
+            r#"{ "type": "level", "min": 2, "max": 3 }"#,
+            r#"{ "type": "stat", "stat": "charisma", "min": 12.0 }"#,
+            r#"{ "type": "has_item", "prototype_id": "ammo", "quantity": 3 }"#,
+            r#"{ "type": "credits", "min": 50 }"#,
+            r#"{ "type": "flag", "flag": "met_boss" }"#,
+            r#"{ "type": "variable", "variable": "thugs_killed", "min": 1, "max": 2 }"#,
+        ];
+        for json in &met {
+            assert!(parse(json).is_met(&context), "{}", json);
+        }
+
+        let not_met = [
+            r#"{ "type": "active_mission", "mission_id": "kill_thugs" }"#,
+            r#"{ "type": "is_in_faction", "faction_id": "thugs" }"#,
+            r#"{ "type": "reputation", "faction_id": "guards", "min": 11 }"#,
+            r#"{ "type": "level", "min": 4 }"#,
+            r#"{ "type": "stat", "stat": "charisma", "max": 11.0 }"#,
+            r#"{ "type": "has_item", "prototype_id": "ammo", "quantity": 4 }"#,
+            r#"{ "type": "has_item", "prototype_id": "pistol" }"#,
+            r#"{ "type": "credits", "min": 51 }"#,
+            r#"{ "type": "flag", "flag": "met_woman" }"#,
+            r#"{ "type": "variable", "variable": "thugs_killed", "min": 3 }"#,
+        ];
+        for json in &not_met {
+            assert!(!parse(json).is_met(&context), "{}", json);
+        }
+    }
+
+    #[test]
+    fn test_combined_requirements() {
+        let state = TestState::new();
+        let context = state.get_context();
+
+        let requirement = parse(
+            r#"{
+                "type": "all",
+                "requirements": [
+                    { "type": "flag", "flag": "met_boss" },
+                    {
+                        "type": "any",
+                        "requirements": [
+                            { "type": "credits", "min": 100 },
+                            { "type": "stat", "stat": "charisma", "min": 12.0 }
+                        ]
+                    },
+                    {
+                        "type": "not",
+                        "requirement": { "type": "active_mission", "mission_id": "kill_thugs" }
+                    }
+                ]
+            }"#,
+        );
+        assert!(requirement.is_met(&context));
+
+        let requirement = parse(
+            r#"{
+                "type": "any",
+                "requirements": [
+                    { "type": "credits", "min": 100 },
+                    { "type": "not", "requirement": { "type": "flag", "flag": "met_boss" } }
+                ]
+            }"#,
+        );
+        assert!(!requirement.is_met(&context));
+
+        // `all` is met, and `any` is not, when there are no requirements
+        assert!(parse(r#"{ "type": "all", "requirements": [] }"#).is_met(&context));
+        assert!(!parse(r#"{ "type": "any", "requirements": [] }"#).is_met(&context));
+    }
+
+    #[test]
+    fn test_is_available() {
+        let state = TestState::new();
+        let context = state.get_context();
+
+        let dialogue: Dialogue = serde_json::from_str(
+            r#"{
+                "id": "deliver_gun",
+                "requirements": [{ "type": "active_mission", "mission_id": "find_gun" }],
+                "exclusions": [{ "type": "completed_mission", "mission_id": "deliver_gun" }]
+            }"#,
+        )
+        .unwrap();
+        assert!(dialogue.is_available(&context));
+
+        let dialogue: Dialogue = serde_json::from_str(
+            r#"{
+                "id": "find_gun",
+                "exclusions": [
+                    { "type": "active_mission", "mission_id": "deliver_gun" },
+                    { "type": "active_mission", "mission_id": "find_gun" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert!(!dialogue.is_available(&context));
+    }
+
+    #[test]
+    fn test_parse_actions() {
+        let dialogue: Dialogue = serde_json::from_str(
+            r#"{
+                "id": "deliver_gun",
+                "action": { "type": "complete_mission", "mission_id": "deliver_gun" },
+                "actions": [
+                    { "type": "take_item", "prototype_id": "gun" },
+                    { "type": "give_credits", "amount": 50 },
+                    { "type": "jump", "dialogue_id": "thanks" }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert!(dialogue.action.is_some());
+        assert_eq!(dialogue.actions.len(), 3);
+        match &dialogue.actions[0] {
+            DialogueAction::TakeItem {
+                prototype_id,
+                quantity,
+            } => {
+                assert_eq!(prototype_id, "gun");
+                assert_eq!(*quantity, 1);
+            }
+            action => panic!("Unexpected action {:?}", action),
+        }
+    }
+
+    #[test]
+    fn test_can_take() {
+        let state = TestState::new();
+        let parse_dialogue = |actions: &str| -> Dialogue {
+            serde_json::from_str(&format!(r#"{{ "id": "trade", "actions": {} }}"#, actions))
+                .unwrap()
+        };
+
+        let dialogue = parse_dialogue(
+            r#"[
+                { "type": "take_item", "prototype_id": "ammo", "quantity": 2 },
+                { "type": "take_credits", "amount": 50 },
+                { "type": "give_item", "prototype_id": "pistol" }
+            ]"#,
+        );
+        assert!(dialogue.can_take(&state.inventory));
+
+        // Takes of the same item are added together, so this fails on the second take
+        let dialogue = parse_dialogue(
+            r#"[
+                { "type": "take_item", "prototype_id": "ammo", "quantity": 2 },
+                { "type": "give_credits", "amount": 100 },
+                { "type": "take_item", "prototype_id": "ammo", "quantity": 2 }
+            ]"#,
+        );
+        assert!(!dialogue.can_take(&state.inventory));
+
+        let dialogue = parse_dialogue(
+            r#"[
+                { "type": "give_item", "prototype_id": "pistol" },
+                { "type": "take_credits", "amount": 51 }
+            ]"#,
+        );
+        assert!(!dialogue.can_take(&state.inventory));
+
+        let dialogue = parse_dialogue(r#"[{ "type": "take_item", "prototype_id": "pistol" }]"#);
+        assert!(!dialogue.can_take(&state.inventory));
+
+        // Equipped items are counted, like by the `has_item` requirement
+        let mut state = TestState::new();
+        state.inventory.add_item(ItemParams {
+            id: "pistol".to_string(),
+            kind: ItemKind::OneHandedWeapon,
+            ..Default::default()
+        });
+        state.inventory.items.last_mut().unwrap().equipped_to = EquipmentSlot::MainHand;
+        let requirement = parse(r#"{ "type": "has_item", "prototype_id": "pistol" }"#);
+        assert!(requirement.is_met(&state.get_context()));
+        assert!(dialogue.can_take(&state.inventory));
+        assert_eq!(
+            state.inventory.get_equipped_item_ids("pistol"),
+            vec!["pistol"]
+        );
+    }
+
+    #[test]
+    fn test_complete_mission() {
+        let new_mission = |id: &str| {
+            Mission::new(MissionParams {
+                id: id.to_string(),
+                objectives: vec![MissionObjective::Kill {
+                    actor_id: "thug".to_string(),
+                }],
+                ..Default::default()
+            })
+        };
+
+        let mut missions = vec![new_mission("find_gun"), new_mission("kill_thugs")];
+        complete_mission(&mut missions, "find_gun");
+
+        assert!(missions[0].is_completed);
+        assert!(missions[0].objectives[0].1);
+        assert!(!missions[1].is_completed);
+        assert!(!missions[1].objectives[0].1);
+    }
+
+    #[test]
+    fn test_check_chance() {
+        let state = TestState::new();
+        let check: DialogueCheck = serde_json::from_str(
+            r#"{
+                "stat": "charisma",
+                "difficulty": 10.0,
+                "success": "persuade_success",
+                "failure": "persuade_failure"
+            }"#,
+        )
+        .unwrap();
+        assert!((check.get_chance(&state.stats) - 60.0).abs() < f32::EPSILON);
+
+        let check = DialogueCheck {
+            difficulty: 30.0,
+            ..check
+        };
+        assert!((check.get_chance(&state.stats) - DialogueCheck::MIN_CHANCE).abs() < f32::EPSILON);
+        assert_eq!(
+            DialogueCheck::get_variable_name("persuade"),
+            "check_persuade"
+        );
+    }
+
+    #[test]
+    fn test_check_outcome_is_recorded() {
+        let check = DialogueCheck {
+            stat: "charisma".to_string(),
+            difficulty: 10.0,
+            success: "persuade_success".to_string(),
+            failure: "persuade_failure".to_string(),
+            show_chance: false,
+        };
+
+        let mut variables = HashMap::new();
+        assert!(check.resolve("persuade", &mut variables, || true));
+        assert_eq!(
+            variables.get("check_persuade"),
+            Some(&DialogueCheck::SUCCESS_VALUE)
+        );
+
+        // A recorded outcome is not re-rolled
+        assert!(check.resolve("persuade", &mut variables, || panic!("Check was re-rolled")));
+
+        variables.insert("check_persuade".to_string(), DialogueCheck::FAILURE_VALUE);
+        assert!(!check.resolve("persuade", &mut variables, || panic!("Check was re-rolled")));
+    }
+}
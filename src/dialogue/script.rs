@@ -0,0 +1,927 @@
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+
+use crate::prelude::*;
+
+// The formats of dialogue scripts that can be imported with `import_dialogue_script`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DialogueScriptFormat {
+    Yarn,
+    Ink,
+}
+
+impl DialogueScriptFormat {
+    // This determines the format from the extension of a script file, `.yarn` or `.ink`
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        match path.as_ref().extension()?.to_str()? {
+            "yarn" => Some(DialogueScriptFormat::Yarn),
+            "ink" => Some(DialogueScriptFormat::Ink),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for DialogueScriptFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "yarn" => Ok(DialogueScriptFormat::Yarn),
+            "ink" => Ok(DialogueScriptFormat::Ink),
+            _ => {
+                let msg = format!("Unknown dialogue script format '{}'", s);
+                Err(Error::new(ErrorKind::Parse, msg))
+            }
+        }
+    }
+}
+
+// The fields of the actions that can be used as commands in scripts, in the order that they are
+// given as arguments
+const ACTION_FIELDS: &[(&str, &[&str])] = &[
+    ("open_trade", &[]),
+    ("start_mission", &["mission_id"]),
+    ("complete_mission", &["mission_id"]),
+    ("map_transition", &["chapter_index", "map_id"]),
+    ("complete_chapter", &[]),
+    ("set_variable", &["variable", "value"]),
+    ("modify_variable", &["variable", "amount"]),
+    ("modify_reputation", &["faction_id", "amount"]),
+    ("join_faction", &["faction_id"]),
+    ("leave_faction", &["faction_id"]),
+    ("set_flag", &["flag"]),
+    ("clear_flag", &["flag"]),
+    ("give_item", &["prototype_id", "quantity"]),
+    ("take_item", &["prototype_id", "quantity"]),
+    ("give_credits", &["amount"]),
+    ("take_credits", &["amount"]),
+    ("grant_experience", &["amount"]),
+    ("start_combat", &[]),
+    ("play_sound", &["sound_effect_id"]),
+];
+
+// The fields of the requirements that can be used in the conditions of choices
+const REQUIREMENT_FIELDS: &[(&str, &[&str])] = &[
+    ("active_mission", &["mission_id"]),
+    ("completed_mission", &["mission_id"]),
+    ("is_in_faction", &["faction_id"]),
+    ("reputation", &["faction_id", "min", "max"]),
+    ("level", &["min", "max"]),
+    ("stat", &["stat", "min", "max"]),
+    ("has_item", &["prototype_id", "quantity"]),
+    ("credits", &["min", "max"]),
+    ("flag", &["flag"]),
+    ("variable", &["variable", "min", "max"]),
+];
+
+const NUMERIC_FIELDS: &[&str] = &["chapter_index", "value", "amount", "quantity", "min", "max"];
+
+// An argument with this value is left out, so that the field will have its default value
+const SKIPPED_ARGUMENT: &str = "_";
+
+// Once-only choices are hidden, after they have been chosen, by a flag with the id of the choice,
+// prefixed by this
+const ONCE_ONLY_FLAG_PREFIX: &str = "chosen_";
+
+fn parse_error(line: usize, msg: &str) -> Error {
+    Error::new(ErrorKind::Parse, format!("Line {}: {}", line, msg))
+}
+
+// This creates an action, or a requirement, from the name of its type and a list of positional
+// arguments
+fn from_arguments<T: DeserializeOwned>(
+    table: &[(&str, &[&str])],
+    name: &str,
+    args: &[String],
+    line: usize,
+) -> Result<T> {
+    let fields = table
+        .iter()
+        .find(|(type_name, _)| *type_name == name)
+        .map(|(_, fields)| *fields)
+        .ok_or_else(|| parse_error(line, &format!("Unknown command '{}'", name)))?;
+
+    if args.len() > fields.len() {
+        let msg = format!("Too many arguments for '{}'", name);
+        return Err(parse_error(line, &msg));
+    }
+
+    let mut map = serde_json::Map::new();
+    map.insert("type".to_string(), serde_json::Value::from(name));
+    for (field, arg) in fields.iter().zip(args) {
+        if arg == SKIPPED_ARGUMENT {
+            continue;
+        }
+
+        let value = if NUMERIC_FIELDS.contains(field) {
+            if let Some(value) = parse_bool(arg) {
+                serde_json::Value::from(value)
+            } else if let Ok(value) = arg.parse::<i64>() {
+                serde_json::Value::from(value)
+            } else if let Ok(value) = arg.parse::<f64>() {
+                serde_json::Value::from(value)
+            } else {
+                let msg = format!("Argument '{}' of '{}' is not a number", arg, name);
+                return Err(parse_error(line, &msg));
+            }
+        } else {
+            serde_json::Value::from(arg.as_str())
+        };
+        map.insert(field.to_string(), value);
+    }
+
+    serde_json::from_value(serde_json::Value::Object(map))
+        .map_err(|err| parse_error(line, &err.to_string()))
+}
+
+// This splits a call, like `has_item(quest_pistol, 2)` or `has_item quest_pistol 2`, into its name
+// and its arguments
+fn parse_call(text: &str) -> (String, Vec<String>) {
+    if let Some(i) = text.find('(') {
+        let args = split_top_level(text[i + 1..].trim_end().trim_end_matches(')'), &[","])
+            .into_iter()
+            .map(|arg| arg.trim().trim_matches('"').to_string())
+            .filter(|arg| !arg.is_empty())
+            .collect();
+        (text[..i].trim().to_string(), args)
+    } else {
+        let mut parts = text.split_whitespace().map(|part| part.to_string());
+        let name = parts.next().unwrap_or_default();
+        (name, parts.collect())
+    }
+}
+
+// Booleans are stored in variables as `1` and `0`, like flags
+fn parse_bool(text: &str) -> Option<i32> {
+    match text {
+        "true" => Some(1),
+        "false" => Some(0),
+        _ => None,
+    }
+}
+
+fn parse_number(text: &str, line: usize) -> Result<i32> {
+    let text = text.trim();
+    if let Some(value) = parse_bool(text) {
+        return Ok(value);
+    }
+
+    text.parse()
+        .map_err(|_| parse_error(line, &format!("'{}' is not a number", text)))
+}
+
+// This splits `expr` at every occurrence of any of `separators`, that is not inside parentheses,
+// or quotes
+fn split_top_level<'a>(expr: &'a str, separators: &[&str]) -> Vec<&'a str> {
+    let mut res = Vec::new();
+    let mut depth = 0;
+    let mut is_quoted = false;
+    let mut start = 0;
+    let mut i = 0;
+    while i < expr.len() {
+        let rest = &expr[i..];
+        if depth == 0 && !is_quoted {
+            if let Some(separator) = separators.iter().find(|sep| rest.starts_with(*sep)) {
+                res.push(&expr[start..i]);
+                i += separator.len();
+                start = i;
+                continue;
+            }
+        }
+
+        let c = rest.chars().next().unwrap();
+        match c {
+            '"' => is_quoted = !is_quoted,
+            '(' if !is_quoted => depth += 1,
+            ')' if !is_quoted => depth -= 1,
+            _ => {}
+        }
+        i += c.len_utf8();
+    }
+    res.push(&expr[start..]);
+    res
+}
+
+// This removes parentheses that enclose the whole of `expr`, like in `(a or b)`
+fn strip_parentheses(expr: &str) -> &str {
+    let mut expr = expr.trim();
+    while expr.starts_with('(') && expr.ends_with(')') {
+        let inner = &expr[1..expr.len() - 1];
+        // The parentheses only enclose the whole expression if the inner expression is balanced,
+        // which isn't the case in `(a) and (b)`
+        let mut depth = 0;
+        for c in inner.chars() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            if depth < 0 {
+                return expr;
+            }
+        }
+        expr = inner.trim();
+    }
+    expr
+}
+
+// Conditions can be combined with `and` and `or`, where `and` binds tighter, grouped with
+// parentheses, and negated with `not`. Variables can be compared to numbers, or booleans, and a
+// variable on its own is treated as a flag.
+fn parse_condition(expr: &str, line: usize) -> Result<DialogueRequirement> {
+    let expr = strip_parentheses(expr);
+    if expr.matches('(').count() != expr.matches(')').count() {
+        return Err(parse_error(line, "Unbalanced parentheses in condition"));
+    }
+
+    let any = split_top_level(expr, &[" or ", "||"]);
+    if any.len() > 1 {
+        let requirements = any
+            .into_iter()
+            .map(|expr| parse_condition(expr, line))
+            .collect::<Result<_>>()?;
+        return Ok(DialogueRequirement::Any { requirements });
+    }
+
+    let all = split_top_level(expr, &[" and ", "&&"]);
+    if all.len() > 1 {
+        let requirements = all
+            .into_iter()
+            .map(|expr| parse_condition(expr, line))
+            .collect::<Result<_>>()?;
+        return Ok(DialogueRequirement::All { requirements });
+    }
+
+    if let Some(expr) = expr.strip_prefix("not ").or_else(|| expr.strip_prefix('!')) {
+        let requirement = Box::new(parse_condition(expr, line)?);
+        return Ok(DialogueRequirement::Not { requirement });
+    }
+
+    for op in &[">=", "<=", "==", "!=", ">", "<"] {
+        let operands = split_top_level(expr, &[op]);
+        if operands.len() > 1 {
+            let i = operands[0].len();
+            let variable = expr[..i].trim().trim_start_matches('$').to_string();
+            let value = parse_number(&expr[i + op.len()..], line)?;
+            let (min, max) = match *op {
+                ">=" => (Some(value), None),
+                "<=" => (None, Some(value)),
+                ">" => (Some(value + 1), None),
+                "<" => (None, Some(value - 1)),
+                _ => (Some(value), Some(value)),
+            };
+
+            let requirement = DialogueRequirement::Variable { variable, min, max };
+            if *op == "!=" {
+                let requirement = Box::new(requirement);
+                return Ok(DialogueRequirement::Not { requirement });
+            }
+            return Ok(requirement);
+        }
+    }
+
+    let (name, args) = parse_call(expr);
+    if args.is_empty() && !expr.contains('(') {
+        let flag = name.trim_start_matches('$').to_string();
+        return Ok(DialogueRequirement::Flag { flag });
+    }
+
+    from_arguments(REQUIREMENT_FIELDS, &name, &args, line)
+}
+
+#[derive(Debug, Clone)]
+enum Statement {
+    Line(String),
+    Action(DialogueAction),
+    Jump(String),
+    Choice {
+        // The text of the option, as it is shown to the player
+        title: String,
+        // The text that is spoken by the player, when the option is chosen
+        body: Option<String>,
+        requirements: Vec<DialogueRequirement>,
+        is_once_only: bool,
+    },
+}
+
+struct ScriptLine {
+    number: usize,
+    // The number of choices that the line is nested in
+    depth: usize,
+    statement: Statement,
+}
+
+struct ScriptNode {
+    id: String,
+    lines: Vec<ScriptLine>,
+}
+
+fn parse_yarn_option(text: &str, line: usize) -> Result<Statement> {
+    let mut requirements = Vec::new();
+    let text = match text.find("<<if ") {
+        Some(i) => {
+            let condition = text[i + 5..]
+                .trim_end()
+                .strip_suffix(">>")
+                .ok_or_else(|| parse_error(line, "Unterminated condition"))?;
+            requirements.push(parse_condition(condition, line)?);
+            text[..i].trim()
+        }
+        None => text,
+    };
+
+    Ok(Statement::Choice {
+        title: text.to_string(),
+        body: Some(text.to_string()),
+        requirements,
+        is_once_only: false,
+    })
+}
+
+fn parse_yarn_command(text: &str, line: usize) -> Result<Statement> {
+    let text = text
+        .trim_start_matches("<<")
+        .strip_suffix(">>")
+        .ok_or_else(|| parse_error(line, "Unterminated command"))?;
+    let (name, args) = parse_call(text);
+    match name.as_str() {
+        "jump" => match args.first() {
+            Some(target) => Ok(Statement::Jump(target.clone())),
+            None => Err(parse_error(line, "Missing node for 'jump'")),
+        },
+        "set" => {
+            if args.len() != 3 || (args[1] != "to" && args[1] != "=") {
+                return Err(parse_error(line, "Expected 'set $variable to <value>'"));
+            }
+            let variable = args[0].trim_start_matches('$').to_string();
+            let value = parse_number(&args[2], line)?;
+            Ok(Statement::Action(DialogueAction::SetVariable {
+                variable,
+                value,
+            }))
+        }
+        _ => from_arguments(ACTION_FIELDS, &name, &args, line).map(Statement::Action),
+    }
+}
+
+// Yarn nodes have a header, with a `title`, followed by `---`, and a body that ends with `===`.
+// Options start with `->` and the lines that are indented below an option belong to it.
+fn parse_yarn(script: &str) -> Result<Vec<ScriptNode>> {
+    let mut nodes = Vec::new();
+    let mut title = None;
+    let mut current: Option<ScriptNode> = None;
+    let mut indents: Vec<usize> = Vec::new();
+    let mut line_cnt = 0;
+
+    for (i, raw) in script.lines().enumerate() {
+        let number = i + 1;
+        line_cnt = number;
+        let trimmed = raw.trim();
+
+        let node = match current.as_mut() {
+            Some(node) => node,
+            None => {
+                if trimmed == "---" {
+                    let id = title
+                        .take()
+                        .ok_or_else(|| parse_error(number, "Node has no title"))?;
+                    current = Some(ScriptNode {
+                        id,
+                        lines: Vec::new(),
+                    });
+                    indents.clear();
+                } else if let Some(value) = trimmed.strip_prefix("title:") {
+                    title = Some(value.trim().to_string());
+                }
+                // Other headers are ignored
+                continue;
+            }
+        };
+
+        if trimmed == "===" {
+            nodes.push(current.take().unwrap());
+            continue;
+        }
+
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+
+        let indent = raw.len() - raw.trim_start().len();
+        while indents.last().map(|last| *last >= indent).unwrap_or(false) {
+            indents.pop();
+        }
+
+        let depth = indents.len();
+        let statement = if let Some(option) = trimmed.strip_prefix("->") {
+            indents.push(indent);
+            parse_yarn_option(option.trim(), number)?
+        } else if trimmed.starts_with("<<") {
+            parse_yarn_command(trimmed, number)?
+        } else {
+            Statement::Line(trimmed.to_string())
+        };
+
+        node.lines.push(ScriptLine {
+            number,
+            depth,
+            statement,
+        });
+    }
+
+    if current.is_some() {
+        return Err(parse_error(line_cnt, "Node is not terminated by '==='"));
+    }
+
+    Ok(nodes)
+}
+
+// This splits a trailing divert, like `-> knot`, from a line. Diverts to `END` or `DONE` just end
+// the dialogue.
+fn split_ink_divert(text: &str) -> (&str, Option<String>) {
+    match text.find("->") {
+        Some(i) => {
+            let target = text[i + 2..].trim();
+            let target = if target == "END" || target == "DONE" {
+                None
+            } else {
+                Some(target.to_string())
+            };
+            (text[..i].trim(), target)
+        }
+        None => (text, None),
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn parse_ink_choice(
+    text: &str,
+    is_once_only: bool,
+    line: usize,
+) -> Result<(Statement, Option<String>)> {
+    let mut requirements = Vec::new();
+    let mut text = text.trim();
+    while let Some(rest) = text.strip_prefix('{') {
+        let i = rest
+            .find('}')
+            .ok_or_else(|| parse_error(line, "Unterminated condition"))?;
+        requirements.push(parse_condition(&rest[..i], line)?);
+        text = rest[i + 1..].trim();
+    }
+
+    // Text in brackets is only shown in the choice, while text after the brackets is only spoken
+    // when the choice is chosen, like in `Hello [there] friend`
+    let (text, divert) = split_ink_divert(text);
+    let (title, body) = match text.find('[') {
+        Some(start) => {
+            let end = text[start..]
+                .find(']')
+                .map(|i| start + i)
+                .ok_or_else(|| parse_error(line, "Unterminated choice text"))?;
+            let title = format!("{}{}", &text[..start], &text[start + 1..end]);
+            let body = format!("{}{}", &text[..start], &text[end + 1..]);
+            (title, body)
+        }
+        None => (text.to_string(), text.to_string()),
+    };
+
+    let body = collapse_whitespace(&body);
+    let choice = Statement::Choice {
+        title: collapse_whitespace(&title),
+        body: if body.is_empty() { None } else { Some(body) },
+        requirements,
+        is_once_only,
+    };
+    Ok((choice, divert))
+}
+
+fn parse_ink_command(text: &str, line: usize) -> Result<Statement> {
+    if text.contains('(') {
+        let (name, args) = parse_call(text);
+        return from_arguments(ACTION_FIELDS, &name, &args, line).map(Statement::Action);
+    }
+
+    for op in &["+=", "-=", "="] {
+        if let Some(i) = text.find(op) {
+            let variable = text[..i].trim().to_string();
+            let value = parse_number(&text[i + op.len()..], line)?;
+            let action = match *op {
+                "+=" => DialogueAction::ModifyVariable {
+                    variable,
+                    amount: value,
+                },
+                "-=" => DialogueAction::ModifyVariable {
+                    variable,
+                    amount: -value,
+                },
+                _ => DialogueAction::SetVariable { variable, value },
+            };
+            return Ok(Statement::Action(action));
+        }
+    }
+
+    Err(parse_error(
+        line,
+        &format!("Unsupported statement '{}'", text),
+    ))
+}
+
+// Ink knots start with `=== knot ===`, choices start with `*`, or `+`, where the number of these
+// determines how deep the choice is nested, and commands and assignments start with `~`. Choices
+// that start with `*` can only be chosen once, while choices that start with `+` are sticky.
+fn parse_ink(script: &str) -> Result<Vec<ScriptNode>> {
+    let mut nodes = Vec::new();
+    let mut current: Option<ScriptNode> = None;
+    let mut depth = 0;
+
+    for (i, raw) in script.lines().enumerate() {
+        let number = i + 1;
+        let trimmed = raw.trim();
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+
+        if trimmed.starts_with("==") {
+            let id = trimmed.trim_matches('=').trim();
+            if id.is_empty() {
+                return Err(parse_error(number, "Knot has no name"));
+            }
+            if let Some(node) = current.take() {
+                nodes.push(node);
+            }
+            current = Some(ScriptNode {
+                id: id.to_string(),
+                lines: Vec::new(),
+            });
+            depth = 0;
+            continue;
+        }
+
+        let node = current
+            .as_mut()
+            .ok_or_else(|| parse_error(number, "Content outside of a knot"))?;
+
+        if trimmed.starts_with('*') || trimmed.starts_with('+') {
+            let is_marker = |c: char| matches!(c, '*' | '+');
+            let level = trimmed
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .take_while(|c| is_marker(*c))
+                .count();
+            let rest = trimmed.trim_start_matches(|c: char| is_marker(c) || c.is_whitespace());
+            let is_once_only = trimmed.starts_with('*');
+            let (choice, divert) = parse_ink_choice(rest, is_once_only, number)?;
+            node.lines.push(ScriptLine {
+                number,
+                depth: level - 1,
+                statement: choice,
+            });
+            depth = level;
+            if let Some(target) = divert {
+                node.lines.push(ScriptLine {
+                    number,
+                    depth,
+                    statement: Statement::Jump(target),
+                });
+            }
+        } else if let Some(command) = trimmed.strip_prefix('~') {
+            node.lines.push(ScriptLine {
+                number,
+                depth,
+                statement: parse_ink_command(command.trim(), number)?,
+            });
+        } else if trimmed.starts_with('-') && !trimmed.starts_with("->") {
+            return Err(parse_error(number, "Gathers are not supported"));
+        } else {
+            let (text, divert) = split_ink_divert(trimmed);
+            if !text.is_empty() {
+                node.lines.push(ScriptLine {
+                    number,
+                    depth,
+                    statement: Statement::Line(text.to_string()),
+                });
+            }
+            if let Some(target) = divert {
+                node.lines.push(ScriptLine {
+                    number,
+                    depth,
+                    statement: Statement::Jump(target),
+                });
+            }
+        }
+    }
+
+    if let Some(node) = current {
+        nodes.push(node);
+    }
+
+    Ok(nodes)
+}
+
+#[derive(Default)]
+struct ScriptBlock {
+    lines: Vec<String>,
+    actions: Vec<DialogueAction>,
+    jump: Option<String>,
+    choices: Vec<ScriptChoice>,
+}
+
+struct ScriptChoice {
+    title: String,
+    body: Option<String>,
+    requirements: Vec<DialogueRequirement>,
+    is_once_only: bool,
+    block: ScriptBlock,
+}
+
+impl ScriptBlock {
+    // Nothing can follow a jump and only other choices can follow a choice
+    fn check_can_add(&self, line: &ScriptLine) -> Result<()> {
+        let is_choice = matches!(line.statement, Statement::Choice { .. });
+        if self.jump.is_some() || (!is_choice && !self.choices.is_empty()) {
+            let msg = "Content after choices, or after a jump, is not supported";
+            return Err(parse_error(line.number, msg));
+        }
+        Ok(())
+    }
+}
+
+fn build_block(lines: &[ScriptLine], pos: &mut usize, depth: usize) -> Result<ScriptBlock> {
+    let mut block = ScriptBlock::default();
+    while let Some(line) = lines.get(*pos) {
+        if line.depth < depth {
+            break;
+        }
+
+        block.check_can_add(line)?;
+        *pos += 1;
+        match line.statement.clone() {
+            Statement::Line(text) => block.lines.push(text),
+            Statement::Action(action) => block.actions.push(action),
+            Statement::Jump(target) => block.jump = Some(target),
+            Statement::Choice {
+                title,
+                body,
+                requirements,
+                is_once_only,
+            } => {
+                let choice_block = build_block(lines, pos, depth + 1)?;
+                block.choices.push(ScriptChoice {
+                    title,
+                    body,
+                    requirements,
+                    is_once_only,
+                    block: choice_block,
+                });
+            }
+        }
+    }
+
+    Ok(block)
+}
+
+// A jump from a block without any lines moves the dialogue directly to the target node. Otherwise
+// the target node is added as an option, so that the lines are shown before moving on.
+fn add_dialogue(res: &mut Vec<Dialogue>, mut dialogue: Dialogue, block: ScriptBlock) {
+    dialogue.response = block.lines;
+    dialogue.actions = block.actions;
+    if let Some(dialogue_id) = block.jump {
+        if dialogue.response.is_empty() {
+            dialogue.actions.push(DialogueAction::Jump { dialogue_id });
+        } else {
+            dialogue.options.push(dialogue_id);
+        }
+    }
+
+    let id = dialogue.id.clone();
+    let choices: Vec<(String, ScriptChoice)> = block
+        .choices
+        .into_iter()
+        .enumerate()
+        .map(|(i, choice)| (format!("{}_{}", id, i + 1), choice))
+        .collect();
+
+    dialogue
+        .options
+        .extend(choices.iter().map(|(choice_id, _)| choice_id.clone()));
+    res.push(dialogue);
+
+    for (choice_id, mut choice) in choices {
+        // A once-only choice sets a flag when it is chosen, which is used to exclude it after that
+        let mut exclusions = Vec::new();
+        if choice.is_once_only {
+            let flag = format!("{}{}", ONCE_ONLY_FLAG_PREFIX, choice_id);
+            exclusions.push(DialogueRequirement::Flag { flag: flag.clone() });
+            choice
+                .block
+                .actions
+                .insert(0, DialogueAction::SetFlag { flag });
+        }
+
+        let dialogue = Dialogue {
+            id: choice_id,
+            title: choice.title,
+            body: choice.body.into_iter().collect(),
+            requirements: choice.requirements,
+            exclusions,
+            ..Default::default()
+        };
+        add_dialogue(res, dialogue, choice.block);
+    }
+}
+
+// This imports a dialogue script, written in a subset of the Yarn, or Ink, syntax, and returns a
+// `Dialogue` for every node in the script, with the title of the node as its id, as well as for
+// every choice. The ids of choices are the id of the node, or choice, that they belong to, followed
+// by the number of the choice, like `start_2_1`.
+pub fn import_dialogue_script(script: &str, format: DialogueScriptFormat) -> Result<Vec<Dialogue>> {
+    let nodes = match format {
+        DialogueScriptFormat::Yarn => parse_yarn(script)?,
+        DialogueScriptFormat::Ink => parse_ink(script)?,
+    };
+
+    let ids: Vec<&str> = nodes.iter().map(|node| node.id.as_str()).collect();
+    for (i, node) in nodes.iter().enumerate() {
+        if ids[..i].contains(&node.id.as_str()) {
+            let msg = format!("Node '{}' is defined more than once", node.id);
+            return Err(Error::new(ErrorKind::Parse, msg));
+        }
+
+        for line in &node.lines {
+            if let Statement::Jump(target) = &line.statement {
+                if !ids.contains(&target.as_str()) {
+                    let msg = format!("Jump to unknown node '{}'", target);
+                    return Err(parse_error(line.number, &msg));
+                }
+            }
+        }
+    }
+
+    let mut res = Vec::new();
+    for node in nodes {
+        let block = build_block(&node.lines, &mut 0, 0)?;
+        let dialogue = Dialogue {
+            id: node.id,
+            ..Default::default()
+        };
+        add_dialogue(&mut res, dialogue, block);
+    }
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_dialogue<'a>(dialogue: &'a [Dialogue], id: &str) -> &'a Dialogue {
+        dialogue.iter().find(|dialogue| dialogue.id == id).unwrap()
+    }
+
+    #[test]
+    fn test_import_yarn() {
+        let script = r#"
+title: guard
+---
+Hello, what do you want...
+-> Do you have any work for me? <<if not $met_boss and has_item quest_pistol>>
+    Yeah, I've lost my gun.
+    <<start_mission mission_find_gun>>
+    <<set $met_boss to 1>>
+    -> Sure!
+        <<give_credits 10>>
+-> Goodbye
+    <<jump goodbye>>
+===
+title: goodbye
+---
+Bye!
+===
+"#;
+        let dialogue = import_dialogue_script(script, DialogueScriptFormat::Yarn).unwrap();
+        assert_eq!(dialogue.len(), 5);
+
+        let root = get_dialogue(&dialogue, "guard");
+        assert_eq!(root.response, vec!["Hello, what do you want..."]);
+        assert_eq!(root.options, vec!["guard_1", "guard_2"]);
+
+        let work = get_dialogue(&dialogue, "guard_1");
+        assert_eq!(work.title, "Do you have any work for me?");
+        assert_eq!(work.options, vec!["guard_1_1"]);
+        assert_eq!(work.actions.len(), 2);
+        assert!(matches!(
+            &work.requirements[..],
+            [DialogueRequirement::All { requirements }] if requirements.len() == 2
+        ));
+
+        let goodbye = get_dialogue(&dialogue, "guard_2");
+        assert!(matches!(
+            &goodbye.actions[..],
+            [DialogueAction::Jump { dialogue_id }] if dialogue_id == "goodbye"
+        ));
+    }
+
+    #[test]
+    fn test_import_ink() {
+        let script = r#"
+=== scared_woman ===
+Please help me!
+* {thugs_killed >= 3} [I have killed the thugs]
+    Thank you!
+    ~ give_item(medkit, 2)
+    ~ thugs_reward += 1
+    -> END
++ [Not now] -> scared_woman
++ Hello [there]   friend
+    Hello.
+"#;
+        let dialogue = import_dialogue_script(script, DialogueScriptFormat::Ink).unwrap();
+        assert_eq!(dialogue.len(), 4);
+
+        let killed = get_dialogue(&dialogue, "scared_woman_1");
+        assert_eq!(killed.title, "I have killed the thugs");
+        assert!(killed.body.is_empty());
+        assert_eq!(killed.response, vec!["Thank you!"]);
+        assert!(killed.options.is_empty());
+        assert!(matches!(
+            &killed.requirements[..],
+            [DialogueRequirement::Variable {
+                min: Some(3),
+                max: None,
+                ..
+            }]
+        ));
+        assert!(matches!(
+            &killed.actions[..],
+            [
+                DialogueAction::SetFlag { flag },
+                DialogueAction::GiveItem { quantity: 2, .. },
+                DialogueAction::ModifyVariable { amount: 1, .. }
+            ] if flag == "chosen_scared_woman_1"
+        ));
+        assert!(matches!(
+            &killed.exclusions[..],
+            [DialogueRequirement::Flag { flag }] if flag == "chosen_scared_woman_1"
+        ));
+
+        let not_now = get_dialogue(&dialogue, "scared_woman_2");
+        assert_eq!(not_now.title, "Not now");
+        assert!(not_now.body.is_empty());
+        assert!(not_now.exclusions.is_empty());
+
+        // Text in brackets is only shown in the choice, and text after them is only spoken
+        let hello = get_dialogue(&dialogue, "scared_woman_3");
+        assert_eq!(hello.title, "Hello there");
+        assert_eq!(hello.body, vec!["Hello friend"]);
+    }
+
+    #[test]
+    fn test_parse_condition() {
+        let requirement = parse_condition("$met_boss == true", 1).unwrap();
+        assert!(matches!(
+            requirement,
+            DialogueRequirement::Variable {
+                min: Some(1),
+                max: Some(1),
+                ..
+            }
+        ));
+
+        let requirement =
+            parse_condition("(met_boss or has_item(quest_pistol)) and not (a && b)", 1).unwrap();
+        match requirement {
+            DialogueRequirement::All { requirements } => {
+                assert!(matches!(
+                    &requirements[..],
+                    [
+                        DialogueRequirement::Any { requirements: any },
+                        DialogueRequirement::Not { .. }
+                    ] if any.len() == 2
+                ));
+            }
+            _ => panic!("Expected an `All` requirement"),
+        }
+
+        let requirement = parse_condition(r#"has_item("x and y")"#, 1).unwrap();
+        assert!(!matches!(requirement, DialogueRequirement::All { .. }));
+
+        assert!(parse_condition("(met_boss or a", 1).is_err());
+        assert_eq!(parse_number("false", 1).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_import_errors() {
+        let script = "title: start\n---\n<<jump nowhere>>\n===";
+        assert!(import_dialogue_script(script, DialogueScriptFormat::Yarn).is_err());
+
+        let script = "=== start ===\n~ give_credits(lots)";
+        assert!(import_dialogue_script(script, DialogueScriptFormat::Ink).is_err());
+
+        let script = "=== start ===\n* [Goodbye";
+        assert!(import_dialogue_script(script, DialogueScriptFormat::Ink).is_err());
+    }
+}
@@ -12,8 +12,23 @@ pub enum Event {
         chapter_index: usize,
         map_id: String,
     },
+    // This marks the current chapter as completed and moves the player to the next chapter, or
+    // to the main menu, if it was the last one
+    CompleteChapter,
     Save,
     Respawn,
+    // This is dispatched for every level an actor gains. It is not handled internally.
+    LevelUp {
+        actor_id: String,
+        level: u32,
+    },
+    // This is dispatched every time an actor takes damage, or evades it, and can be used for
+    // floating combat text or combat logs. It is not handled internally.
+    DamageTaken {
+        actor_id: String,
+        source_id: String,
+        result: DamageResult,
+    },
     Quit,
 }
 
@@ -27,8 +42,18 @@ impl Event {
                 chapter_index: _,
                 map_id: _,
             } => "change map",
+            CompleteChapter => "complete chapter",
             Save => "save",
             Respawn => "respawn",
+            LevelUp {
+                actor_id: _,
+                level: _,
+            } => "level up",
+            DamageTaken {
+                actor_id: _,
+                source_id: _,
+                result: _,
+            } => "damage taken",
             Quit => "quit",
         }
     }
@@ -86,6 +111,46 @@ pub async fn handle_event(event: Event) -> Result<Event> {
 
             load_scene(character)?;
         }
+        Event::CompleteChapter => {
+            let (character, chapter_index) = {
+                let mut game_state = scene::find_node_by_type::<GameState>().unwrap();
+                game_state.complete_chapter();
+                let character = game_state.get_player_character().expect(
+                    "No player character found. Use `Event::StartGame` to start a new game!",
+                );
+                (character, game_state.chapter_index)
+            };
+
+            let (outro, next_map_id) = {
+                let resources = storage::get::<Resources>();
+                let chapter = resources.chapters.get(chapter_index).unwrap();
+                let outro = chapter
+                    .outro
+                    .as_ref()
+                    .map(|outro| (chapter.title.clone(), outro.clone()));
+                let next_map_id = resources
+                    .chapters
+                    .get(chapter_index + 1)
+                    .map(|chapter| chapter.initial_map_id.clone());
+                (outro, next_map_id)
+            };
+
+            scene::clear();
+
+            if let Some((title, outro)) = outro {
+                gui::show_chapter_screen(&title, &outro).await;
+            }
+
+            if let Some(map_id) = next_map_id {
+                let character = character.with_map(chapter_index + 1, &map_id);
+                character.save()?;
+                gui::show_chapter_intro(chapter_index + 1).await;
+                load_scene(character)?;
+            } else {
+                character.save()?;
+                gui::show_main_menu().await?;
+            }
+        }
         Event::Respawn => {
             let game_state = scene::find_node_by_type::<GameState>().unwrap();
             let path = character_name_to_path(&game_state.character_name);
@@ -97,6 +162,15 @@ pub async fn handle_event(event: Event) -> Result<Event> {
             let character = game_state.get_player_character().unwrap();
             character.save()?;
         }
+        Event::LevelUp {
+            actor_id: _,
+            level: _,
+        } => {}
+        Event::DamageTaken {
+            actor_id: _,
+            source_id: _,
+            result: _,
+        } => {}
         Event::Quit => {
             scene::clear();
         }
@@ -48,7 +48,7 @@ async fn load_resources(game_params: &GameParams) {
     while !coroutine.is_done() {
         clear_background(color::BLACK);
         draw_text(
-            "Loading game resources...",
+            &tr("Loading game resources..."),
             vec2(get_screen_width() / 2.0, get_screen_height() / 2.0),
             HorizontalAlignment::Center,
             VerticalAlignment::Center,
@@ -78,6 +78,10 @@ pub async fn init(params: GameParams) -> Result<()> {
     fs::create_dir_all(&params.characters_path)?;
     storage::store(params.clone());
 
+    // Localization is loaded before the resources, so that the loading screen can be translated
+    let localization = Localization::new(&params).await?;
+    storage::store(localization);
+
     load_resources(&params).await;
 
     let gui_theme = GuiTheme::load().await?;
@@ -0,0 +1,67 @@
+use crate::gui::*;
+
+// This shows a screen with the title of a chapter and a text, like the description of the chapter
+// or its outro, until the player continues
+pub async fn show_chapter_screen(title: &str, text: &str) {
+    const WINDOW_WIDTH: f32 = 500.0;
+    const WINDOW_HEIGHT: f32 = 350.0;
+
+    let gui_skins = storage::get::<GuiSkins>();
+    root_ui().push_skin(&gui_skins.default);
+
+    let size = vec2(WINDOW_WIDTH, WINDOW_HEIGHT);
+
+    let title = tr(title);
+    let text = tr(text);
+    let continue_label = tr("Continue");
+
+    let btn_size = vec2(
+        WINDOW_WIDTH - GuiSkins::WINDOW_MARGIN_X * 2.0,
+        GuiSkins::BUTTON_HEIGHT,
+    );
+    let btn_position_y = WINDOW_HEIGHT - GuiSkins::WINDOW_MARGIN_Y * 2.0 - GuiSkins::BUTTON_HEIGHT;
+
+    loop {
+        let mut should_continue = false;
+
+        WindowBuilder::new(hash!(), size)
+            .with_centered_pos(true)
+            .build(&mut *root_ui(), |ui| {
+                ui.push_skin(&gui_skins.header_label);
+                ui.label(None, &title);
+                ui.pop_skin();
+
+                for line in text.lines() {
+                    ui.label(None, line);
+                }
+
+                should_continue = widgets::Button::new(continue_label.as_str())
+                    .size(btn_size)
+                    .position(vec2(0.0, btn_position_y))
+                    .ui(ui);
+            });
+
+        if should_continue {
+            root_ui().pop_skin();
+            return;
+        }
+
+        end_frame().await;
+    }
+}
+
+// This shows the title and description of a chapter, if it has an intro
+pub async fn show_chapter_intro(chapter_index: usize) {
+    let intro = {
+        let resources = storage::get::<Resources>();
+        resources
+            .chapters
+            .get(chapter_index)
+            .filter(|chapter| chapter.has_intro)
+            .map(|chapter| (chapter.title.clone(), chapter.description.clone()))
+    };
+
+    if let Some((title, description)) = intro {
+        show_chapter_screen(&title, &description).await;
+    }
+}
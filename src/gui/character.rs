@@ -1,38 +1,270 @@
+use crate::gui::inventory::get_item_name;
 use crate::gui::*;
+use crate::stats::ATTRIBUTES;
+
+// This draws an attribute, along with buttons to spend build points on it, or to take them back,
+// as long as the value is above `min_value`. Returns `true` if the value was changed.
+pub(crate) fn draw_character_attribute(
+    ui: &mut Ui,
+    i: usize,
+    name: &str,
+    value: &mut u32,
+    min_value: u32,
+    build_points: &mut u32,
+) -> bool {
+    let mut is_changed = false;
+
+    let gui_skins = storage::get::<GuiSkins>();
+
+    let y_offset = i as f32 * 22.0;
+
+    ui.label(
+        vec2(2.0, y_offset - 2.0),
+        &format!("{}: {}", tr(name), value),
+    );
+
+    if *value > min_value {
+        ui.push_skin(&gui_skins.condensed_button);
+        if ui.button(vec2(58.0, y_offset), "-") {
+            *value -= 1;
+            *build_points += 1;
+            is_changed = true;
+        }
+        ui.pop_skin();
+    } else {
+        ui.push_skin(&gui_skins.condensed_button_inactive);
+        ui.button(vec2(58.0, y_offset), "-");
+        ui.pop_skin();
+    }
+
+    if *build_points > 0 {
+        ui.push_skin(&gui_skins.condensed_button);
+        if ui.button(vec2(74.0, y_offset), "+") {
+            *value += 1;
+            *build_points -= 1;
+            is_changed = true;
+        }
+        ui.pop_skin();
+    } else {
+        ui.push_skin(&gui_skins.condensed_button_inactive);
+        ui.button(vec2(74.0, y_offset), "+");
+        ui.pop_skin();
+    }
+
+    is_changed
+}
+
+// This draws the modified value of an attribute next to its buttons, if it differs from the base
+fn draw_modified_attribute(ui: &mut Ui, i: usize, value: u32, modified_value: f32) {
+    if modified_value.round() as u32 != value {
+        let y_offset = i as f32 * 22.0;
+        ui.label(
+            vec2(94.0, y_offset - 2.0),
+            &format!("({})", modified_value.round()),
+        );
+    }
+}
 
 pub fn draw_character_window() {
     if let Some(game_state) = scene::find_node_by_type::<GameState>() {
         if game_state.gui_state.should_draw_character_window {
-            if let Some(player) = get_player_actor() {
+            if let Some(mut player) = get_player_actor() {
                 let gui_skins = storage::get::<GuiSkins>();
+                let resources = storage::get::<Resources>();
 
-                let size = vec2(300.0, 300.0);
+                let size = vec2(300.0, 400.0);
                 let position = vec2(50.0, 150.0);
 
                 root_ui().push_skin(&gui_skins.default);
 
+                let player = &mut *player;
+                let next_level_threshold = player
+                    .class_id
+                    .as_ref()
+                    .and_then(|class_id| resources.character_classes.get(class_id))
+                    .and_then(|class| class.get_next_level_threshold(player.level));
+
                 WindowBuilder::new(hash!(), size)
                     .with_pos(position, false)
                     .build(&mut *root_ui(), |ui| {
-                        ui.label(None, &format!("STR: {}", player.stats.strength));
-                        ui.label(None, &format!("DEX: {}", player.stats.dexterity));
-                        ui.label(None, &format!("CON: {}", player.stats.constitution));
-                        ui.label(None, &format!("INT: {}", player.stats.intelligence));
-                        ui.label(None, &format!("WIL: {}", player.stats.willpower));
-                        ui.label(None, &format!("PER: {}", player.stats.perception));
-                        ui.label(None, &format!("CHA: {}", player.stats.charisma));
+                        ui.label(
+                            None,
+                            &tr_args("Level: {level}", &[("level", &player.level)]),
+                        );
+                        if let Some(threshold) = next_level_threshold {
+                            ui.label(
+                                None,
+                                &tr_args(
+                                    "XP: {experience}/{threshold}",
+                                    &[
+                                        ("experience", &player.experience),
+                                        ("threshold", &threshold),
+                                    ],
+                                ),
+                            );
+                        } else {
+                            ui.label(
+                                None,
+                                &tr_args("XP: {experience}", &[("experience", &player.experience)]),
+                            );
+                        }
 
                         ui.separator();
 
-                        ui.tree_node(hash!(), "Regeneration", |ui| {
-                            ui.label(None, &format!("Health:  {}", player.stats.health_regen));
-                            ui.label(None, &format!("Stamina: {}", player.stats.stamina_regen));
-                            ui.label(None, &format!("Energy:  {}", player.stats.energy_regen));
+                        if player.attribute_points > 0 {
+                            ui.label(
+                                None,
+                                &tr_args(
+                                    "Attribute points: {points}",
+                                    &[("points", &player.attribute_points)],
+                                ),
+                            );
+                        }
+
+                        // Points are spent immediately, so they can not be taken back
+                        let custom_attributes = &resources.stat_formulas.attributes;
+                        let group_height =
+                            (ATTRIBUTES.len() + custom_attributes.len()) as f32 * 22.0;
+                        let stats = &mut player.stats;
+                        let attribute_points = &mut player.attribute_points;
+                        let mut is_changed = false;
+                        widgets::Group::new(hash!(), vec2(140.0, group_height)).ui(ui, |ui| {
+                            let names = ["STR", "DEX", "CON", "INT", "WIL", "PER", "CHA"];
+                            let attributes = ATTRIBUTES
+                                .iter()
+                                .zip(names.iter())
+                                .map(|(id, name)| (id.to_string(), name.to_string(), 0))
+                                .chain(custom_attributes.iter().map(|attribute| {
+                                    let id = attribute.id.clone();
+                                    (id.clone(), id, attribute.default_value)
+                                }))
+                                .collect::<Vec<_>>();
+
+                            for (i, (id, name, default_value)) in attributes.iter().enumerate() {
+                                let min_value = stats
+                                    .get_base_stat(id)
+                                    .map(|value| value as u32)
+                                    .unwrap_or(*default_value);
+                                let mut value = min_value;
+                                if draw_character_attribute(
+                                    ui,
+                                    i,
+                                    name,
+                                    &mut value,
+                                    min_value,
+                                    attribute_points,
+                                ) {
+                                    stats.set_attribute(id, value);
+                                    is_changed = true;
+                                }
+                                let modified_value = stats.modifiers.apply(id, min_value as f32);
+                                draw_modified_attribute(ui, i, min_value, modified_value);
+                            }
                         });
 
+                        // Derived stats depend on the attributes, so they are recalculated once,
+                        // after any points have been spent
+                        if is_changed {
+                            player.stats.recalculate_derived();
+                        }
+
                         ui.separator();
 
-                        ui.label(None, &format!("XP: {}", player.experience));
+                        ui.tree_node(hash!(), &tr("Regeneration"), |ui| {
+                            let regeneration = [
+                                ("Health: {value}", player.stats.health_regen),
+                                ("Stamina: {value}", player.stats.stamina_regen),
+                                ("Energy: {value}", player.stats.energy_regen),
+                            ];
+                            for (label, value) in &regeneration {
+                                ui.label(None, &tr_args(label, &[("value", value)]));
+                            }
+                        });
+
+                        if !player.stats.derived.is_empty() {
+                            let mut derived: Vec<(&String, &f32)> =
+                                player.stats.derived.iter().collect();
+                            derived.sort_by(|a, b| a.0.cmp(b.0));
+
+                            ui.tree_node(hash!(), &tr("Other"), |ui| {
+                                for (id, value) in derived {
+                                    ui.label(None, &format!("{}: {}", tr(id), value));
+                                }
+                            });
+                        }
+
+                        if !player.equipped_items.slots.is_empty() {
+                            ui.tree_node(hash!(), &tr("Equipment"), |ui| {
+                                for slot in &resources.equipment_slots {
+                                    let item = player.equipped_items.slots.get(&slot.id).and_then(
+                                        |item_id| {
+                                            player
+                                                .inventory
+                                                .items
+                                                .iter()
+                                                .find(|entry| entry.params.id == *item_id)
+                                        },
+                                    );
+                                    if let Some(entry) = item {
+                                        ui.label(
+                                            None,
+                                            &format!(
+                                                "{}: {}",
+                                                tr(&slot.name),
+                                                get_item_name(&entry.params)
+                                            ),
+                                        );
+                                    }
+                                }
+                            });
+                        }
+
+                        if !player.status_effects.is_empty() {
+                            ui.tree_node(hash!(), &tr("Status effects"), |ui| {
+                                for status_effect in &player.status_effects {
+                                    let mut label = format!(
+                                        "{} ({}s)",
+                                        status_effect.params.id,
+                                        status_effect.time_remaining.ceil()
+                                    );
+                                    if status_effect.stacks > 1 {
+                                        label = format!("{} x{}", label, status_effect.stacks);
+                                    }
+                                    ui.label(None, &label);
+                                }
+                            });
+                        }
+
+                        if !player.unlocked_abilities.is_empty() {
+                            ui.separator();
+
+                            let selected_id = player
+                                .selected_ability
+                                .as_ref()
+                                .map(|ability| ability.id.clone());
+
+                            let mut ability_to_select = None;
+                            ui.tree_node(hash!(), &tr("Abilities"), |ui| {
+                                for ability_id in &player.unlocked_abilities {
+                                    let ability_name = tr(ability_id);
+                                    if Some(ability_id) == selected_id.as_ref() {
+                                        ui.label(
+                                            None,
+                                            &tr_args(
+                                                "{ability} (selected)",
+                                                &[("ability", &ability_name)],
+                                            ),
+                                        );
+                                    } else if ui.button(None, ability_name.as_str()) {
+                                        ability_to_select = Some(ability_id.clone());
+                                    }
+                                }
+                            });
+
+                            if let Some(ability_id) = ability_to_select {
+                                player.select_ability(&ability_id);
+                            }
+                        }
                     });
 
                 root_ui().pop_skin();
@@ -33,7 +33,7 @@ pub fn draw_confirmation_modal(ui: &mut Ui, body: Vec<String>) -> Option<bool> {
                 GuiSkins::BUTTON_HEIGHT,
             );
 
-            let confirm_btn = widgets::Button::new("Confirm")
+            let confirm_btn = widgets::Button::new(tr("Confirm").as_str())
                 .position(vec2(0.0, buttons_y))
                 .size(buttons_size)
                 .ui(ui);
@@ -42,7 +42,7 @@ pub fn draw_confirmation_modal(ui: &mut Ui, body: Vec<String>) -> Option<bool> {
                 res = Some(true);
             }
 
-            let cancel_btn = widgets::Button::new("Cancel")
+            let cancel_btn = widgets::Button::new(tr("Cancel").as_str())
                 .position(vec2(buttons_size.x + GuiSkins::ELEMENT_MARGIN, buttons_y))
                 .size(buttons_size)
                 .ui(ui);
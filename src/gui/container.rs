@@ -0,0 +1,129 @@
+use crate::gui::inventory::get_item_label;
+use crate::gui::*;
+
+enum TransferAction {
+    Take(String),
+    Store(String),
+    TakeAll,
+    Close,
+}
+
+fn draw_transfer_entry(ui: &mut Ui, entry: &InventoryEntry, label: &str) -> bool {
+    let gui_skins = storage::get::<GuiSkins>();
+
+    let mut res = false;
+    widgets::Group::new(hash!(), vec2(250.0, 30.0)).ui(ui, |ui| {
+        ui.label(vec2(0.0, 0.0), &get_item_label(&entry.params));
+
+        ui.push_skin(&gui_skins.condensed_button);
+        res = ui.button(vec2(200.0, 0.0), tr(label).as_str());
+        ui.pop_skin();
+    });
+    res
+}
+
+// This draws the contents of the container that the player has open, along with the player's
+// own inventory, so that items can be moved between them
+pub fn draw_container_window() {
+    if let Some(mut player) = get_player_actor() {
+        let handle = match player.current_container {
+            Some(handle) => handle,
+            None => return,
+        };
+
+        if let Some(mut container) = scene::try_get_node(handle) {
+            let gui_skins = storage::get::<GuiSkins>();
+            let resources = storage::get::<Resources>();
+
+            let size = vec2(300.0, 450.0);
+            let position = vec2(400.0, 150.0);
+
+            let mut action = None;
+
+            // Corpses are named after the actor, and the name is translated before it is composed
+            let title = if container.is_corpse {
+                tr_args("{name} (dead)", &[("name", &tr(&container.name))])
+            } else {
+                container.name.clone()
+            };
+
+            root_ui().push_skin(&gui_skins.default);
+
+            WindowBuilder::new(hash!(), size)
+                .with_pos(position, false)
+                .with_title(&title)
+                .build(&mut *root_ui(), |ui| {
+                    if container.is_locked {
+                        ui.label(None, &tr("Locked"));
+                        if let Some(key) = container
+                            .key_id
+                            .as_ref()
+                            .and_then(|key_id| resources.items.get(key_id))
+                        {
+                            let key_name = tr(&key.name);
+                            ui.label(None, &tr_args("Requires: {item}", &[("item", &key_name)]));
+                        }
+                    } else {
+                        ui.label(
+                            None,
+                            &tr_args(
+                                "credits: {credits}",
+                                &[("credits", &container.inventory.credits)],
+                            ),
+                        );
+                        if !container.is_empty() && ui.button(None, tr("Take all").as_str()) {
+                            action = Some(TransferAction::TakeAll);
+                        }
+
+                        for entry in &container.inventory.items {
+                            if draw_transfer_entry(ui, entry, "Take") {
+                                action = Some(TransferAction::Take(entry.params.id.clone()));
+                            }
+                        }
+
+                        ui.separator();
+                        ui.label(None, &tr("Inventory"));
+
+                        // Equipped items and quest items can not be stored in containers
+                        for entry in &player.inventory.items {
+                            if entry.equipped_to == EquipmentSlot::None
+                                && !entry.params.is_quest_item
+                                && draw_transfer_entry(ui, entry, "Store")
+                            {
+                                action = Some(TransferAction::Store(entry.params.id.clone()));
+                            }
+                        }
+                    }
+
+                    ui.separator();
+                    if ui.button(None, tr("Close").as_str()) {
+                        action = Some(TransferAction::Close);
+                    }
+                });
+
+            root_ui().pop_skin();
+
+            match action {
+                Some(TransferAction::Take(item_id)) => {
+                    container
+                        .inventory
+                        .transfer(&item_id, &mut player.inventory);
+                }
+                Some(TransferAction::Store(item_id)) => {
+                    player.assign_to_hotbar(&item_id, None);
+                    player
+                        .inventory
+                        .transfer(&item_id, &mut container.inventory);
+                }
+                Some(TransferAction::TakeAll) => {
+                    container.inventory.transfer_all(&mut player.inventory);
+                }
+                Some(TransferAction::Close) => {
+                    drop(container);
+                    player.close_container();
+                }
+                None => {}
+            }
+        }
+    }
+}
@@ -7,34 +7,62 @@ pub fn draw_dialogue_window() {
 
             let size = vec2(400.0, 350.0);
 
+            // The text is translated and interpolated before the window is drawn, as the player
+            // is borrowed mutably when an option is selected
+            let (body, response, options) = {
+                let game_state = scene::get_node(player.game_state);
+                let context = TextContext {
+                    actor_name: Some(&dialogue.actor_name),
+                    variables: Some(&game_state.variables),
+                    ..TextContext::from_player(&player)
+                };
+
+                let get_lines = |lines: &[String]| -> Vec<String> {
+                    lines
+                        .iter()
+                        .map(|line| interpolate(&tr(line), &context))
+                        .collect()
+                };
+
+                let mut options = dialogue.get_options(&player);
+                for option in &mut options {
+                    option.title = interpolate(&option.title, &context);
+                }
+
+                (
+                    get_lines(&dialogue.body),
+                    get_lines(&dialogue.response),
+                    options,
+                )
+            };
+
             root_ui().push_skin(&gui_skins.default);
 
             WindowBuilder::new(hash!(), size)
                 .with_centered_pos(true)
                 .build(&mut *root_ui(), |ui| {
-                    if !dialogue.body.is_empty() {
+                    if !body.is_empty() {
                         ui.label(None, &format!("{}:", player.name));
                     }
 
-                    for line in dialogue.body.clone() {
+                    for line in &body {
                         ui.label(None, &format!(" {}", line));
                     }
 
                     ui.separator();
 
-                    if !dialogue.response.is_empty() {
-                        ui.label(None, &format!("{}:", dialogue.actor_name));
+                    if !response.is_empty() {
+                        ui.label(None, &format!("{}:", tr(&dialogue.actor_name)));
                     }
 
-                    for line in dialogue.response.clone() {
+                    for line in &response {
                         ui.label(None, &format!(" {}", line));
                     }
 
                     ui.separator();
 
-                    let options = dialogue.get_options(&player);
                     if options.is_empty() {
-                        if ui.button(None, "Continue") {
+                        if ui.button(None, tr("Continue").as_str()) {
                             player.current_dialogue = None;
                         }
                     } else {
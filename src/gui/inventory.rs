@@ -1,19 +1,165 @@
 use crate::gui::*;
 
-fn draw_entry(ui: &mut Ui, player: &mut RefMut<Actor>, entry: &InventoryEntry) {
+// The name of an item with affixes is composed from the translated names of its prototype and
+// affixes, as the composed name can not be translated as a whole
+pub(crate) fn get_item_name(params: &ItemParams) -> String {
+    if !params.affixes.is_empty() {
+        let resources = storage::get::<Resources>();
+        let prototype = params
+            .prototype_id
+            .as_ref()
+            .and_then(|prototype_id| resources.items.get(prototype_id));
+        if let Some(prototype) = prototype {
+            return params.compose_affixed_name(&tr(&prototype.name), tr);
+        }
+    }
+
+    tr(&params.name)
+}
+
+pub(crate) fn get_item_label(params: &ItemParams) -> String {
+    let mut label = get_item_name(params);
+    if params.quantity > 1 {
+        label = format!("{} x{}", label, params.quantity);
+    }
+    if params.kind == ItemKind::Consumable && params.charges > 1 {
+        label = format!("{} ({})", label, params.charges);
+    }
+    label
+}
+
+fn get_modifier_label(modifier: &StatModifier) -> String {
+    match modifier.kind {
+        StatModifierKind::Flat => format!("{:+} {}", modifier.value, modifier.stat),
+        StatModifierKind::Percent => format!("{:+}% {}", modifier.value, modifier.stat),
+        StatModifierKind::Multiplier => format!("x{} {}", modifier.value, modifier.stat),
+    }
+}
+
+fn get_tooltip_lines(params: &ItemParams) -> Vec<String> {
+    let resources = storage::get::<Resources>();
+
+    let mut lines = vec![get_item_name(params)];
+    if let Some(rarity_id) = &params.rarity {
+        let rarity = resources
+            .affixes
+            .get_rarity(rarity_id)
+            .map(|rarity| tr(&rarity.name))
+            .unwrap_or_else(|| rarity_id.clone());
+        lines.push(rarity);
+    }
+    if !params.description.is_empty() {
+        lines.push(tr(&params.description));
+    }
+    for modifier in params.get_modifiers() {
+        lines.push(get_modifier_label(&modifier));
+    }
+    if !params.requirements.classes.is_empty() {
+        let classes = params.requirements.classes.join(", ");
+        lines.push(tr_args(
+            "Requires class: {classes}",
+            &[("classes", &classes)],
+        ));
+    }
+    let mut attributes: Vec<(&String, &u32)> = params.requirements.attributes.iter().collect();
+    attributes.sort_by(|a, b| a.0.cmp(b.0));
+    for (id, value) in attributes {
+        lines.push(tr_args(
+            "Requires {attribute}: {value}",
+            &[("attribute", id), ("value", value)],
+        ));
+    }
+    lines
+}
+
+// This draws a tooltip with the details of an item, including any rolled affixes, next to the
+// mouse cursor
+fn draw_item_tooltip(params: &ItemParams) {
     let gui_skins = storage::get::<GuiSkins>();
 
+    let lines = get_tooltip_lines(params);
+    let size = vec2(250.0, lines.len() as f32 * 22.0 + 16.0);
+    let position = get_mouse_position() + vec2(16.0, 16.0);
+
+    let id = hash!();
+    root_ui().push_skin(&gui_skins.default);
+    root_ui().move_window(id, position);
+    WindowBuilder::new(id, size)
+        .with_pos(position, false)
+        .build(&mut *root_ui(), |ui| {
+            for line in &lines {
+                ui.label(None, line);
+            }
+        });
+    root_ui().pop_skin();
+}
+
+// Returns `true` if the mouse is over the name of the entry
+fn draw_entry(ui: &mut Ui, player: &mut RefMut<Actor>, entry: &InventoryEntry) -> bool {
+    let gui_skins = storage::get::<GuiSkins>();
+
+    let mut is_hovered = false;
     widgets::Group::new(hash!(), vec2(250.0, 30.0)).ui(ui, |ui| {
-        ui.label(vec2(0.0, 0.0), &entry.params.name);
-        if entry.equipped_to == EquipmentSlot::None {
+        ui.push_skin(&gui_skins.label_button);
+        ui.button(vec2(0.0, 0.0), get_item_label(&entry.params).as_str());
+        is_hovered = ui.last_item_hovered();
+        ui.pop_skin();
+
+        // Stacks of any kind are split in half
+        if entry.params.quantity > 1 {
             ui.push_skin(&gui_skins.condensed_button);
-            if ui.button(vec2(160.0, 0.0), "Equip") {
-                player.equip_item(&entry.params.id);
+            if ui.button(vec2(100.0, 0.0), tr("Split").as_str()) {
+                let quantity = entry.params.quantity / 2;
+                player.inventory.split(&entry.params.id, quantity);
             }
             ui.pop_skin();
+        }
+
+        if entry.params.kind == ItemKind::Consumable {
+            if player.get_item_cooldown(&entry.params) > 0.0 {
+                ui.push_skin(&gui_skins.condensed_button_inactive);
+                ui.button(vec2(140.0, 0.0), tr("Use").as_str());
+                ui.pop_skin();
+            } else {
+                ui.push_skin(&gui_skins.condensed_button);
+                if ui.button(vec2(140.0, 0.0), tr("Use").as_str()) {
+                    player.controller.use_item = Some(entry.params.id.clone());
+                }
+                ui.pop_skin();
+            }
+
+            // This cycles through the slots of the hotbar, and then back to unassigned
+            let slot = player.get_hotbar_slot(&entry.params.id);
+            let label = slot
+                .map(|slot| format!("[{}]", slot + 1))
+                .unwrap_or_else(|| "[-]".to_string());
+            ui.push_skin(&gui_skins.condensed_button);
+            if ui.button(vec2(175.0, 0.0), label.as_str()) {
+                let next_slot = match slot {
+                    Some(slot) if slot + 1 >= Actor::HOTBAR_SIZE => None,
+                    Some(slot) => Some(slot + 1),
+                    None => Some(0),
+                };
+                player.assign_to_hotbar(&entry.params.id, next_slot);
+            }
+            ui.pop_skin();
+        } else if entry.equipped_to == EquipmentSlot::None {
+            if entry.params.kind != ItemKind::Misc {
+                // Items that the player doesn't meet the requirements for are shown as inactive
+                if player.can_equip(&entry.params) {
+                    ui.push_skin(&gui_skins.condensed_button);
+                    if ui.button(vec2(160.0, 0.0), tr("Equip").as_str()) {
+                        player.equip_item(&entry.params.id);
+                    }
+                } else {
+                    ui.push_skin(&gui_skins.condensed_button_inactive);
+                    ui.button(vec2(160.0, 0.0), tr("Equip").as_str());
+                }
+                ui.pop_skin();
+            }
         } else {
             ui.push_skin(&gui_skins.condensed_button);
-            if ui.button(vec2(150.0, 0.0), "Unequip") {
+            if ui.button(vec2(150.0, 0.0), tr("Unequip").as_str()) {
                 player.unequip_item(&entry.params.id);
             }
             ui.pop_skin();
@@ -21,14 +167,17 @@ fn draw_entry(ui: &mut Ui, player: &mut RefMut<Actor>, entry: &InventoryEntry) {
 
         if !entry.params.is_quest_item {
             ui.push_skin(&gui_skins.condensed_button_inactive);
-            if ui.button(vec2(210.0, 0.0), "Drop") {
+            if ui.button(vec2(210.0, 0.0), tr("Drop").as_str()) {
                 player.unequip_item(&entry.params.id);
+                player.assign_to_hotbar(&entry.params.id, None);
                 let position = player.body.position;
                 player.inventory.drop(&entry.params.id, position);
             }
             ui.pop_skin();
         }
     });
+
+    is_hovered
 }
 
 pub fn draw_inventory_window() {
@@ -40,6 +189,8 @@ pub fn draw_inventory_window() {
                 let size = vec2(300.0, 400.0);
                 let position = vec2(50.0, 475.0);
 
+                let mut hovered_item = None;
+
                 root_ui().push_skin(&gui_skins.default);
 
                 WindowBuilder::new(hash!(), size)
@@ -47,13 +198,37 @@ pub fn draw_inventory_window() {
                     .build(&mut *root_ui(), |ui| {
                         ui.label(
                             None,
-                            &format!(
-                                "credits: {}, weight: {}/{}",
-                                player.inventory.credits,
-                                player.inventory.get_total_weight(),
-                                player.stats.carry_capacity
+                            &tr_args(
+                                "credits: {credits}, weight: {weight}/{capacity}",
+                                &[
+                                    ("credits", &player.inventory.credits),
+                                    ("weight", &player.inventory.get_total_weight()),
+                                    ("capacity", &player.stats.carry_capacity),
+                                ],
                             ),
                         );
+                        match player.inventory.capacity {
+                            InventoryCapacity::Slots { slots } => {
+                                let label = tr_args(
+                                    "slots: {used}/{slots}",
+                                    &[("used", &player.inventory.items.len()), ("slots", &slots)],
+                                );
+                                ui.label(None, &label);
+                            }
+                            InventoryCapacity::Grid { width, height } => {
+                                ui.label(
+                                    None,
+                                    &tr_args(
+                                        "grid: {width}x{height}",
+                                        &[("width", &width), ("height", &height)],
+                                    ),
+                                );
+                            }
+                            InventoryCapacity::Unlimited => {}
+                        }
+                        if ui.button(None, tr("Stack all").as_str()) {
+                            player.inventory.stack_all();
+                        }
                         {
                             let items = player.inventory.get_all_of_kind(&[
                                 ItemKind::OneHandedWeapon,
@@ -61,7 +236,31 @@ pub fn draw_inventory_window() {
                             ]);
                             if !items.is_empty() {
                                 for item in &items {
-                                    draw_entry(ui, &mut player, item);
+                                    if draw_entry(ui, &mut player, item) {
+                                        hovered_item = Some(item.params.clone());
+                                    }
+                                }
+                            }
+                        }
+                        {
+                            let items = player
+                                .inventory
+                                .get_all_of_kind(&[ItemKind::Armor, ItemKind::Accessory]);
+                            if !items.is_empty() {
+                                for item in &items {
+                                    if draw_entry(ui, &mut player, item) {
+                                        hovered_item = Some(item.params.clone());
+                                    }
+                                }
+                            }
+                        }
+                        {
+                            let items = player.inventory.get_all_of_kind(&[ItemKind::Consumable]);
+                            if !items.is_empty() {
+                                for item in &items {
+                                    if draw_entry(ui, &mut player, item) {
+                                        hovered_item = Some(item.params.clone());
+                                    }
                                 }
                             }
                         }
@@ -69,13 +268,19 @@ pub fn draw_inventory_window() {
                             let items = player.inventory.get_all_of_kind(&[ItemKind::Misc]);
                             if !items.is_empty() {
                                 for item in &items {
-                                    draw_entry(ui, &mut player, item)
+                                    if draw_entry(ui, &mut player, item) {
+                                        hovered_item = Some(item.params.clone());
+                                    }
                                 }
                             }
                         }
                     });
 
                 root_ui().pop_skin();
+
+                if let Some(params) = hovered_item {
+                    draw_item_tooltip(&params);
+                }
             }
         }
     }
@@ -1,55 +1,45 @@
 use crate::gui::*;
 
-const OPT_CANCEL: usize = 0;
-
-pub(crate) async fn draw_chapter_selection_menu() -> Option<(usize, String)> {
-    loop {
-        let gui_skins = storage::get::<GuiSkins>();
-        root_ui().push_skin(&gui_skins.default);
-
-        let mut result = None;
-        let mut should_cancel = false;
-
-        let resources = storage::get::<Resources>();
-
-        let params = gui_skins.theme.menu_params.get("chapter_selection").cloned().unwrap();
+const CHAPTER_SELECTION_MENU_ID: &str = "chapter_selection";
+
+// This returns the index of the selected chapter, out of the chapters that are unlocked for the
+// character, or `None` if the menu was cancelled
+pub(crate) async fn draw_chapter_selection(character: &Character) -> Option<usize> {
+    let gui_skins = storage::get::<GuiSkins>();
+    let resources = storage::get::<Resources>();
+    let mut params = gui_skins
+        .theme
+        .menu_params
+        .get(CHAPTER_SELECTION_MENU_ID)
+        .cloned()
+        .unwrap();
+
+    for (i, chapter) in resources.chapters.iter().enumerate() {
+        if character.is_chapter_unlocked(i) {
+            let title = if i == character.chapter_index {
+                let title = tr(&chapter.title);
+                tr_args("{title} (current)", &[("title", &title)])
+            } else {
+                chapter.title.clone()
+            };
 
-        let mut index = 1; // 0 is cancel
-        let mut options = params.options;
-        for chapter in &resources.chapters {
-            options.push(MenuOption {
-                index,
-                title: chapter.title.clone(),
-                push_down: false,
+            params.options.push(MenuOption {
+                index: Some(i),
+                title: Some(title),
+                ..Default::default()
             });
-            index += 1;
-        }
-
-        let params = MenuParams {
-            options,
-            ..params
-        };
-
-        if let Some(i) = MenuBuilder::new(hash!(), params).build(&mut *root_ui()) {
-            if i == OPT_CANCEL {
-                should_cancel = true;
-            } else {
-                let chapter_index = i - 1;
-                let chapter = resources.chapters.get(chapter_index).unwrap();
-                result = Some((chapter_index, chapter.initial_map_id.clone()));
-            }
         }
+    }
 
-        if result.is_some() || should_cancel {
-            root_ui().pop_skin();
+    let builder = MenuBuilder::new(hash!(), params);
 
-            if should_cancel {
-                return None;
-            } else {
-                return result;
-            }
+    loop {
+        match builder.build(&mut *root_ui()) {
+            MenuResult::Index(i) => return Some(i),
+            MenuResult::Cancel => return None,
+            _ => {}
         }
 
-        next_frame().await;
+        end_frame().await;
     }
 }
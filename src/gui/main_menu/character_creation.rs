@@ -1,46 +1,8 @@
+use crate::gui::character::draw_character_attribute;
 use crate::gui::*;
 
 const MINIMUM_CHARACTER_NAME_LENGTH: usize = 4;
-
-fn draw_character_attribute(
-    ui: &mut Ui,
-    i: usize,
-    name: &str,
-    value: &mut u32,
-    build_points: &mut u32,
-) {
-    let gui_skins = storage::get::<GuiSkins>();
-
-    let y_offset = i as f32 * 22.0;
-
-    ui.label(vec2(2.0, y_offset - 2.0), &format!("{}: {}", name, value));
-
-    if *value > 6 {
-        ui.push_skin(&gui_skins.condensed_button);
-        if ui.button(vec2(58.0, y_offset), "-") {
-            *value -= 1;
-            *build_points += 1;
-        }
-        ui.pop_skin();
-    } else {
-        ui.push_skin(&gui_skins.condensed_button_inactive);
-        ui.button(vec2(58.0, y_offset), "-");
-        ui.pop_skin();
-    }
-
-    if *build_points > 0 {
-        ui.push_skin(&gui_skins.condensed_button);
-        if ui.button(vec2(74.0, y_offset), "+") {
-            *value += 1;
-            *build_points -= 1;
-        }
-        ui.pop_skin();
-    } else {
-        ui.push_skin(&gui_skins.condensed_button_inactive);
-        ui.button(vec2(74.0, y_offset), "+");
-        ui.pop_skin();
-    }
-}
+const MINIMUM_ATTRIBUTE_VALUE: u32 = 6;
 
 #[cfg(not(any(target_family = "wasm", target_os = "android")))]
 async fn is_name_in_use(name: &str) -> bool {
@@ -94,7 +56,10 @@ pub(crate) async fn draw_character_creation(class_id: &str) -> Option<Character>
 
                 ui.separator();
 
-                ui.label(None, &format!("Build points: {}", build_points));
+                ui.label(
+                    None,
+                    &tr_args("Build points: {points}", &[("points", &build_points)]),
+                );
 
                 let bottom_y = WINDOW_HEIGHT
                     - GuiSkins::ELEMENT_MARGIN
@@ -111,6 +76,7 @@ pub(crate) async fn draw_character_creation(class_id: &str) -> Option<Character>
                             0,
                             "STR",
                             &mut params.strength,
+                            MINIMUM_ATTRIBUTE_VALUE,
                             &mut build_points,
                         );
                         draw_character_attribute(
@@ -118,6 +84,7 @@ pub(crate) async fn draw_character_creation(class_id: &str) -> Option<Character>
                             1,
                             "DEX",
                             &mut params.dexterity,
+                            MINIMUM_ATTRIBUTE_VALUE,
                             &mut build_points,
                         );
                         draw_character_attribute(
@@ -125,6 +92,7 @@ pub(crate) async fn draw_character_creation(class_id: &str) -> Option<Character>
                             2,
                             "CON",
                             &mut params.constitution,
+                            MINIMUM_ATTRIBUTE_VALUE,
                             &mut build_points,
                         );
                         draw_character_attribute(
@@ -132,6 +100,7 @@ pub(crate) async fn draw_character_creation(class_id: &str) -> Option<Character>
                             3,
                             "INT",
                             &mut params.intelligence,
+                            MINIMUM_ATTRIBUTE_VALUE,
                             &mut build_points,
                         );
                         draw_character_attribute(
@@ -139,6 +108,7 @@ pub(crate) async fn draw_character_creation(class_id: &str) -> Option<Character>
                             4,
                             "WIL",
                             &mut params.willpower,
+                            MINIMUM_ATTRIBUTE_VALUE,
                             &mut build_points,
                         );
                         draw_character_attribute(
@@ -146,6 +116,7 @@ pub(crate) async fn draw_character_creation(class_id: &str) -> Option<Character>
                             5,
                             "PER",
                             &mut params.perception,
+                            MINIMUM_ATTRIBUTE_VALUE,
                             &mut build_points,
                         );
                         draw_character_attribute(
@@ -153,6 +124,7 @@ pub(crate) async fn draw_character_creation(class_id: &str) -> Option<Character>
                             6,
                             "CHA",
                             &mut params.charisma,
+                            MINIMUM_ATTRIBUTE_VALUE,
                             &mut build_points,
                         );
                     });
@@ -162,21 +134,21 @@ pub(crate) async fn draw_character_creation(class_id: &str) -> Option<Character>
                     .ui(ui, |ui| {
                         //draw_checkbox(ui, hash!(), vec2(0.0, 130.0), "Hardcore", &mut is_permadeath);
 
-                        Checkbox::new(hash!(), vec2(0.0, 130.0), "Hardcore", &mut is_permadeath)
-                            .ui(ui);
+                        let label = tr("Hardcore");
+                        Checkbox::new(hash!(), vec2(0.0, 130.0), &label, &mut is_permadeath).ui(ui);
                     });
 
                 if should_show_build_points_warning {
-                    let label = "You have unspent build points!";
-                    let warning_y = bottom_y - GuiSkins::ELEMENT_MARGIN - ui.calc_size(label).y;
+                    let label = tr("You have unspent build points!");
+                    let warning_y = bottom_y - GuiSkins::ELEMENT_MARGIN - ui.calc_size(&label).y;
                     ui.push_skin(&gui_skins.warning_label);
-                    ui.label(vec2(0.0, warning_y), label);
+                    ui.label(vec2(0.0, warning_y), &label);
                     ui.pop_skin();
                 } else if should_show_name_warning {
-                    let label = "Name is already in use!";
-                    let warning_y = bottom_y - GuiSkins::ELEMENT_MARGIN - ui.calc_size(label).y;
+                    let label = tr("Name is already in use!");
+                    let warning_y = bottom_y - GuiSkins::ELEMENT_MARGIN - ui.calc_size(&label).y;
                     ui.push_skin(&gui_skins.warning_label);
-                    ui.label(vec2(0.0, warning_y), label);
+                    ui.label(vec2(0.0, warning_y), &label);
                     ui.pop_skin();
                 }
 
@@ -188,7 +160,7 @@ pub(crate) async fn draw_character_creation(class_id: &str) -> Option<Character>
 
                 if name_warning.is_some() || build_points > 0 {
                     ui.push_skin(&gui_skins.inactive_button);
-                    let done_btn = widgets::Button::new("Done")
+                    let done_btn = widgets::Button::new(tr("Done").as_str())
                         .size(btn_size)
                         .position(vec2(0.0, btn_y))
                         .ui(ui);
@@ -199,7 +171,7 @@ pub(crate) async fn draw_character_creation(class_id: &str) -> Option<Character>
                     }
                     ui.pop_skin();
                 } else {
-                    let done_btn = widgets::Button::new("Done")
+                    let done_btn = widgets::Button::new(tr("Done").as_str())
                         .size(btn_size)
                         .position(vec2(0.0, btn_y))
                         .ui(ui);
@@ -211,7 +183,7 @@ pub(crate) async fn draw_character_creation(class_id: &str) -> Option<Character>
                     }
                 }
 
-                let cancel_btn = widgets::Button::new("Cancel")
+                let cancel_btn = widgets::Button::new(tr("Cancel").as_str())
                     .size(btn_size)
                     .position(vec2(btn_size.x + GuiSkins::ELEMENT_MARGIN, btn_y))
                     .ui(ui);
@@ -260,13 +232,14 @@ pub(crate) async fn draw_set_character_name() -> Option<String> {
         WindowBuilder::new(hash!(), size)
             .with_centered_pos(true)
             .build(&mut *root_ui(), |ui| {
-                ui.label(None, "Character Name:");
+                ui.label(None, &tr("Character Name:"));
 
                 ui.push_skin(&gui_skins.big_editbox);
                 ui.input_text(hash!(), "", &mut name);
                 ui.pop_skin();
 
-                let ok_btn = widgets::Button::new("Ok")
+                let ok_label = tr("Ok");
+                let ok_btn = widgets::Button::new(ok_label.as_str())
                     .position(vec2(0.0, btn_y))
                     .size(btn_size);
 
@@ -291,7 +264,8 @@ pub(crate) async fn draw_set_character_name() -> Option<String> {
                     }
                 }
 
-                let cancel_btn = widgets::Button::new("Cancel")
+                let cancel_label = tr("Cancel");
+                let cancel_btn = widgets::Button::new(cancel_label.as_str())
                     .position(vec2(btn_width + 2.0, btn_y))
                     .size(btn_size);
 
@@ -315,9 +289,9 @@ async fn is_name_valid(name: &str) -> bool {
 
 async fn get_name_warning(name: &str) -> Option<String> {
     if is_name_in_use(name).await {
-        Some("Name is already in use!".to_string())
+        Some(tr("Name is already in use!"))
     } else if !is_name_valid(name).await {
-        Some("Invalid name!".to_string())
+        Some(tr("Invalid name!"))
     } else {
         None
     }
@@ -28,7 +28,7 @@ pub(crate) async fn draw_character_selection() -> CharacterSelectionResult {
             let character: &Character = characters.get(i).unwrap();
 
             let modal_body = vec![
-                "Are you sure you want to delete".to_string(),
+                tr("Are you sure you want to delete"),
                 format!("'{}'?", character.actor.name),
             ];
 
@@ -80,7 +80,7 @@ fn draw_character_list(
             let gui_skins = storage::get::<GuiSkins>();
 
             ui.push_skin(&gui_skins.header_label);
-            ui.label(None, "Select Character");
+            ui.label(None, &tr("Select Character"));
             ui.pop_skin();
 
             let margins = vec2(GuiSkins::WINDOW_MARGIN_X, GuiSkins::WINDOW_MARGIN_Y) * 2.0;
@@ -105,7 +105,7 @@ fn draw_character_list(
                     }
                 });
 
-            let create_btn = widgets::Button::new("Create")
+            let create_btn = widgets::Button::new(tr("Create").as_str())
                 .size(btn_size)
                 .position(vec2(0.0, btn_position_y))
                 .ui(ui);
@@ -114,7 +114,7 @@ fn draw_character_list(
                 result = Some(CharacterSelectionResult::CreateCharacter);
             }
 
-            let cancel_btn = widgets::Button::new("Cancel")
+            let cancel_btn = widgets::Button::new(tr("Cancel").as_str())
                 .size(btn_size)
                 .position(vec2(btn_size.x + GuiSkins::ELEMENT_MARGIN, btn_position_y))
                 .ui(ui);
@@ -167,7 +167,7 @@ fn draw_character_details(
                     - GuiSkins::ELEMENT_MARGIN,
             );
 
-            let delete_btn = widgets::Button::new("Delete")
+            let delete_btn = widgets::Button::new(tr("Delete").as_str())
                 .size(delete_btn_size)
                 .position(delete_btn_position)
                 .ui(ui);
@@ -177,7 +177,7 @@ fn draw_character_details(
                 *delete_i = Some(i);
             }
 
-            let start_btn = widgets::Button::new("Start")
+            let start_btn = widgets::Button::new(tr("Start").as_str())
                 .size(btn_size)
                 .position(vec2(0.0, btn_position_y))
                 .ui(ui);
@@ -188,7 +188,7 @@ fn draw_character_details(
                 )));
             }
 
-            let back_btn = widgets::Button::new("Back")
+            let back_btn = widgets::Button::new(tr("Back").as_str())
                 .size(btn_size)
                 .position(vec2(btn_size.x + GuiSkins::ELEMENT_MARGIN, btn_position_y))
                 .ui(ui);
@@ -1,10 +1,11 @@
+mod chapter_selection;
 mod character_creation;
 mod character_selection;
-//mod chapter_selection;
 mod class_selection;
 mod module_management;
 mod settings;
 
+use chapter_selection::draw_chapter_selection;
 use character_creation::{draw_character_creation, draw_set_character_name};
 use character_selection::{draw_character_selection, CharacterSelectionResult};
 use class_selection::draw_class_selection;
@@ -27,7 +28,25 @@ pub async fn show_main_menu() -> Result<()> {
     'menu: loop {
         match draw_main_menu().await {
             MainMenuResult::StartGame => match draw_character_selection().await {
-                CharacterSelectionResult::SelectCharacter(character) => {
+                CharacterSelectionResult::SelectCharacter(mut character) => {
+                    // Characters that have completed a chapter can revisit any unlocked chapter
+                    if !character.completed_chapters.is_empty() {
+                        match draw_chapter_selection(&character).await {
+                            Some(chapter_index) => {
+                                if chapter_index != character.chapter_index {
+                                    let resources = storage::get::<Resources>();
+                                    let chapter = resources.chapters.get(chapter_index).unwrap();
+                                    character = Box::new(
+                                        character.with_map(chapter_index, &chapter.initial_map_id),
+                                    );
+                                    drop(resources);
+                                    show_chapter_intro(chapter_index).await;
+                                }
+                            }
+                            None => continue 'menu,
+                        }
+                    }
+
                     dispatch_event(Event::StartGame { character });
                     break 'menu;
                 }
@@ -46,6 +65,7 @@ pub async fn show_main_menu() -> Result<()> {
                                 let character: Character = params.into();
 
                                 character.save()?;
+                                show_chapter_intro(character.chapter_index).await;
 
                                 dispatch_event(Event::StartGame {
                                     character: Box::new(character),
@@ -54,6 +74,7 @@ pub async fn show_main_menu() -> Result<()> {
                             }
                         } else if let Some(character) = draw_character_creation(&class_id).await {
                             character.save()?;
+                            show_chapter_intro(character.chapter_index).await;
 
                             dispatch_event(Event::StartGame {
                                 character: Box::new(character),
@@ -108,7 +108,7 @@ pub(crate) async fn show_module_management() {
             .titlebar(false)
             .ui(&mut *root_ui(), |ui| {
                 ui.push_skin(&gui_skins.header_label);
-                ui.label(None, "Modules");
+                ui.label(None, &tr("Modules"));
                 ui.pop_skin();
 
                 let size = vec2(450.0, 550.0);
@@ -157,18 +157,18 @@ pub(crate) async fn show_module_management() {
 
                 if will_require_restart {
                     ui.push_skin(&gui_skins.warning_label);
-                    ui.label(vec2(0.0, 493.0), "Changes require a restart!");
+                    ui.label(vec2(0.0, 493.0), &tr("Changes require a restart!"));
                     ui.pop_skin();
                 }
 
                 let btn_size = vec2(222.0, 32.0);
 
-                let save_btn = widgets::Button::new("Save")
+                let save_btn = widgets::Button::new(tr("Save").as_str())
                     .position(vec2(0.0, 520.0))
                     .size(btn_size)
                     .ui(ui);
 
-                let cancel_btn = widgets::Button::new("Cancel")
+                let cancel_btn = widgets::Button::new(tr("Cancel").as_str())
                     .position(vec2(226.0, 520.0))
                     .size(btn_size)
                     .ui(ui);
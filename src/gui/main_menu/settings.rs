@@ -23,6 +23,13 @@ pub(crate) async fn show_settings() {
 
     let mut fullscreen_cfg = config.fullscreen;
 
+    let (locales, font_id) = {
+        let localization = storage::get::<Localization>();
+        let font_id = localization.get_font_id(&config.locale);
+        (localization.get_available_locales(), font_id)
+    };
+    let mut locale_cfg = config.locale.clone();
+
     let resolution_regex = Regex::new(r"^[0-9]*$").unwrap();
 
     loop {
@@ -30,10 +37,10 @@ pub(crate) async fn show_settings() {
             .titlebar(false)
             .ui(&mut *root_ui(), |ui| {
                 ui.push_skin(&gui_skins.header_label);
-                ui.label(None, "Settings");
+                ui.label(None, &tr("Settings"));
                 ui.pop_skin();
 
-                ui.label(None, "Resolution");
+                ui.label(None, &tr("Resolution"));
                 ui.editbox(hash!(), vec2(42.0, 18.0), &mut resolution_x_str);
 
                 ui.same_line(48.0);
@@ -42,10 +49,24 @@ pub(crate) async fn show_settings() {
                 ui.same_line(58.0);
                 ui.editbox(hash!(), vec2(42.0, 18.0), &mut resolution_y_str);
 
-                Checkbox::new(hash!(), None, "Fullscreen", &mut fullscreen_cfg).ui(ui);
+                let label = tr("Fullscreen");
+                Checkbox::new(hash!(), None, &label, &mut fullscreen_cfg).ui(ui);
 
                 ui.separator();
 
+                // Clicking the locale cycles through the available locales
+                if !locales.is_empty() {
+                    ui.label(None, &tr("Language"));
+                    let i = locales.iter().position(|(id, _)| *id == locale_cfg);
+                    let label = i
+                        .map(|i| locales[i].1.clone())
+                        .unwrap_or_else(|| locale_cfg.clone());
+                    if ui.button(None, label.as_str()) {
+                        let next_i = i.map(|i| (i + 1) % locales.len()).unwrap_or(0);
+                        locale_cfg = locales[next_i].0.clone();
+                    }
+                }
+
                 // ui.label(None, "UI Scale");
                 // ui.editbox(hash!(), vec2(32.0, 18.0), &mut gui_scale_str);
                 //
@@ -82,18 +103,18 @@ pub(crate) async fn show_settings() {
 
                 if will_require_restart {
                     ui.push_skin(&gui_skins.warning_label);
-                    ui.label(vec2(0.0, 213.0), "Changes require a restart!");
+                    ui.label(vec2(0.0, 213.0), &tr("Changes require a restart!"));
                     ui.pop_skin();
                 }
 
                 let btn_size = vec2(132.0, 28.0);
 
-                let save_btn = widgets::Button::new("Save")
+                let save_btn = widgets::Button::new(tr("Save").as_str())
                     .position(vec2(0.0, 245.0))
                     .size(btn_size)
                     .ui(ui);
 
-                let cancel_btn = widgets::Button::new("Cancel")
+                let cancel_btn = widgets::Button::new(tr("Cancel").as_str())
                     .position(vec2(137.0, 245.0))
                     .size(btn_size)
                     .ui(ui);
@@ -115,8 +136,11 @@ pub(crate) async fn show_settings() {
             resolution_y_str.parse().unwrap(),
         );
 
-        will_require_restart =
-            resolution != config.resolution || fullscreen_cfg != config.fullscreen;
+        // Text is translated immediately, but the GUI skins have to be rebuilt to change the font
+        let locale_font_id = storage::get::<Localization>().get_font_id(&locale_cfg);
+        will_require_restart = resolution != config.resolution
+            || fullscreen_cfg != config.fullscreen
+            || locale_font_id != font_id;
 
         if should_save || should_cancel {
             root_ui().pop_skin();
@@ -128,6 +152,7 @@ pub(crate) async fn show_settings() {
                 );
 
                 config.fullscreen = fullscreen_cfg;
+                config.locale = locale_cfg.clone();
 
                 storage::store(config.clone());
                 config.save();
@@ -228,8 +228,9 @@ impl MenuBuilder {
                     builder
                 };
 
+                // The titles of the options, and of the menu, are translated to the current locale
                 if let Some(title) = &opt.title {
-                    builder = builder.with_label(title);
+                    builder = builder.with_label(&tr(title));
                 }
 
                 let menu_result = if opt.is_cancel {
@@ -246,7 +247,7 @@ impl MenuBuilder {
             let mut next_top_y = 0.0;
             if let Some(title) = &self.params.title {
                 ui.push_skin(&gui_skins.window_title);
-                next_top_y += ui.calc_size(title).y + 2.0;
+                next_top_y += ui.calc_size(&tr(title)).y + 2.0;
                 ui.pop_skin();
             }
 
@@ -1,13 +1,16 @@
 use std::ops::Deref;
 
+pub use chapter::{show_chapter_intro, show_chapter_screen};
 pub use character::draw_character_window;
 pub use confirmation_modal::draw_confirmation_modal;
+pub use container::draw_container_window;
 pub use dialogue::draw_dialogue_window;
 pub use game_menu::draw_game_menu;
 pub use inventory::draw_inventory_window;
 pub use macroquad::ui::{hash, root_ui, widgets, Drag, Id, Skin, Ui};
 pub use main_menu::show_main_menu;
 pub use theme::{GuiSkins, GuiTheme};
+pub use trade::draw_trade_window;
 
 pub use window_builder::WindowBuilder;
 
@@ -21,15 +24,18 @@ use crate::prelude::*;
 pub use checkbox::Checkbox;
 
 mod button_builder;
+mod chapter;
 mod character;
 mod checkbox;
 mod confirmation_modal;
+mod container;
 mod dialogue;
 mod game_menu;
 mod inventory;
 mod main_menu;
 mod menu_builder;
 mod theme;
+mod trade;
 mod window_builder;
 
 #[derive(Debug, Default, Clone)]
@@ -49,6 +55,8 @@ pub(crate) fn draw_gui() {
     draw_character_window();
     draw_inventory_window();
     draw_dialogue_window();
+    draw_container_window();
+    draw_trade_window();
     draw_game_menu();
 }
 
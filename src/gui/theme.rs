@@ -1,4 +1,5 @@
 use crate::gui::*;
+use macroquad::ui::StyleBuilder;
 
 fn sub_offsets(a: RectOffset, b: RectOffset) -> RectOffset {
     RectOffset::new(
@@ -195,6 +196,18 @@ impl Default for GuiTheme {
     }
 }
 
+fn get_style_builder(font: Option<&[u8]>) -> StyleBuilder {
+    let builder = root_ui().style_builder();
+    match font {
+        // The font is validated when the skins are built, so this should never fall back to the
+        // default font
+        Some(bytes) => builder
+            .font(bytes)
+            .unwrap_or_else(|_| root_ui().style_builder()),
+        None => builder,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct GuiSkins {
     pub default: Skin,
@@ -266,23 +279,48 @@ impl GuiSkins {
 
         let blank_image = resources.images.get("blank_image").unwrap();
 
+        // Locales can define a font for characters that are not covered by the default font. If
+        // that font can't be found, or loaded, the default font is used instead.
+        let font = {
+            let config = storage::get::<Config>();
+            storage::try_get::<Localization>()
+                .and_then(|localization| localization.get_font_id(&config.locale))
+                .and_then(|font_id| {
+                    let bytes = resources.font_bytes.get(&font_id);
+                    if bytes.is_none() {
+                        println!(
+                            "GUI: Locale '{}' uses an unknown font '{}', so the default font is used instead",
+                            config.locale, font_id
+                        );
+                    }
+                    bytes.map(|bytes| (font_id, bytes.as_slice()))
+                })
+                .and_then(|(font_id, bytes)| {
+                    if let Err(err) = root_ui().style_builder().font(bytes) {
+                        println!(
+                            "GUI: Unable to load the font '{}' ({:?}), so the default font is used instead",
+                            font_id, err
+                        );
+                        return None;
+                    }
+                    Some(bytes)
+                })
+        };
+
         let default = {
-            let window_style = root_ui()
-                .style_builder()
+            let window_style = get_style_builder(font)
                 .background(window_bg)
                 .background_margin(theme.window_bg.margins)
                 .margin(sub_offsets(theme.window_margins, theme.window_bg.margins))
                 .build();
 
-            let label_style = root_ui()
-                .style_builder()
+            let label_style = get_style_builder(font)
                 .margin(theme.label_margins)
                 .text_color(theme.text_color)
                 .font_size(theme.font_size)
                 .build();
 
-            let button_style = root_ui()
-                .style_builder()
+            let button_style = get_style_builder(font)
                 .margin(sub_offsets(theme.button_margins, theme.button_bg.margins))
                 .background_margin(theme.button_bg.margins)
                 .background(button_bg.clone())
@@ -292,8 +330,7 @@ impl GuiSkins {
                 .font_size(theme.font_size)
                 .build();
 
-            let editbox_style = root_ui()
-                .style_builder()
+            let editbox_style = get_style_builder(font)
                 .background(editbox_bg.clone())
                 .margin(sub_offsets(theme.editbox_margins, theme.editbox_bg.margins))
                 .background_margin(theme.editbox_bg.margins)
@@ -301,30 +338,26 @@ impl GuiSkins {
                 .font_size(theme.font_size)
                 .build();
 
-            let checkbox_style = root_ui()
-                .style_builder()
+            let checkbox_style = get_style_builder(font)
                 .background(checkbox_bg.clone())
                 .background_hovered(checkbox_bg_hovered.clone())
                 .background_clicked(checkbox_bg_clicked.clone())
                 .build();
 
-            let group_style = root_ui()
-                .style_builder()
+            let group_style = get_style_builder(font)
                 .margin(theme.group_margins)
                 .color(theme.group_border_color)
                 .color_hovered(theme.group_border_color_hovered)
                 .color_clicked(theme.group_border_color_clicked)
                 .build();
 
-            let scrollbar_style = root_ui()
-                .style_builder()
+            let scrollbar_style = get_style_builder(font)
                 .color(theme.scrollbar_color)
                 .color_hovered(theme.scrollbar_color_hovered)
                 .color_clicked(theme.scrollbar_color_clicked)
                 .build();
 
-            let scrollbar_handle_style = root_ui()
-                .style_builder()
+            let scrollbar_handle_style = get_style_builder(font)
                 .color(theme.scrollbar_handle_color)
                 .color_hovered(theme.scrollbar_handle_color_hovered)
                 .color_clicked(theme.scrollbar_handle_color_clicked)
@@ -345,8 +378,7 @@ impl GuiSkins {
         };
 
         let window_title = {
-            let label_style = root_ui()
-                .style_builder()
+            let label_style = get_style_builder(font)
                 .margin(theme.window_title_margins)
                 .text_color(theme.window_title_color)
                 .font_size(theme.window_title_size)
@@ -359,21 +391,18 @@ impl GuiSkins {
         };
 
         let module_list_entry = {
-            let group_style = root_ui()
-                .style_builder()
+            let group_style = get_style_builder(font)
                 .color(COLOR_NONE)
                 .color_hovered(Color::from_rgba(255, 255, 255, 255))
                 .build();
 
-            let scrollbar_style = root_ui()
-                .style_builder()
+            let scrollbar_style = get_style_builder(font)
                 .color(COLOR_NONE)
                 .color_hovered(COLOR_NONE)
                 .color_clicked(COLOR_NONE)
                 .build();
 
-            let scrollbar_handle_style = root_ui()
-                .style_builder()
+            let scrollbar_handle_style = get_style_builder(font)
                 .color(COLOR_NONE)
                 .color_hovered(COLOR_NONE)
                 .color_clicked(COLOR_NONE)
@@ -388,30 +417,26 @@ impl GuiSkins {
         };
 
         let checkbox = {
-            let button_style = root_ui()
-                .style_builder()
+            let button_style = get_style_builder(font)
                 .background(checkbox_bg.clone())
                 .background_hovered(checkbox_bg_hovered.clone())
                 .background_clicked(checkbox_bg_clicked.clone())
                 .background_margin(theme.checkbox_bg.margins)
                 .build();
 
-            let scrollbar_style = root_ui()
-                .style_builder()
+            let scrollbar_style = get_style_builder(font)
                 .color(COLOR_NONE)
                 .color_hovered(COLOR_NONE)
                 .color_clicked(COLOR_NONE)
                 .build();
 
-            let scrollbar_handle_style = root_ui()
-                .style_builder()
+            let scrollbar_handle_style = get_style_builder(font)
                 .color(COLOR_NONE)
                 .color_hovered(COLOR_NONE)
                 .color_clicked(COLOR_NONE)
                 .build();
 
-            let group_style = root_ui()
-                .style_builder()
+            let group_style = get_style_builder(font)
                 .color(COLOR_NONE)
                 .color_hovered(COLOR_NONE)
                 .color_clicked(COLOR_NONE)
@@ -427,8 +452,7 @@ impl GuiSkins {
         };
 
         let checkbox_selected = {
-            let button_style = root_ui()
-                .style_builder()
+            let button_style = get_style_builder(font)
                 .background(checkbox_bg_selected.clone())
                 .background_hovered(checkbox_bg_selected_hovered.clone())
                 .background_clicked(checkbox_bg_clicked.clone())
@@ -443,8 +467,7 @@ impl GuiSkins {
         };
 
         let header_label = {
-            let label_style = root_ui()
-                .style_builder()
+            let label_style = get_style_builder(font)
                 .margin(theme.header_label_margins)
                 .text_color(theme.text_color)
                 .font_size(theme.header_font_size)
@@ -457,8 +480,7 @@ impl GuiSkins {
         };
 
         let warning_label = {
-            let label_style = root_ui()
-                .style_builder()
+            let label_style = get_style_builder(font)
                 .margin(theme.label_margins)
                 .text_color(theme.warning_text_color)
                 .font_size(theme.font_size)
@@ -471,8 +493,7 @@ impl GuiSkins {
         };
 
         let inactive_button = {
-            let button_style = root_ui()
-                .style_builder()
+            let button_style = get_style_builder(font)
                 .margin(sub_offsets(
                     theme.button_margins,
                     theme.button_bg_inactive.margins,
@@ -492,8 +513,7 @@ impl GuiSkins {
         };
 
         let label_button = {
-            let button_style = root_ui()
-                .style_builder()
+            let button_style = get_style_builder(font)
                 .background(blank_image.clone())
                 .background_hovered(blank_image.clone())
                 .background_clicked(blank_image.clone())
@@ -510,8 +530,7 @@ impl GuiSkins {
         };
 
         let label_button_highlighted = {
-            let button_style = root_ui()
-                .style_builder()
+            let button_style = get_style_builder(font)
                 .background(blank_image.clone())
                 .background_hovered(blank_image.clone())
                 .background_clicked(blank_image.clone())
@@ -528,8 +547,7 @@ impl GuiSkins {
         };
 
         let label_button_inactive = {
-            let button_style = root_ui()
-                .style_builder()
+            let button_style = get_style_builder(font)
                 .background(blank_image.clone())
                 .background_hovered(blank_image.clone())
                 .background_clicked(blank_image.clone())
@@ -546,8 +564,7 @@ impl GuiSkins {
         };
 
         let condensed_button = {
-            let button_style = root_ui()
-                .style_builder()
+            let button_style = get_style_builder(font)
                 .background(button_bg.clone())
                 .background_hovered(button_bg_hovered.clone())
                 .background_clicked(button_bg_clicked.clone())
@@ -564,8 +581,7 @@ impl GuiSkins {
         };
 
         let condensed_button_inactive = {
-            let button_style = root_ui()
-                .style_builder()
+            let button_style = get_style_builder(font)
                 .background(button_bg_inactive.clone())
                 .background_hovered(button_bg_inactive.clone())
                 .background_clicked(button_bg_inactive.clone())
@@ -582,8 +598,7 @@ impl GuiSkins {
         };
 
         let big_editbox = {
-            let editbox_style = root_ui()
-                .style_builder()
+            let editbox_style = get_style_builder(font)
                 .background(editbox_bg.clone())
                 .margin(RectOffset::new(10.0, 10.0, 0.0, -4.0))
                 .background_margin(RectOffset::new(4.0, 4.0, 4.0, 4.0))
@@ -600,8 +615,7 @@ impl GuiSkins {
         let mut custom = HashMap::new();
 
         let slider_fix = {
-            let editbox_style = root_ui()
-                .style_builder()
+            let editbox_style = get_style_builder(font)
                 .background(editbox_bg.clone())
                 .margin(RectOffset::new(10.0, 10.0, 0.0, -4.0))
                 .background_margin(RectOffset::new(4.0, 4.0, 4.0, 4.0))
@@ -609,15 +623,13 @@ impl GuiSkins {
                 .font_size(theme.font_size)
                 .build();
 
-            let scrollbar_style = root_ui()
-                .style_builder()
+            let scrollbar_style = get_style_builder(font)
                 .color(COLOR_NONE)
                 .color_hovered(COLOR_NONE)
                 .color_clicked(COLOR_NONE)
                 .build();
 
-            let scrollbar_handle_style = root_ui()
-                .style_builder()
+            let scrollbar_handle_style = get_style_builder(font)
                 .color(COLOR_NONE)
                 .color_hovered(COLOR_NONE)
                 .color_clicked(COLOR_NONE)
@@ -632,15 +644,13 @@ impl GuiSkins {
         };
 
         let custom_button = {
-            let group_style = root_ui()
-                .style_builder()
+            let group_style = get_style_builder(font)
                 .color(COLOR_NONE)
                 .color_hovered(COLOR_NONE)
                 .color_clicked(COLOR_NONE)
                 .build();
 
-            let button_style = root_ui()
-                .style_builder()
+            let button_style = get_style_builder(font)
                 .color(COLOR_NONE)
                 .color_hovered(COLOR_NONE)
                 .color_clicked(COLOR_NONE)
@@ -0,0 +1,131 @@
+use crate::gui::inventory::get_item_label;
+use crate::gui::*;
+
+enum TradeAction {
+    Buy(String),
+    Sell(String),
+    BuyBack(String),
+    Close,
+}
+
+fn draw_trade_entry(ui: &mut Ui, params: &ItemParams, price: u32, label: &str) -> bool {
+    let gui_skins = storage::get::<GuiSkins>();
+
+    let mut res = false;
+    widgets::Group::new(hash!(), vec2(350.0, 30.0)).ui(ui, |ui| {
+        ui.label(vec2(0.0, 0.0), &get_item_label(params));
+        ui.label(
+            vec2(200.0, 0.0),
+            &tr_args("{price} cr", &[("price", &price)]),
+        );
+
+        ui.push_skin(&gui_skins.condensed_button);
+        res = ui.button(vec2(270.0, 0.0), tr(label).as_str());
+        ui.pop_skin();
+    });
+    res
+}
+
+// This draws the stock of the vendor that the player is trading with, the items that have been
+// sold to it, and the items that the player can sell
+pub fn draw_trade_window() {
+    if let Some(mut player) = get_player_actor() {
+        let vendor_id = match player.current_vendor.clone() {
+            Some(vendor_id) => vendor_id,
+            None => return,
+        };
+
+        let gui_skins = storage::get::<GuiSkins>();
+        let resources = storage::get::<Resources>();
+        let params = match resources.vendors.get(&vendor_id) {
+            Some(params) => params,
+            None => {
+                player.current_vendor = None;
+                return;
+            }
+        };
+
+        let price_modifier = params.get_actor_price_modifier(&player);
+        let context = LootContext::from_actor(&player);
+
+        let mut game_state = scene::get_node(player.game_state);
+        let vendor = game_state.get_vendor(&vendor_id, &context);
+
+        let size = vec2(400.0, 500.0);
+        let position = vec2(400.0, 100.0);
+
+        let mut action = None;
+
+        root_ui().push_skin(&gui_skins.default);
+
+        WindowBuilder::new(hash!(), size)
+            .with_pos(position, false)
+            .with_title("Trade")
+            .build(&mut *root_ui(), |ui| {
+                ui.label(
+                    None,
+                    &tr_args("Vendor credits: {credits}", &[("credits", &vendor.credits)]),
+                );
+                for item in &vendor.items {
+                    let price = params.get_buy_price(item, price_modifier);
+                    if draw_trade_entry(ui, item, price, "Buy") {
+                        action = Some(TradeAction::Buy(item.id.clone()));
+                    }
+                }
+
+                if !vendor.buyback.is_empty() {
+                    ui.separator();
+                    ui.label(None, &tr("Buy back"));
+                    for buyback in &vendor.buyback {
+                        if draw_trade_entry(ui, &buyback.item, buyback.price, "Buy") {
+                            action = Some(TradeAction::BuyBack(buyback.item.id.clone()));
+                        }
+                    }
+                }
+
+                ui.separator();
+                ui.label(
+                    None,
+                    &tr_args(
+                        "Your credits: {credits}",
+                        &[("credits", &player.inventory.credits)],
+                    ),
+                );
+
+                // Equipped items and quest items can not be sold
+                for entry in &player.inventory.items {
+                    if entry.equipped_to == EquipmentSlot::None && !entry.params.is_quest_item {
+                        let price = params.get_sell_price(&entry.params, price_modifier);
+                        if draw_trade_entry(ui, &entry.params, price, "Sell") {
+                            action = Some(TradeAction::Sell(entry.params.id.clone()));
+                        }
+                    }
+                }
+
+                ui.separator();
+                if ui.button(None, tr("Close").as_str()) {
+                    action = Some(TradeAction::Close);
+                }
+            });
+
+        root_ui().pop_skin();
+
+        match action {
+            Some(TradeAction::Buy(item_id)) => {
+                vendor.buy(params, &item_id, &mut player.inventory, price_modifier);
+            }
+            Some(TradeAction::Sell(item_id)) => {
+                if vendor.sell(params, &item_id, &mut player.inventory, price_modifier) {
+                    player.assign_to_hotbar(&item_id, None);
+                }
+            }
+            Some(TradeAction::BuyBack(item_id)) => {
+                vendor.buy_back(&item_id, &mut player.inventory);
+            }
+            Some(TradeAction::Close) => {
+                player.current_vendor = None;
+            }
+            None => {}
+        }
+    }
+}
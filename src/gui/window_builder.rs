@@ -19,9 +19,10 @@ impl WindowBuilder {
         }
     }
 
+    // The title is translated to the current locale
     pub fn with_title(self, title: &str) -> Self {
         WindowBuilder {
-            title: Some(title.to_string()),
+            title: Some(tr(title)),
             ..self
         }
     }
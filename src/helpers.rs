@@ -8,7 +8,7 @@ use macroquad::prelude::*;
 
 // Used in serde attributes to skip serialization of bools that are false
 pub fn is_false(value: &bool) -> bool {
-    *value
+    !*value
 }
 
 pub fn sort_by_distance(position: Vec2, a: &Vec2, b: &Vec2) -> Ordering {
@@ -40,7 +40,25 @@ pub fn apply_input(_player_id: &str, node: &mut RefMut<Actor>) {
         node.controller.is_sprint_locked = !node.controller.is_sprint_locked;
     }
 
-    if node.controller.should_use_weapon || node.controller.should_use_selected_ability {
+    let hotbar_keys = [
+        KeyCode::Key1,
+        KeyCode::Key2,
+        KeyCode::Key3,
+        KeyCode::Key4,
+        KeyCode::Key5,
+    ];
+    for (i, key) in hotbar_keys.iter().enumerate() {
+        if is_key_pressed(*key) {
+            if let Some(item_id) = node.hotbar.get(i).cloned().flatten() {
+                node.controller.use_item = Some(item_id);
+            }
+        }
+    }
+
+    if node.controller.should_use_weapon
+        || node.controller.should_use_selected_ability
+        || node.controller.use_item.is_some()
+    {
         node.controller.aim_direction = mouse_position.sub(node.body.position).normalize_or_zero();
     } else {
         node.controller.aim_direction = node.controller.move_direction;
@@ -52,6 +70,11 @@ pub fn apply_input(_player_id: &str, node: &mut RefMut<Actor>) {
         node.controller.should_sprint = is_key_down(KeyCode::LeftShift);
     }
 
+    // This is reset when the dash has been consumed, in the actor's fixed update
+    if is_key_pressed(KeyCode::Space) {
+        node.controller.should_dash = true;
+    }
+
     node.controller.should_start_interaction = is_key_released(KeyCode::F);
 
     node.controller.should_pick_up_items = is_key_down(KeyCode::R);
@@ -73,11 +96,13 @@ pub fn apply_input(_player_id: &str, node: &mut RefMut<Actor>) {
 
     if is_key_released(KeyCode::Escape) {
         if node.current_dialogue.is_some()
+            || node.current_vendor.is_some()
             || game_state.gui_state.should_draw_inventory_window
             || game_state.gui_state.should_draw_character_window
             || game_state.gui_state.should_draw_game_menu
         {
             node.current_dialogue = None;
+            node.current_vendor = None;
             game_state.gui_state.should_draw_inventory_window = false;
             game_state.gui_state.should_draw_character_window = false;
             game_state.gui_state.should_draw_game_menu = false;
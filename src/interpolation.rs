@@ -0,0 +1,264 @@
+use std::fmt::Display;
+
+use crate::prelude::*;
+
+// The values that placeholders in dialogue, mission and GUI text can refer to. Placeholders that
+// refer to a value that is not available in the context are left as they are.
+#[derive(Default)]
+pub struct TextContext<'a> {
+    pub player_name: Option<&'a str>,
+    pub player_level: Option<u32>,
+    // The credits of the player
+    pub credits: Option<u32>,
+    // The active and completed missions of the player
+    pub missions: Vec<&'a Mission>,
+    // The name of the actor that holds the dialogue
+    pub actor_name: Option<&'a str>,
+    pub variables: Option<&'a HashMap<String, i32>>,
+    // The mission that the text belongs to
+    pub mission: Option<&'a Mission>,
+    // Named values, like the ones passed to `tr_args`, which take precedence over the other values
+    pub args: &'a [(&'a str, &'a dyn Display)],
+}
+
+enum TextValue {
+    Text(String),
+    Number(i64),
+}
+
+impl<'a> TextContext<'a> {
+    // Returns a context with the values of `player`
+    pub fn from_player(player: &'a Actor) -> Self {
+        TextContext {
+            player_name: Some(&player.name),
+            player_level: Some(player.level),
+            credits: Some(player.inventory.credits),
+            missions: player
+                .active_missions
+                .iter()
+                .chain(player.completed_missions.iter())
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn get_mission_value(mission: &Mission, key: &str) -> Option<TextValue> {
+        match key {
+            "completed" => {
+                let cnt = mission
+                    .objectives
+                    .iter()
+                    .filter(|(_, is_completed)| *is_completed)
+                    .count();
+                Some(TextValue::Number(cnt as i64))
+            }
+            "total" => Some(TextValue::Number(mission.objectives.len() as i64)),
+            _ => None,
+        }
+    }
+
+    fn get_value(&self, key: &str) -> Option<TextValue> {
+        if let Some((_, value)) = self.args.iter().find(|(name, _)| *name == key) {
+            // Arguments that are integers can be given plural forms
+            let text = value.to_string();
+            return match text.parse::<i64>() {
+                Ok(value) if value.to_string() == text => Some(TextValue::Number(value)),
+                _ => Some(TextValue::Text(text)),
+            };
+        }
+
+        if let Some(variable) = key
+            .strip_prefix("flag:")
+            .or_else(|| key.strip_prefix("var:"))
+        {
+            // Variables that are not set, like flags that have not been set, are zero
+            let value = self
+                .variables
+                .and_then(|variables| variables.get(variable))
+                .copied()
+                .unwrap_or(0);
+            return Some(TextValue::Number(value as i64));
+        }
+
+        if let Some(key) = key.strip_prefix("mission.") {
+            return self
+                .mission
+                .and_then(|mission| Self::get_mission_value(mission, key));
+        }
+
+        if let Some(key) = key.strip_prefix("mission:") {
+            let (mission_id, key) = key.split_once('.')?;
+            return self
+                .missions
+                .iter()
+                .find(|mission| mission.id == mission_id)
+                .and_then(|mission| Self::get_mission_value(mission, key));
+        }
+
+        match key {
+            "actor.name" => self.actor_name.map(|name| TextValue::Text(tr(name))),
+            "player.name" => self
+                .player_name
+                .map(|name| TextValue::Text(name.to_string())),
+            "player.level" => self
+                .player_level
+                .map(|level| TextValue::Number(level as i64)),
+            "credits" => self
+                .credits
+                .map(|credits| TextValue::Number(credits as i64)),
+            _ => None,
+        }
+    }
+}
+
+// This resolves a placeholder, without the braces, or returns `None` if it can not be resolved.
+// A placeholder can have plural forms, separated by `|`, like `credits|# credit|# credits`, and
+// the first form is used if the value is `1`, and the second otherwise, with `#` replaced by the
+// value.
+fn resolve_placeholder(placeholder: &str, context: &TextContext) -> Option<String> {
+    let mut parts = placeholder.split('|');
+    let key = parts.next()?.trim();
+    let forms: Vec<&str> = parts.collect();
+
+    let value = context.get_value(key)?;
+    match value {
+        TextValue::Text(text) => Some(text),
+        TextValue::Number(value) => {
+            let form = match forms.len() {
+                0 => return Some(value.to_string()),
+                1 => forms[0],
+                _ if value == 1 => forms[0],
+                _ => forms[1],
+            };
+            Some(form.replace('#', &value.to_string()))
+        }
+    }
+}
+
+// This replaces the placeholders in `text`, like `{player.name}`, with values from `context`. It
+// should be used on text after it has been translated, so that the placeholders can be moved
+// around, or given other plural forms, in translations.
+pub fn interpolate(text: &str, context: &TextContext) -> String {
+    let mut res = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        res.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match rest.find('}') {
+            Some(end) => {
+                let placeholder = &rest[1..end];
+                match resolve_placeholder(placeholder, context) {
+                    Some(value) => res.push_str(&value),
+                    None => res.push_str(&rest[..=end]),
+                }
+                rest = &rest[end + 1..];
+            }
+            None => break,
+        }
+    }
+    res.push_str(rest);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolate() {
+        let mut variables = HashMap::new();
+        variables.insert("returned_gun".to_string(), 1);
+        variables.insert("thugs_killed".to_string(), 2);
+
+        let mut mission = Mission::new(MissionParams {
+            id: "kill_thugs".to_string(),
+            objectives: vec![
+                MissionObjective::Kill {
+                    actor_id: "thug_1".to_string(),
+                },
+                MissionObjective::Kill {
+                    actor_id: "thug_2".to_string(),
+                },
+            ],
+            ..Default::default()
+        });
+        mission.objectives[0].1 = true;
+
+        let context = TextContext {
+            player_name: Some("Jane"),
+            credits: Some(1),
+            actor_name: Some("Guard"),
+            variables: Some(&variables),
+            mission: Some(&mission),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            interpolate("Killed {mission.completed}/{mission.total}", &context),
+            "Killed 1/2"
+        );
+        assert_eq!(
+            interpolate("{var:thugs_killed|# thug|# thugs} down", &context),
+            "2 thugs down"
+        );
+        assert_eq!(
+            interpolate("{mission.completed|One down|# down}", &context),
+            "One down"
+        );
+        assert_eq!(
+            interpolate("{flag:returned_gun|Thanks!|Where is it?}", &context),
+            "Thanks!"
+        );
+        assert_eq!(
+            interpolate("{flag:found_gun|Thanks!|Where is it?}", &context),
+            "Where is it?"
+        );
+        assert_eq!(
+            interpolate("{actor.name}: Hello {player.name}", &context),
+            "Guard: Hello Jane"
+        );
+        assert_eq!(
+            interpolate("You have {credits|# credit|# credits}", &context),
+            "You have 1 credit"
+        );
+        // Placeholders that can not be resolved are left as they are
+        assert_eq!(
+            interpolate("Level {player.level}, {unknown} {", &context),
+            "Level {player.level}, {unknown} {"
+        );
+    }
+
+    #[test]
+    fn test_interpolate_args() {
+        let missions = vec![Mission::new(MissionParams {
+            id: "find_gun".to_string(),
+            objectives: vec![MissionObjective::Kill {
+                actor_id: "thug".to_string(),
+            }],
+            ..Default::default()
+        })];
+
+        let args: &[(&str, &dyn Display)] = &[("count", &3), ("item", &"Pistol"), ("credits", &5)];
+        let context = TextContext {
+            credits: Some(1),
+            missions: missions.iter().collect(),
+            args,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            interpolate("{count|# pistol|# pistols}", &context),
+            "3 pistols"
+        );
+        assert_eq!(interpolate("{item}: {count}", &context), "Pistol: 3");
+        // Arguments take precedence over the other values
+        assert_eq!(interpolate("{credits} cr", &context), "5 cr");
+        assert_eq!(
+            interpolate(
+                "{mission:find_gun.completed}/{mission:find_gun.total}",
+                &context
+            ),
+            "0/1"
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AffixKind {
+    Prefix,
+    Suffix,
+}
+
+// A rarity that can be rolled when an item is generated, which determines how many affixes it
+// will be given
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RarityParams {
+    pub id: String,
+    pub name: String,
+    // The relative chance of this rarity being rolled
+    pub weight: f32,
+    #[serde(default)]
+    pub prefixes: u32,
+    #[serde(default)]
+    pub suffixes: u32,
+}
+
+// A modifier with a value that is rolled between `min` and `max` when an affix is applied
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AffixModifierParams {
+    pub stat: String,
+    #[serde(default)]
+    pub kind: StatModifierKind,
+    pub min: f32,
+    pub max: f32,
+    // If this is set, rolled values will be rounded to a multiple of it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<f32>,
+}
+
+impl AffixModifierParams {
+    pub fn roll(&self, rng: &mut LootRng) -> StatModifier {
+        let mut value = if self.max > self.min {
+            rng.gen_range_f32(self.min, self.max)
+        } else {
+            self.min
+        };
+
+        if let Some(step) = self.step.filter(|step| *step > 0.0) {
+            value = ((value / step).round() * step).clamp(self.min, self.max);
+        }
+
+        StatModifier::new(&self.stat, self.kind, value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AffixParams {
+    pub id: String,
+    pub kind: AffixKind,
+    // This is added before the name of the item, for prefixes, or after it, for suffixes
+    pub name: String,
+    #[serde(default = "AffixParams::default_weight")]
+    pub weight: f32,
+    // If not empty, the affix can only be rolled for items of these kinds
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub item_kinds: Vec<ItemKind>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modifiers: Vec<AffixModifierParams>,
+}
+
+impl AffixParams {
+    pub fn default_weight() -> f32 {
+        1.0
+    }
+
+    pub fn can_apply_to(&self, params: &ItemParams) -> bool {
+        self.item_kinds.is_empty() || self.item_kinds.contains(&params.kind)
+    }
+
+    pub fn roll(&self, rng: &mut LootRng) -> ItemAffix {
+        ItemAffix {
+            id: self.id.clone(),
+            kind: self.kind,
+            name: self.name.clone(),
+            modifiers: self
+                .modifiers
+                .iter()
+                .map(|params| params.roll(rng))
+                .collect(),
+        }
+    }
+}
+
+// An affix that has been applied to an item instance, with its rolled values
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemAffix {
+    pub id: String,
+    pub kind: AffixKind,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modifiers: Vec<StatModifier>,
+}
+
+// The rarities and affixes that are used when generating items. These are defined in the
+// optional `affixes.json` data file.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AffixTables {
+    #[serde(default)]
+    pub rarities: Vec<RarityParams>,
+    #[serde(default)]
+    pub affixes: Vec<AffixParams>,
+}
+
+impl AffixTables {
+    // This replaces any rarities and affixes that have the same id as one in `other` and adds
+    // the rest
+    pub fn extend(&mut self, other: AffixTables) {
+        for rarity in other.rarities {
+            if let Some(existing) = self.rarities.iter_mut().find(|r| r.id == rarity.id) {
+                *existing = rarity;
+            } else {
+                self.rarities.push(rarity);
+            }
+        }
+
+        for affix in other.affixes {
+            if let Some(existing) = self.affixes.iter_mut().find(|a| a.id == affix.id) {
+                *existing = affix;
+            } else {
+                self.affixes.push(affix);
+            }
+        }
+    }
+
+    pub fn get_rarity(&self, rarity_id: &str) -> Option<&RarityParams> {
+        self.rarities.iter().find(|rarity| rarity.id == rarity_id)
+    }
+
+    // Only equippable items that can not be stacked, and that are not quest items, will be
+    // given a rarity and affixes when generated
+    pub fn can_generate(params: &ItemParams) -> bool {
+        !params.is_quest_item
+            && params.max_stack <= 1
+            && matches!(
+                params.kind,
+                ItemKind::OneHandedWeapon
+                    | ItemKind::TwoHandedWeapon
+                    | ItemKind::Armor
+                    | ItemKind::Accessory
+            )
+    }
+
+    // This creates a new instance of the item prototype `params` and, if it can be generated,
+    // rolls a rarity, unless the prototype has one, and the affixes of that rarity
+    pub fn generate(&self, params: &ItemParams) -> ItemParams {
+        self.generate_with_rng(params, &mut LootRng::from_global())
+    }
+
+    pub fn generate_with_rng(&self, params: &ItemParams, rng: &mut LootRng) -> ItemParams {
+        let mut instance = params.instantiate();
+        if !Self::can_generate(params) {
+            return instance;
+        }
+
+        let rarity = match &params.rarity {
+            Some(rarity_id) => self.get_rarity(rarity_id),
+            None => rng.choose_weighted(&self.rarities, |rarity| rarity.weight),
+        };
+
+        if let Some(rarity) = rarity {
+            instance.rarity = Some(rarity.id.clone());
+            instance.affixes = Vec::new();
+            self.roll_affixes(&mut instance, AffixKind::Prefix, rarity.prefixes, rng);
+            self.roll_affixes(&mut instance, AffixKind::Suffix, rarity.suffixes, rng);
+            instance.name = instance.get_affixed_name();
+        }
+
+        instance
+    }
+
+    // The same affix will not be rolled more than once for an item
+    fn roll_affixes(&self, params: &mut ItemParams, kind: AffixKind, cnt: u32, rng: &mut LootRng) {
+        let mut available: Vec<&AffixParams> = self
+            .affixes
+            .iter()
+            .filter(|affix| affix.kind == kind && affix.can_apply_to(params))
+            .collect();
+
+        for _ in 0..cnt {
+            let i = match rng.choose_weighted_index(&available, |affix| affix.weight) {
+                Some(i) => i,
+                None => break,
+            };
+            let affix = available.remove(i);
+            params.affixes.push(affix.roll(rng));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_tables() -> AffixTables {
+        let json = r#"{
+            "rarities": [
+                { "id": "rare", "name": "Rare", "weight": 1.0, "prefixes": 1, "suffixes": 2 }
+            ],
+            "affixes": [
+                {
+                    "id": "sturdy",
+                    "kind": "prefix",
+                    "name": "Sturdy",
+                    "item_kinds": ["armor"],
+                    "modifiers": [{ "stat": "armor", "min": 1.0, "max": 4.0, "step": 1.0 }]
+                },
+                {
+                    "id": "sharp",
+                    "kind": "prefix",
+                    "name": "Sharp",
+                    "item_kinds": ["one_handed_weapon"]
+                },
+                {
+                    "id": "of_the_bear",
+                    "kind": "suffix",
+                    "name": "of the Bear",
+                    "modifiers": [{ "stat": "strength", "min": 2.0, "max": 2.0 }]
+                }
+            ]
+        }"#;
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_generate() {
+        let tables = get_tables();
+        let prototype = ItemParams {
+            id: "vest".to_string(),
+            name: "Vest".to_string(),
+            kind: ItemKind::Armor,
+            ..Default::default()
+        };
+
+        let res = tables.generate_with_rng(&prototype, &mut LootRng::new(1));
+        assert_eq!(res.prototype_id, Some("vest".to_string()));
+        assert_eq!(res.rarity, Some("rare".to_string()));
+        // There is only one suffix available, so the second will not be rolled
+        assert_eq!(res.affixes.len(), 2);
+        assert_eq!(res.name, "Sturdy Vest of the Bear");
+
+        let armor = &res.affixes[0].modifiers[0];
+        assert!(armor.value >= 1.0 && armor.value <= 4.0);
+        assert!(armor.value.fract().abs() < f32::EPSILON);
+
+        let modifiers = res.get_modifiers();
+        assert_eq!(modifiers.len(), 2);
+        assert!(modifiers
+            .iter()
+            .any(|modifier| modifier.stat == "strength" && modifier.value > 1.0));
+
+        let json = serde_json::to_string(&res).unwrap();
+        let loaded: ItemParams = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.affixes, res.affixes);
+        assert_eq!(loaded.rarity, res.rarity);
+    }
+
+    #[test]
+    fn test_generate_skips_stackable_items() {
+        let tables = get_tables();
+        let prototype = ItemParams {
+            id: "ammo".to_string(),
+            kind: ItemKind::Armor,
+            max_stack: 10,
+            ..Default::default()
+        };
+
+        let res = tables.generate(&prototype);
+        assert_eq!(res.rarity, None);
+        assert!(res.affixes.is_empty());
+    }
+}
@@ -0,0 +1,90 @@
+use crate::prelude::*;
+
+// Limits on the number of stacks an inventory can hold, in addition to the weight limit set by an
+// actor's `carry_capacity`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InventoryCapacity {
+    Unlimited,
+    // Every stack takes up one slot, regardless of size
+    Slots { slots: u32 },
+    // Every stack takes up its `grid_size` in a grid of `width` by `height` cells
+    Grid { width: u32, height: u32 },
+}
+
+impl Default for InventoryCapacity {
+    fn default() -> Self {
+        InventoryCapacity::Unlimited
+    }
+}
+
+impl InventoryCapacity {
+    pub fn is_unlimited(&self) -> bool {
+        *self == InventoryCapacity::Unlimited
+    }
+
+    // Returns `true` if stacks of the given sizes will fit. Items are placed in order, in the
+    // first free cell, so grid capacity is checked the same way every time.
+    pub fn fits(&self, sizes: &[UVec2]) -> bool {
+        match *self {
+            InventoryCapacity::Unlimited => true,
+            InventoryCapacity::Slots { slots } => sizes.len() as u32 <= slots,
+            InventoryCapacity::Grid { width, height } => {
+                let mut grid = vec![false; (width * height) as usize];
+                sizes
+                    .iter()
+                    .all(|size| place_in_grid(&mut grid, width, height, *size))
+            }
+        }
+    }
+}
+
+fn place_in_grid(grid: &mut [bool], width: u32, height: u32, size: UVec2) -> bool {
+    if size.x > width || size.y > height {
+        return false;
+    }
+
+    for y in 0..=height - size.y {
+        for x in 0..=width - size.x {
+            let is_free = (y..y + size.y)
+                .all(|cy| (x..x + size.x).all(|cx| !grid[(cy * width + cx) as usize]));
+            if is_free {
+                for cy in y..y + size.y {
+                    for cx in x..x + size.x {
+                        grid[(cy * width + cx) as usize] = true;
+                    }
+                }
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_capacity() {
+        let capacity = InventoryCapacity::Slots { slots: 2 };
+        assert!(capacity.fits(&[uvec2(1, 1), uvec2(3, 3)]));
+        assert!(!capacity.fits(&[uvec2(1, 1), uvec2(1, 1), uvec2(1, 1)]));
+    }
+
+    #[test]
+    fn test_grid_capacity() {
+        let capacity = InventoryCapacity::Grid {
+            width: 4,
+            height: 2,
+        };
+        assert!(capacity.fits(&[uvec2(2, 2), uvec2(1, 2), uvec2(1, 1), uvec2(1, 1)]));
+        assert!(!capacity.fits(&[uvec2(2, 2), uvec2(2, 2), uvec2(1, 1)]));
+        assert!(!capacity.fits(&[uvec2(5, 1)]));
+
+        let json = r#"{ "type": "grid", "width": 4, "height": 2 }"#;
+        let res: InventoryCapacity = serde_json::from_str(json).unwrap();
+        assert_eq!(res, capacity);
+    }
+}
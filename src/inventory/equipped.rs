@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -6,6 +8,8 @@ pub enum EquipmentSlot {
     MainHand,
     OffHand,
     BothHands,
+    // A slot for armor or accessories, by the id of its `EquipmentSlotParams`
+    Other(String),
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -14,4 +18,123 @@ pub struct EquippedItems {
     pub main_hand: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub off_hand: Option<String>,
+    // The ids of the items equipped to armor and accessory slots, by slot id
+    #[serde(flatten)]
+    pub slots: HashMap<String, String>,
+}
+
+impl EquippedItems {
+    pub fn get_slot_of(&self, item_id: &str) -> Option<String> {
+        self.slots
+            .iter()
+            .find(|(_, equipped_id)| *equipped_id == item_id)
+            .map(|(slot_id, _)| slot_id.clone())
+    }
+}
+
+// A slot that armor and accessories can be equipped to. Slots are defined in the optional
+// `equipment_slots.json` data file and any slots defined there will be added to the defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EquipmentSlotParams {
+    pub id: String,
+    pub name: String,
+    // The slot types of the items that can be equipped to this slot, so that, for example, two
+    // ring slots can both accept items with the `ring` slot type. If this is empty, only items
+    // with a slot type that is the same as the id of the slot can be equipped to it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accepts: Vec<String>,
+    // The order that the sprites of equipped items are drawn over the actor, from lowest to
+    // highest
+    #[serde(default)]
+    pub layer: i32,
+}
+
+impl EquipmentSlotParams {
+    pub fn new(id: &str, name: &str, accepts: &[&str], layer: i32) -> Self {
+        EquipmentSlotParams {
+            id: id.to_string(),
+            name: name.to_string(),
+            accepts: accepts.iter().map(|slot| slot.to_string()).collect(),
+            layer,
+        }
+    }
+
+    pub fn accepts(&self, slot: &str) -> bool {
+        if self.accepts.is_empty() {
+            self.id == slot
+        } else {
+            self.accepts.iter().any(|accepted| accepted == slot)
+        }
+    }
+}
+
+pub fn default_equipment_slots() -> Vec<EquipmentSlotParams> {
+    vec![
+        EquipmentSlotParams::new("feet", "Feet", &[], 0),
+        EquipmentSlotParams::new("legs", "Legs", &[], 1),
+        EquipmentSlotParams::new("body", "Body", &[], 2),
+        EquipmentSlotParams::new("hands", "Hands", &[], 3),
+        EquipmentSlotParams::new("amulet", "Amulet", &[], 4),
+        EquipmentSlotParams::new("head", "Head", &[], 5),
+        EquipmentSlotParams::new("left_ring", "Left Ring", &["ring"], 6),
+        EquipmentSlotParams::new("right_ring", "Right Ring", &["ring"], 6),
+    ]
+}
+
+// This replaces any slots that have the same id as one in `other` and adds the rest
+pub fn extend_equipment_slots(
+    slots: &mut Vec<EquipmentSlotParams>,
+    other: Vec<EquipmentSlotParams>,
+) {
+    for slot in other {
+        if let Some(existing) = slots.iter_mut().find(|existing| existing.id == slot.id) {
+            *existing = slot;
+        } else {
+            slots.push(slot);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extend_equipment_slots() {
+        let mut slots = default_equipment_slots();
+        let json = r#"[
+            { "id": "head", "name": "Helmet", "layer": 10 },
+            { "id": "back", "name": "Back", "accepts": ["cloak", "backpack"] }
+        ]"#;
+        let other: Vec<EquipmentSlotParams> = serde_json::from_str(json).unwrap();
+        extend_equipment_slots(&mut slots, other);
+
+        assert_eq!(slots.len(), default_equipment_slots().len() + 1);
+        let head = slots.iter().find(|slot| slot.id == "head").unwrap();
+        assert_eq!(head.name, "Helmet");
+        assert!(head.accepts("head"));
+
+        let back = slots.last().unwrap();
+        assert!(back.accepts("backpack"));
+        assert!(!back.accepts("back"));
+
+        let ring = slots.iter().find(|slot| slot.id == "left_ring").unwrap();
+        assert!(ring.accepts("ring"));
+    }
+
+    #[test]
+    fn test_equipped_items_from_json() {
+        let json = r#"{ "main_hand": "pistol", "head": "helmet", "left_ring": "ring" }"#;
+        let equipped_items: EquippedItems = serde_json::from_str(json).unwrap();
+        assert_eq!(equipped_items.main_hand, Some("pistol".to_string()));
+        assert_eq!(equipped_items.slots.len(), 2);
+        assert_eq!(
+            equipped_items.get_slot_of("ring"),
+            Some("left_ring".to_string())
+        );
+
+        let json = serde_json::to_string(&equipped_items).unwrap();
+        let res: EquippedItems = serde_json::from_str(&json).unwrap();
+        assert_eq!(res.slots, equipped_items.slots);
+    }
 }
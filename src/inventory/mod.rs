@@ -1,13 +1,25 @@
+mod affixes;
+mod capacity;
 mod equipped;
 
 use crate::prelude::*;
-pub use equipped::{EquipmentSlot, EquippedItems};
+pub use affixes::{
+    AffixKind, AffixModifierParams, AffixParams, AffixTables, ItemAffix, RarityParams,
+};
+pub use capacity::InventoryCapacity;
+pub use equipped::{
+    default_equipment_slots, extend_equipment_slots, EquipmentSlot, EquipmentSlotParams,
+    EquippedItems,
+};
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct InventoryParams {
+    #[serde(default)]
     pub items: Vec<String>,
     #[serde(default)]
     pub credits: u32,
+    #[serde(default, skip_serializing_if = "InventoryCapacity::is_unlimited")]
+    pub capacity: InventoryCapacity,
 }
 
 #[derive(Clone)]
@@ -41,6 +53,7 @@ impl InventoryEntry {
 pub struct Inventory {
     pub items: Vec<InventoryEntry>,
     pub credits: u32,
+    pub capacity: InventoryCapacity,
 }
 
 impl Inventory {
@@ -50,23 +63,19 @@ impl Inventory {
         Self::default()
     }
 
+    // Prototype items are added regardless of capacity, but will still be stacked
     pub fn from_prototypes(params: &InventoryParams) -> Self {
         let resources = storage::get::<Resources>();
-        Inventory {
-            items: params
-                .items
-                .clone()
-                .into_iter()
-                .map(|id| {
-                    let params = resources.items.get(&id).cloned().unwrap();
-                    InventoryEntry::new(ItemParams {
-                        id: generate_id(),
-                        ..params
-                    })
-                })
-                .collect(),
+        let mut inventory = Inventory {
+            items: Vec::new(),
             credits: params.credits,
+            capacity: params.capacity,
+        };
+        for id in &params.items {
+            let params = resources.items.get(id).cloned().unwrap();
+            inventory.add(params.instantiate(), false);
         }
+        inventory
     }
 
     pub fn from_saved(params: &InventoryParams, item_params: &[ItemParams]) -> Self {
@@ -82,6 +91,7 @@ impl Inventory {
         Inventory {
             items,
             credits: params.credits,
+            capacity: params.capacity,
         }
     }
 
@@ -98,25 +108,294 @@ impl Inventory {
             .collect()
     }
 
-    pub fn pick_up(&mut self, item: RefMut<Item>) {
-        self.items.push(InventoryEntry::new(ItemParams {
-            position: None,
-            ..item.to_params()
-        }));
+    // Any part of the stack that doesn't fit in the inventory is left on the ground. Returns
+    // `true` if the whole stack was picked up.
+    pub fn pick_up(&mut self, mut item: RefMut<Item>) -> bool {
+        if let Some(remainder) = self.add_item(item.to_params()) {
+            item.id = remainder.id;
+            item.quantity = remainder.quantity;
+            return false;
+        }
         item.delete();
+        true
+    }
+
+    // This adds an item to any stacks it can be stacked with, before adding new stacks, as long as
+    // there is room for them. Any part of the stack that doesn't fit is returned.
+    pub fn add_item(&mut self, item_params: ItemParams) -> Option<ItemParams> {
+        self.add(item_params, true)
     }
 
-    pub fn add_item(&mut self, item_params: ItemParams) {
-        self.items.push(InventoryEntry::new(ItemParams {
+    pub(crate) fn add(
+        &mut self,
+        item_params: ItemParams,
+        check_capacity: bool,
+    ) -> Option<ItemParams> {
+        let mut params = ItemParams {
             position: None,
             ..item_params
-        }));
+        };
+
+        for entry in &mut self.items {
+            if params.quantity == 0 {
+                return None;
+            }
+            if entry.params.can_stack_with(&params) && entry.params.quantity < params.max_stack {
+                let quantity = params
+                    .quantity
+                    .min(params.max_stack - entry.params.quantity);
+                entry.params.quantity += quantity;
+                params.quantity -= quantity;
+            }
+        }
+
+        while params.quantity > 0 {
+            if check_capacity && !self.has_room_for(&params) {
+                return Some(params);
+            }
+
+            let quantity = params.quantity.min(params.max_stack.max(1));
+            self.items.push(InventoryEntry::new(ItemParams {
+                quantity,
+                ..params.clone()
+            }));
+
+            params.quantity -= quantity;
+            params.id = generate_id();
+        }
+
+        None
+    }
+
+    // Returns `true` if there is room for a new stack of the item
+    pub fn has_room_for(&self, item_params: &ItemParams) -> bool {
+        let mut sizes: Vec<UVec2> = self
+            .items
+            .iter()
+            .map(|entry| entry.params.get_grid_size())
+            .collect();
+        sizes.push(item_params.get_grid_size());
+        self.capacity.fits(&sizes)
+    }
+
+    // This splits `quantity` items off a stack, into a new stack, and returns the id of the new
+    // stack, if there was room for it
+    pub fn split(&mut self, item_id: &str, quantity: u32) -> Option<String> {
+        let i = self
+            .items
+            .iter()
+            .position(|entry| entry.params.id == item_id)?;
+        let params = self.items[i].params.clone();
+        if quantity == 0 || quantity >= params.quantity || !self.has_room_for(&params) {
+            return None;
+        }
+
+        let id = generate_id();
+        self.items[i].params.quantity -= quantity;
+        self.items.insert(
+            i + 1,
+            InventoryEntry::new(ItemParams {
+                id: id.clone(),
+                quantity,
+                ..params
+            }),
+        );
+        Some(id)
+    }
+
+    // This moves as many items as possible from one stack to another and removes the first stack,
+    // if it is emptied. Returns `true` if any items were moved.
+    pub fn merge(&mut self, from_id: &str, to_id: &str) -> bool {
+        let from = self
+            .items
+            .iter()
+            .position(|entry| entry.params.id == from_id);
+        let to = self.items.iter().position(|entry| entry.params.id == to_id);
+        if let (Some(from), Some(to)) = (from, to) {
+            let (from_params, to_params) = (&self.items[from].params, &self.items[to].params);
+            if from == to
+                || self.items[from].equipped_to != EquipmentSlot::None
+                || !from_params.can_stack_with(to_params)
+                || to_params.quantity >= to_params.max_stack
+            {
+                return false;
+            }
+
+            let quantity = from_params
+                .quantity
+                .min(to_params.max_stack - to_params.quantity);
+            self.items[to].params.quantity += quantity;
+            self.items[from].params.quantity -= quantity;
+            if self.items[from].params.quantity == 0 {
+                self.items.remove(from);
+            }
+            return true;
+        }
+        false
+    }
+
+    // This merges all stacks that can be stacked with each other, into the first ones
+    pub fn stack_all(&mut self) {
+        let mut i = 1;
+        while i < self.items.len() {
+            let from_id = self.items[i].params.id.clone();
+            let to_ids: Vec<String> = self.items[..i]
+                .iter()
+                .map(|entry| entry.params.id.clone())
+                .collect();
+            for to_id in to_ids {
+                self.merge(&from_id, &to_id);
+            }
+            if self.items[i..]
+                .first()
+                .map(|entry| entry.params.id == from_id)
+                == Some(true)
+            {
+                i += 1;
+            }
+        }
+    }
+
+    // This removes an item without dropping it, like when a consumable runs out of charges
+    pub fn remove_item(&mut self, item_id: &str) -> Option<ItemParams> {
+        let i = self
+            .items
+            .iter()
+            .position(|entry| entry.params.id == item_id)?;
+        Some(self.items.remove(i).params)
     }
 
     pub fn add_credits(&mut self, amount: u32) {
         self.credits += amount;
     }
 
+    // Returns `true` if the inventory holds an instance of the item prototype with `prototype_id`
+    pub fn has_prototype(&self, prototype_id: &str) -> bool {
+        self.items.iter().any(|entry| {
+            entry
+                .params
+                .prototype_id
+                .as_deref()
+                .unwrap_or(&entry.params.id)
+                == prototype_id
+        })
+    }
+
+    // Returns the total quantity of all the stacks of the item prototype with `prototype_id`
+    pub fn get_quantity(&self, prototype_id: &str) -> u32 {
+        self.items
+            .iter()
+            .filter(|entry| {
+                entry
+                    .params
+                    .prototype_id
+                    .as_deref()
+                    .unwrap_or(&entry.params.id)
+                    == prototype_id
+            })
+            .map(|entry| entry.params.quantity)
+            .sum()
+    }
+
+    // Returns the total quantity of the stacks of the item prototype with `prototype_id`, that are
+    // not equipped, which is the quantity that can be removed with `remove_quantity`
+    pub fn get_unequipped_quantity(&self, prototype_id: &str) -> u32 {
+        self.items
+            .iter()
+            .filter(|entry| Self::is_unequipped_prototype(entry, prototype_id))
+            .map(|entry| entry.params.quantity)
+            .sum()
+    }
+
+    // Returns the ids of the equipped stacks of the item prototype with `prototype_id`
+    pub fn get_equipped_item_ids(&self, prototype_id: &str) -> Vec<String> {
+        self.items
+            .iter()
+            .filter(|entry| {
+                entry.equipped_to != EquipmentSlot::None
+                    && entry
+                        .params
+                        .prototype_id
+                        .as_deref()
+                        .unwrap_or(&entry.params.id)
+                        == prototype_id
+            })
+            .map(|entry| entry.params.id.clone())
+            .collect()
+    }
+
+    fn is_unequipped_prototype(entry: &InventoryEntry, prototype_id: &str) -> bool {
+        entry.equipped_to == EquipmentSlot::None
+            && entry
+                .params
+                .prototype_id
+                .as_deref()
+                .unwrap_or(&entry.params.id)
+                == prototype_id
+    }
+
+    // This removes `quantity` items of the item prototype with `prototype_id`, from the last
+    // stacks first, and returns the ids of the stacks that were emptied. Equipped items are not
+    // removed, and nothing is removed if there are not enough unequipped items.
+    pub fn remove_quantity(&mut self, prototype_id: &str, quantity: u32) -> Option<Vec<String>> {
+        if self.get_unequipped_quantity(prototype_id) < quantity {
+            return None;
+        }
+
+        let mut remaining = quantity;
+        let mut removed = Vec::new();
+        for entry in self.items.iter_mut().rev() {
+            if remaining == 0 {
+                break;
+            }
+            if Self::is_unequipped_prototype(entry, prototype_id) {
+                let amount = remaining.min(entry.params.quantity);
+                entry.params.quantity -= amount;
+                remaining -= amount;
+                if entry.params.quantity == 0 {
+                    removed.push(entry.params.id.clone());
+                }
+            }
+        }
+
+        self.items
+            .retain(|entry| !removed.contains(&entry.params.id));
+        Some(removed)
+    }
+
+    // This moves an item to another inventory. Any part of the stack that doesn't fit in the other
+    // inventory is kept. Returns `true` if the whole stack was moved.
+    pub fn transfer(&mut self, item_id: &str, other: &mut Inventory) -> bool {
+        if let Some(params) = self.remove_item(item_id) {
+            if let Some(remainder) = other.add_item(params) {
+                self.add(remainder, false);
+                return false;
+            }
+            return true;
+        }
+        false
+    }
+
+    // This moves all items and credits to another inventory, for as long as there is room for
+    // them. Returns `true` if everything was moved.
+    pub fn transfer_all(&mut self, other: &mut Inventory) -> bool {
+        other.credits += self.credits;
+        self.credits = 0;
+
+        let item_ids: Vec<String> = self
+            .items
+            .iter()
+            .map(|entry| entry.params.id.clone())
+            .collect();
+        let mut res = true;
+        for item_id in item_ids {
+            if !self.transfer(&item_id, other) {
+                res = false;
+            }
+        }
+        res
+    }
+
     pub fn drop(&mut self, item_id: &str, position: Vec2) -> bool {
         self.items
             .drain_filter(|entry| {
@@ -134,6 +413,32 @@ impl Inventory {
             .is_some()
     }
 
+    // This drops `quantity` items from a stack, or the whole stack, if there are not more items
+    // than that in it
+    pub fn drop_quantity(&mut self, item_id: &str, quantity: u32, position: Vec2) -> bool {
+        let entry = match self
+            .items
+            .iter_mut()
+            .find(|entry| entry.params.id == item_id && !entry.params.is_quest_item)
+        {
+            Some(entry) => entry,
+            None => return false,
+        };
+
+        if quantity >= entry.params.quantity {
+            return self.drop(item_id, position);
+        }
+
+        entry.params.quantity -= quantity;
+        Item::add_node(ItemParams {
+            id: generate_id(),
+            position: Some(Self::randomize_drop_position(position)),
+            quantity,
+            ..entry.params.clone()
+        });
+        true
+    }
+
     pub fn drop_all(&mut self, position: Vec2, include_credits: bool) {
         self.items.drain_filter(|entry| {
             if !entry.params.is_quest_item {
@@ -166,7 +471,7 @@ impl Inventory {
     pub fn get_total_weight(&self) -> f32 {
         let mut weight = 0.0;
         for item in &self.items {
-            weight += item.params.weight;
+            weight += item.params.get_total_weight();
         }
         weight
     }
@@ -179,10 +484,11 @@ impl Inventory {
                 .map(|entry| entry.params.id.clone())
                 .collect(),
             credits: self.credits,
+            capacity: self.capacity,
         }
     }
 
-    fn randomize_drop_position(position: Vec2) -> Vec2 {
+    pub(crate) fn randomize_drop_position(position: Vec2) -> Vec2 {
         vec2(
             rand::gen_range(
                 position.x - Self::DROP_ALL_POSITION_VARIANCE,
@@ -195,3 +501,118 @@ impl Inventory {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arrows(quantity: u32) -> ItemParams {
+        ItemParams {
+            id: "arrows".to_string(),
+            max_stack: 20,
+            quantity,
+            ..Default::default()
+        }
+        .instantiate()
+    }
+
+    fn get_quantities(inventory: &Inventory) -> Vec<u32> {
+        inventory
+            .items
+            .iter()
+            .map(|entry| entry.params.quantity)
+            .collect()
+    }
+
+    #[test]
+    fn test_add_stacks() {
+        let mut inventory = Inventory::new();
+        assert!(inventory.add_item(arrows(15)).is_none());
+        assert!(inventory.add_item(arrows(30)).is_none());
+        assert_eq!(get_quantities(&inventory), vec![20, 20, 5]);
+        assert_eq!(inventory.get_total_weight().round() as u32, 5);
+
+        // Items that are not stackable are always added as new entries
+        inventory.add_item(ItemParams::default().instantiate());
+        inventory.add_item(ItemParams::default().instantiate());
+        assert_eq!(inventory.items.len(), 5);
+    }
+
+    #[test]
+    fn test_capacity() {
+        let mut inventory = Inventory {
+            capacity: InventoryCapacity::Slots { slots: 2 },
+            ..Default::default()
+        };
+        let remainder = inventory.add_item(arrows(50)).unwrap();
+        assert_eq!(get_quantities(&inventory), vec![20, 20]);
+        assert_eq!(remainder.quantity, 10);
+        assert_ne!(remainder.id, inventory.items[1].params.id);
+
+        assert!(inventory
+            .split(&inventory.items[0].params.id.clone(), 5)
+            .is_none());
+    }
+
+    #[test]
+    fn test_split_and_merge() {
+        let mut inventory = Inventory::new();
+        inventory.add_item(arrows(20));
+        let from_id = inventory.items[0].params.id.clone();
+
+        let to_id = inventory.split(&from_id, 8).unwrap();
+        assert_eq!(get_quantities(&inventory), vec![12, 8]);
+        assert!(inventory.split(&from_id, 12).is_none());
+
+        assert!(inventory.merge(&from_id, &to_id));
+        assert_eq!(get_quantities(&inventory), vec![20]);
+        assert_eq!(inventory.items[0].params.id, to_id);
+
+        inventory.split(&to_id, 5);
+        inventory.split(&to_id, 5);
+        inventory.stack_all();
+        assert_eq!(get_quantities(&inventory), vec![20]);
+    }
+
+    #[test]
+    fn test_remove_quantity() {
+        let mut inventory = Inventory::new();
+        inventory.add_item(arrows(30));
+        assert!(inventory.remove_quantity("arrows", 40).is_none());
+        assert_eq!(get_quantities(&inventory), vec![20, 10]);
+
+        let removed = inventory.remove_quantity("arrows", 15).unwrap();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(get_quantities(&inventory), vec![15]);
+        assert_eq!(inventory.get_quantity("arrows"), 15);
+    }
+
+    #[test]
+    fn test_transfer() {
+        let mut container = Inventory {
+            credits: 50,
+            ..Default::default()
+        };
+        container.add_item(arrows(30));
+        let key = ItemParams {
+            id: "key".to_string(),
+            ..Default::default()
+        }
+        .instantiate();
+        container.add_item(key);
+        assert!(container.has_prototype("key"));
+
+        let mut inventory = Inventory {
+            capacity: InventoryCapacity::Slots { slots: 2 },
+            ..Default::default()
+        };
+        inventory.add_item(arrows(5));
+
+        assert!(!container.transfer_all(&mut inventory));
+        assert_eq!(inventory.credits, 50);
+        assert_eq!(get_quantities(&inventory), vec![20, 15]);
+        assert_eq!(container.items.len(), 1);
+        assert!(container.has_prototype("key"));
+        assert!(!inventory.has_prototype("key"));
+    }
+}
@@ -70,6 +70,7 @@ impl From<Map> for MapDef {
                         id: layer.id.clone(),
                         kind: layer.kind.clone(),
                         collision: layer.collision.clone(),
+                        collision_layers: layer.collision_layers.clone(),
                         objects,
                         tiles,
                         is_visible: layer.is_visible,
@@ -176,6 +177,7 @@ impl From<MapDef> for Map {
                 id: layer.id.clone(),
                 kind: layer.kind.clone(),
                 collision: layer.collision.clone(),
+                collision_layers: layer.collision_layers.clone(),
                 grid_size: def.grid_size,
                 tiles,
                 objects,
@@ -205,6 +207,8 @@ pub struct MapLayerDef {
     pub id: String,
     #[serde(default, skip_serializing_if = "CollisionKind::is_none")]
     pub collision: CollisionKind,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collision_layers: Vec<String>,
     pub kind: MapLayerKind,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tiles: Option<Vec<u32>>,
@@ -221,6 +225,7 @@ impl Default for MapLayerDef {
         MapLayerDef {
             id: "".to_string(),
             collision: CollisionKind::None,
+            collision_layers: Vec::new(),
             kind: MapLayerKind::TileLayer,
             tiles: Some(Vec::new()),
             objects: None,
@@ -28,6 +28,7 @@ pub struct TiledObject {
     pub rotation: f32,
     pub ellipse: Option<bool>,
     pub polygon: Option<Vec<TiledPolyPoint>>,
+    pub polyline: Option<Vec<TiledPolyPoint>>,
     pub properties: Option<Vec<TiledProperty>>,
 }
 
@@ -101,9 +102,13 @@ pub struct TiledMap {
 
 impl TiledMap {
     pub const OBJECT_LAYER_KIND_PROP_KEY: &'static str = "object_layer_kind";
+    pub const COLLISION_PROP_KEY: &'static str = "collision";
+    pub const COLLISION_LAYERS_PROP_KEY: &'static str = "collision_layers";
     pub const SPAWN_POINTS_LAYER_PROP: &'static str = "spawn_points";
     pub const ITEMS_LAYER_PROP: &'static str = "items";
     pub const LIGHT_SOURCES_LAYER_PROP: &'static str = "light_sources";
+    pub const CONTAINERS_LAYER_PROP: &'static str = "containers";
+    pub const COLLIDERS_LAYER_PROP: &'static str = "colliders";
 
     pub const BOOL_VALUE_TYPE: &'static str = "bool";
     pub const FLOAT_VALUE_TYPE: &'static str = "float";
@@ -254,10 +259,19 @@ impl From<TiledMap> for Map {
                     player_spawn_point = Some(position);
                 }
 
+                // Polygons are closed, while polylines are left open
+                let (points, is_closed) = match (&object.polygon, &object.polyline) {
+                    (Some(points), _) => (points.as_slice(), true),
+                    (None, Some(points)) => (points.as_slice(), false),
+                    (None, None) => (&[][..], false),
+                };
+
                 let object = MapObject {
                     name: object.name.clone(),
                     position,
                     size,
+                    points: points.iter().map(|point| vec2(point.x, point.y)).collect(),
+                    is_closed,
                     properties,
                 };
 
@@ -279,6 +293,10 @@ impl From<TiledMap> for Map {
                                 object_layer_kind = ObjectLayerKind::SpawnPoints;
                             } else if value == TiledMap::LIGHT_SOURCES_LAYER_PROP {
                                 object_layer_kind = ObjectLayerKind::LightSources;
+                            } else if value == TiledMap::CONTAINERS_LAYER_PROP {
+                                object_layer_kind = ObjectLayerKind::Containers;
+                            } else if value == TiledMap::COLLIDERS_LAYER_PROP {
+                                object_layer_kind = ObjectLayerKind::Colliders;
                             }
                         }
                     } else {
@@ -288,10 +306,24 @@ impl From<TiledMap> for Map {
             }
 
             let mut collision = CollisionKind::None;
-            if let Some(MapProperty::String { value }) = properties.remove("collision") {
+            if let Some(MapProperty::String { value }) =
+                properties.remove(TiledMap::COLLISION_PROP_KEY)
+            {
                 collision = CollisionKind::from(value)
             }
 
+            // Additional collision layers are defined as a comma separated list of names
+            let mut collision_layers = Vec::new();
+            if let Some(MapProperty::String { value }) =
+                properties.remove(TiledMap::COLLISION_LAYERS_PROP_KEY)
+            {
+                collision_layers = value
+                    .split(',')
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty())
+                    .collect();
+            }
+
             let kind = if tiled_layer.layer_type == *"tilelayer" {
                 MapLayerKind::TileLayer
             } else {
@@ -302,6 +334,7 @@ impl From<TiledMap> for Map {
                 id: tiled_layer.name.clone(),
                 kind,
                 collision,
+                collision_layers,
                 grid_size,
                 tiles,
                 objects,
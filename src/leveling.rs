@@ -0,0 +1,164 @@
+use crate::prelude::*;
+
+pub const STARTING_LEVEL: u32 = 1;
+
+// This defines the amount of experience required to reach each level. All values are for the
+// total experience, so the level can always be calculated from an actor's `experience`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExperienceCurve {
+    // Every level requires `experience` more than the previous one
+    Linear { experience: u32 },
+    // The first level up requires `experience` and every level after that requires `factor`
+    // times more than the previous one
+    Exponential { experience: u32, factor: f32 },
+    // Explicit thresholds, starting with the one for level two. The length of the table will
+    // also be the maximum number of level ups.
+    Table { thresholds: Vec<u32> },
+}
+
+impl Default for ExperienceCurve {
+    fn default() -> Self {
+        ExperienceCurve::Exponential {
+            experience: 100,
+            factor: 1.5,
+        }
+    }
+}
+
+impl ExperienceCurve {
+    // Returns the total experience required to reach `level`, or `None` if the level can not be
+    // reached with this curve
+    pub fn get_threshold(&self, level: u32) -> Option<u32> {
+        if level <= STARTING_LEVEL {
+            return Some(0);
+        }
+
+        let level_ups = level - STARTING_LEVEL;
+        match self {
+            ExperienceCurve::Linear { experience } => {
+                let level_ups = level_ups as u64;
+                let threshold = *experience as u64 * (level_ups * (level_ups + 1) / 2);
+                if threshold <= u32::MAX as u64 {
+                    Some(threshold as u32)
+                } else {
+                    None
+                }
+            }
+            ExperienceCurve::Exponential { experience, factor } => {
+                let mut threshold = 0.0;
+                let mut required = *experience as f64;
+                for _ in 0..level_ups {
+                    threshold += required.round();
+                    required *= *factor as f64;
+                }
+                if threshold <= u32::MAX as f64 {
+                    Some(threshold as u32)
+                } else {
+                    None
+                }
+            }
+            ExperienceCurve::Table { thresholds } => {
+                thresholds.get(level_ups as usize - 1).cloned()
+            }
+        }
+    }
+
+    // Returns the level reached with `experience`, limited to `max_level`, if specified.
+    // Leveling stops at the first threshold that is not higher than the previous one, so that a
+    // curve with no experience requirement doesn't lead to endless level ups.
+    pub fn get_level(&self, experience: u32, max_level: Option<u32>) -> u32 {
+        let mut level = STARTING_LEVEL;
+        let mut previous = 0;
+        while max_level.map(|max_level| level < max_level).unwrap_or(true) {
+            match self.get_threshold(level + 1) {
+                Some(threshold) if threshold <= experience && threshold > previous => {
+                    previous = threshold;
+                    level += 1;
+                }
+                _ => break,
+            }
+        }
+        level
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbilityUnlock {
+    pub level: u32,
+    #[serde(rename = "ability")]
+    pub ability_id: String,
+}
+
+// A set of stat modifiers that is applied to an actor of a class, once it reaches `level`. The
+// perk's `id` is used as the source of the modifiers, so it should be unique.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassPerk {
+    pub id: String,
+    #[serde(default = "ClassPerk::default_level")]
+    pub level: u32,
+    pub modifiers: Vec<StatModifier>,
+}
+
+impl ClassPerk {
+    pub fn default_level() -> u32 {
+        STARTING_LEVEL
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_curve() {
+        let curve = ExperienceCurve::Linear { experience: 100 };
+        assert_eq!(curve.get_threshold(1), Some(0));
+        assert_eq!(curve.get_threshold(2), Some(100));
+        assert_eq!(curve.get_threshold(3), Some(300));
+        assert_eq!(curve.get_threshold(4), Some(600));
+        assert_eq!(curve.get_level(299, None), 2);
+        assert_eq!(curve.get_level(300, None), 3);
+    }
+
+    #[test]
+    fn test_exponential_curve() {
+        let curve = ExperienceCurve::Exponential {
+            experience: 100,
+            factor: 2.0,
+        };
+        assert_eq!(curve.get_threshold(2), Some(100));
+        assert_eq!(curve.get_threshold(3), Some(300));
+        assert_eq!(curve.get_threshold(4), Some(700));
+        assert_eq!(curve.get_level(0, None), 1);
+        assert_eq!(curve.get_level(700, None), 4);
+        assert_eq!(curve.get_level(u32::MAX, None), 26);
+    }
+
+    #[test]
+    fn test_table_curve() {
+        let curve = ExperienceCurve::Table {
+            thresholds: vec![50, 150],
+        };
+        assert_eq!(curve.get_threshold(3), Some(150));
+        assert_eq!(curve.get_threshold(4), None);
+        assert_eq!(curve.get_level(10_000, None), 3);
+    }
+
+    #[test]
+    fn test_max_level() {
+        let curve = ExperienceCurve::Linear { experience: 10 };
+        assert_eq!(curve.get_level(10_000, Some(5)), 5);
+        assert_eq!(curve.get_level(0, Some(5)), 1);
+
+        let curve = ExperienceCurve::Linear { experience: 0 };
+        assert_eq!(curve.get_level(10_000, None), 1);
+    }
+
+    #[test]
+    fn test_curve_from_json() {
+        let json = r#"{ "type": "exponential", "experience": 100, "factor": 1.5 }"#;
+        let curve: ExperienceCurve = serde_json::from_str(json).unwrap();
+        assert_eq!(curve, ExperienceCurve::default());
+    }
+}
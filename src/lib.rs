@@ -9,6 +9,7 @@ pub mod behavior_sets;
 pub mod chapter;
 pub mod character;
 pub mod config;
+pub mod damage;
 pub mod dialogue;
 pub mod error;
 pub mod events;
@@ -17,8 +18,12 @@ pub mod game;
 pub mod gui;
 pub mod helpers;
 pub mod input;
+pub mod interpolation;
 pub mod inventory;
 pub mod json;
+pub mod leveling;
+pub mod localization;
+pub mod loot;
 pub mod map;
 pub mod math;
 pub mod missions;
@@ -31,6 +36,9 @@ pub mod prelude;
 pub mod render;
 pub mod resources;
 pub mod scene;
+pub mod stats;
+pub mod status_effects;
+pub mod vendor;
 pub mod versions;
 
 pub use macroquad;
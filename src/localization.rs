@@ -0,0 +1,487 @@
+use std::fmt::Display;
+
+use serde::de::DeserializeOwned;
+
+use crate::file_io::deserialize_file;
+use crate::gui::MenuParams;
+use crate::helpers::ToStringHelper;
+use crate::prelude::*;
+
+// A locale, which is defined in the optional `locales.json` data file, or by a module
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocaleParams {
+    pub id: String,
+    // The name of the locale, as it is shown in the settings menu
+    pub name: String,
+    // The path to the string table of the locale, relative to the data directory, or to the
+    // directory of the module that defines it
+    pub path: String,
+    // Strings that are missing from this locale are looked up in the fallback locale
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<String>,
+    // A font, from the font assets, that is used by the GUI when this locale is selected. This is
+    // needed for locales with characters that are not covered by the default font.
+    #[serde(default, rename = "font", skip_serializing_if = "Option::is_none")]
+    pub font_id: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Locale {
+    pub id: String,
+    pub name: String,
+    pub fallback: Option<String>,
+    pub font_id: Option<String>,
+    pub strings: HashMap<String, String>,
+}
+
+impl Locale {
+    pub fn new(params: LocaleParams, strings: HashMap<String, String>) -> Self {
+        Locale {
+            id: params.id,
+            name: params.name,
+            fallback: params.fallback,
+            font_id: params.font_id,
+            strings,
+        }
+    }
+
+    // Strings that are left empty in a string table are treated as missing
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.strings
+            .get(key)
+            .map(|value| value.as_str())
+            .filter(|value| !value.is_empty())
+    }
+}
+
+// This holds the string tables of all the locales. It is loaded before the rest of the resources,
+// so that the loading screen can be translated, as well.
+#[derive(Debug, Default, Clone)]
+pub struct Localization {
+    pub locales: HashMap<String, Locale>,
+}
+
+impl Localization {
+    pub const LOCALES_FILE_NAME: &'static str = "locales.json";
+
+    pub async fn new(game_params: &GameParams) -> Result<Self> {
+        let mut localization = Localization::default();
+
+        // The locales file is optional
+        let data_path = Path::new(&game_params.data_path);
+        let locales_path = data_path.join(Self::LOCALES_FILE_NAME);
+        if let Ok(bytes) = load_file(&locales_path).await {
+            let params: Vec<LocaleParams> = serde_json::from_slice(&bytes)
+                .map_err(|err| json::Error::new(&locales_path.to_string_helper(), err))?;
+            localization.add_locales(data_path, params).await?;
+        }
+
+        Ok(localization)
+    }
+
+    // This loads the string tables of `params`, from paths relative to `base_path`. Strings of
+    // locales that have already been added are merged into the existing string tables, replacing
+    // any strings with the same keys.
+    pub async fn add_locales(&mut self, base_path: &Path, params: Vec<LocaleParams>) -> Result<()> {
+        for params in params {
+            let path = base_path.join(&params.path);
+            let strings: HashMap<String, String> = deserialize_file(&path).await?;
+
+            if let Some(locale) = self.locales.get_mut(&params.id) {
+                locale.strings.extend(strings);
+                if params.fallback.is_some() {
+                    locale.fallback = params.fallback;
+                }
+                if params.font_id.is_some() {
+                    locale.font_id = params.font_id;
+                }
+            } else {
+                self.locales
+                    .insert(params.id.clone(), Locale::new(params, strings));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Returns the locale with `locale_id`, followed by its chain of fallback locales
+    pub fn get_fallback_chain(&self, locale_id: &str) -> Vec<&Locale> {
+        let mut res: Vec<&Locale> = Vec::new();
+        let mut next = Some(locale_id);
+        while let Some(locale_id) = next {
+            // Fallback chains that loop back on themselves are cut off
+            if res.iter().any(|locale| locale.id == locale_id) {
+                break;
+            }
+            match self.locales.get(locale_id) {
+                Some(locale) => {
+                    res.push(locale);
+                    next = locale.fallback.as_deref();
+                }
+                None => break,
+            }
+        }
+        res
+    }
+
+    // This looks up `key` in the locale with `locale_id` and its fallback locales, in order
+    pub fn translate(&self, locale_id: &str, key: &str) -> Option<&str> {
+        self.get_fallback_chain(locale_id)
+            .into_iter()
+            .find_map(|locale| locale.get(key))
+    }
+
+    // Returns the id of the font of the locale with `locale_id`, or of the first of its fallback
+    // locales that defines one
+    pub fn get_font_id(&self, locale_id: &str) -> Option<String> {
+        self.get_fallback_chain(locale_id)
+            .into_iter()
+            .find_map(|locale| locale.font_id.clone())
+    }
+
+    // Returns the ids and names of all the locales, sorted by name
+    pub fn get_available_locales(&self) -> Vec<(String, String)> {
+        let mut res: Vec<(String, String)> = self
+            .locales
+            .values()
+            .map(|locale| (locale.id.clone(), locale.name.clone()))
+            .collect();
+        res.sort_by(|a, b| a.1.cmp(&b.1));
+        res
+    }
+}
+
+// This translates `text` to the locale selected in the config. Any string can be used as a key in
+// a string table, so `text` can either be an English text, that is used as is if it has not been
+// translated, or a key, like `item_pistol_name`, that is defined by the string tables.
+pub fn tr(text: &str) -> String {
+    let config = storage::try_get::<Config>();
+    let localization = storage::try_get::<Localization>();
+    if let (Some(config), Some(localization)) = (config, localization) {
+        if let Some(translated) = localization.translate(&config.locale, text) {
+            return translated.to_string();
+        }
+    }
+
+    text.to_string()
+}
+
+// This translates `text` and replaces the named placeholders in it, like `{credits}`, with the
+// values in `args`. Placeholders are resolved by `interpolate`, so integer values can be given
+// plural forms, like `{credits|# credit|# credits}`.
+pub fn tr_args(text: &str, args: &[(&str, &dyn Display)]) -> String {
+    let context = TextContext {
+        args,
+        ..Default::default()
+    };
+    interpolate(&tr(text), &context)
+}
+
+// The strings that are used by the GUI. Every string that is passed to `tr`, or `tr_args`, by the
+// GUI should be added here, so that it is included when missing strings are extracted from a
+// string table.
+pub const GUI_STRINGS: &[&str] = &[
+    "Loading game resources...",
+    "Continue",
+    "Close",
+    "Cancel",
+    "Confirm",
+    "Save",
+    "Ok",
+    "Done",
+    "Back",
+    "Changes require a restart!",
+    "Select Character",
+    "Create",
+    "Delete",
+    "Start",
+    "Are you sure you want to delete",
+    "Create Character",
+    "Character Name:",
+    "Build points: {points}",
+    "Hardcore",
+    "You have unspent build points!",
+    "Name is already in use!",
+    "Invalid name!",
+    "Settings",
+    "Resolution",
+    "Fullscreen",
+    "Language",
+    "Modules",
+    "{title} (current)",
+    "Level: {level}",
+    "XP: {experience}/{threshold}",
+    "XP: {experience}",
+    "Attribute points: {points}",
+    "STR",
+    "DEX",
+    "CON",
+    "INT",
+    "WIL",
+    "PER",
+    "CHA",
+    "Regeneration",
+    "Health: {value}",
+    "Stamina: {value}",
+    "Energy: {value}",
+    "Other",
+    "Equipment",
+    "Status effects",
+    "Abilities",
+    "{ability} (selected)",
+    "credits: {credits}, weight: {weight}/{capacity}",
+    "slots: {used}/{slots}",
+    "grid: {width}x{height}",
+    "Stack all",
+    "Use",
+    "Split",
+    "Equip",
+    "Unequip",
+    "Drop",
+    "Requires class: {classes}",
+    "Requires {attribute}: {value}",
+    "Locked",
+    "Requires: {item}",
+    "{name} (dead)",
+    "credits: {credits}",
+    "Take all",
+    "Take",
+    "Inventory",
+    "Store",
+    "Trade",
+    "Vendor credits: {credits}",
+    "Your credits: {credits}",
+    "Buy",
+    "Buy back",
+    "Sell",
+    "{price} cr",
+    "Active missions:",
+    "Completed missions:",
+];
+
+#[cfg(not(any(target_family = "wasm", target_os = "android")))]
+fn read_data_file<T: DeserializeOwned>(path: &Path) -> Result<Option<T>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let bytes = fs::read(path)?;
+    let res = serde_json::from_slice(&bytes)
+        .map_err(|err| json::Error::new(&path.to_string_helper(), err))?;
+    Ok(Some(res))
+}
+
+// This collects all the strings, shown to the player, from the data files in `data_path`, as well
+// as the strings used by the GUI
+#[cfg(not(any(target_family = "wasm", target_os = "android")))]
+pub fn get_translatable_strings<P: AsRef<Path>>(data_path: P) -> Result<Vec<String>> {
+    let data_path = data_path.as_ref();
+    let mut res: Vec<String> = GUI_STRINGS.iter().map(|text| text.to_string()).collect();
+
+    let path = data_path.join("actors.json");
+    for params in read_data_file::<Vec<ActorParams>>(&path)?.unwrap_or_default() {
+        res.push(params.name);
+    }
+
+    let path = data_path.join("character_classes.json");
+    for class in read_data_file::<Vec<CharacterClass>>(&path)?.unwrap_or_default() {
+        res.push(class.name);
+        res.push(class.description);
+    }
+
+    let path = data_path.join("items.json");
+    for params in read_data_file::<Vec<ItemParams>>(&path)?.unwrap_or_default() {
+        res.push(params.name);
+        res.push(params.description);
+    }
+
+    let path = data_path.join("equipment_slots.json");
+    for params in read_data_file::<Vec<EquipmentSlotParams>>(&path)?.unwrap_or_default() {
+        res.push(params.name);
+    }
+
+    let path = data_path.join("affixes.json");
+    if let Some(affixes) = read_data_file::<AffixTables>(&path)? {
+        res.extend(affixes.rarities.into_iter().map(|rarity| rarity.name));
+        res.extend(affixes.affixes.into_iter().map(|affix| affix.name));
+    }
+
+    let path = data_path.join("missions.json");
+    for params in read_data_file::<Vec<MissionParams>>(&path)?.unwrap_or_default() {
+        res.push(params.title);
+        res.push(params.description);
+    }
+
+    let path = data_path.join("dialogue.json");
+    for dialogue in read_data_file::<Vec<Dialogue>>(&path)?.unwrap_or_default() {
+        res.push(dialogue.title);
+        res.extend(dialogue.body);
+        res.extend(dialogue.response);
+        res.extend(dialogue.check.map(|check| check.stat));
+    }
+
+    // Abilities have no names, so their ids are shown in the GUI
+    let path = data_path.join("abilities.json");
+    for params in read_data_file::<Vec<AbilityParams>>(&path)?.unwrap_or_default() {
+        res.push(params.id);
+    }
+
+    let path = data_path.join("scenario.json");
+    for params in read_data_file::<Vec<ChapterParams>>(&path)?.unwrap_or_default() {
+        res.push(params.title);
+        res.push(params.description);
+        res.extend(params.outro);
+    }
+
+    let path = data_path.join("containers.json");
+    for params in read_data_file::<Vec<ContainerParams>>(&path)?.unwrap_or_default() {
+        res.push(params.name);
+    }
+
+    let path = data_path.join("gui").join("menus.json");
+    for params in read_data_file::<Vec<MenuParams>>(&path)?.unwrap_or_default() {
+        res.extend(params.title);
+        res.extend(params.options.into_iter().filter_map(|option| option.title));
+    }
+
+    let mut seen = Vec::new();
+    res.retain(|text| {
+        if text.is_empty() || seen.contains(text) {
+            return false;
+        }
+        seen.push(text.clone());
+        true
+    });
+
+    Ok(res)
+}
+
+// This returns the translatable strings of the data in `data_path` that are missing from the
+// string table of the locale with `locale_id`, not counting its fallback locales
+#[cfg(not(any(target_family = "wasm", target_os = "android")))]
+pub fn get_missing_strings<P: AsRef<Path>>(data_path: P, locale_id: &str) -> Result<Vec<String>> {
+    let data_path = data_path.as_ref();
+    let path = data_path.join(Localization::LOCALES_FILE_NAME);
+    let locales = read_data_file::<Vec<LocaleParams>>(&path)?.unwrap_or_default();
+
+    let mut strings = HashMap::new();
+    for params in locales.into_iter().filter(|params| params.id == locale_id) {
+        let path = data_path.join(&params.path);
+        let table = read_data_file::<HashMap<String, String>>(&path)?.unwrap_or_default();
+        strings.extend(table);
+    }
+
+    let locale = Locale {
+        id: locale_id.to_string(),
+        name: locale_id.to_string(),
+        fallback: None,
+        font_id: None,
+        strings,
+    };
+
+    let res = get_translatable_strings(data_path)?
+        .into_iter()
+        .filter(|text| locale.get(text).is_none())
+        .collect();
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_locale(id: &str, fallback: Option<&str>, strings: &[(&str, &str)]) -> Locale {
+        Locale {
+            id: id.to_string(),
+            name: id.to_string(),
+            fallback: fallback.map(|fallback| fallback.to_string()),
+            font_id: None,
+            strings: strings
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_fallback_chain() {
+        let mut localization = Localization::default();
+        for locale in [
+            get_locale("en", None, &[("item_pistol", "Pistol"), ("Hello", "Hi")]),
+            get_locale("de", Some("en"), &[("Hello", "Hallo"), ("Close", "")]),
+            get_locale("de_at", Some("de"), &[("Hello", "Servus")]),
+            // A fallback chain that loops back on itself
+            get_locale("xx", Some("yy"), &[]),
+            get_locale("yy", Some("xx"), &[]),
+        ] {
+            localization.locales.insert(locale.id.clone(), locale);
+        }
+
+        assert_eq!(localization.translate("de_at", "Hello"), Some("Servus"));
+        assert_eq!(localization.translate("de", "Hello"), Some("Hallo"));
+        assert_eq!(
+            localization.translate("de_at", "item_pistol"),
+            Some("Pistol")
+        );
+        // Empty strings are treated as missing
+        assert_eq!(localization.translate("de", "Close"), None);
+        assert_eq!(localization.translate("fr", "Hello"), None);
+        assert_eq!(localization.get_fallback_chain("xx").len(), 2);
+        assert_eq!(localization.translate("xx", "Hello"), None);
+    }
+
+    // This returns the string literals that are passed to `tr`, or `tr_args`, in `text`
+    fn find_translated_literals(text: &str) -> Vec<String> {
+        let mut res = Vec::new();
+        for call in &["tr(", "tr_args("] {
+            for (i, _) in text.match_indices(call) {
+                let is_call = text[..i]
+                    .chars()
+                    .last()
+                    .map(|c| !c.is_alphanumeric() && c != '_')
+                    .unwrap_or(true);
+                let rest = text[i + call.len()..].trim_start();
+                if let (true, Some(rest)) = (is_call, rest.strip_prefix('"')) {
+                    if let Some(end) = rest.find('"') {
+                        res.push(rest[..end].to_string());
+                    }
+                }
+            }
+        }
+        res
+    }
+
+    fn find_gui_sources(path: &Path, res: &mut Vec<PathBuf>) {
+        for entry in fs::read_dir(path).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                find_gui_sources(&path, res);
+            } else if path.extension().map(|ext| ext == "rs").unwrap_or(false) {
+                res.push(path);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gui_strings() {
+        let gui_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("gui");
+        let mut paths = Vec::new();
+        find_gui_sources(&gui_path, &mut paths);
+        assert!(!paths.is_empty());
+
+        for path in paths {
+            let text = fs::read_to_string(&path).unwrap();
+            for literal in find_translated_literals(&text) {
+                assert!(
+                    GUI_STRINGS.contains(&literal.as_str()),
+                    "'{}', in '{}', is missing from `GUI_STRINGS`",
+                    literal,
+                    path.display()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,573 @@
+use crate::prelude::*;
+
+// Loot tables can reference other tables, so this limits how deep they can be nested, in case a
+// table references itself
+pub const MAX_LOOT_TABLE_DEPTH: u32 = 8;
+
+// A random number generator with its own state, so that loot can be rolled from a seed and give
+// the same results every time, regardless of any other use of the global generator
+#[derive(Debug, Clone)]
+pub struct LootRng {
+    state: u64,
+}
+
+impl LootRng {
+    pub fn new(seed: u64) -> Self {
+        // The state of the generator can not be zero
+        let state = (seed ^ 0x9e37_79b9_7f4a_7c15).max(1);
+        LootRng { state }
+    }
+
+    // This seeds a new generator from the global generator
+    pub fn from_global() -> Self {
+        let seed = ((rand::rand() as u64) << 32) | rand::rand() as u64;
+        Self::new(seed)
+    }
+
+    // This seeds a new generator from a string id, so that the same id will always produce the
+    // same rolls. The id is hashed with FNV-1a, as the hashers of the standard library are not
+    // guaranteed to be stable between releases.
+    pub fn from_id(id: &str) -> Self {
+        let seed = id.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        });
+        Self::new(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    // Returns a value in the range `[0.0, 1.0)`
+    pub fn gen_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    // Returns a value in the range `[low, high)`
+    pub fn gen_range_f32(&mut self, low: f32, high: f32) -> f32 {
+        low + (high - low) * self.gen_f32()
+    }
+
+    // Returns a value in the range `[min, max]`
+    pub fn gen_range_u32(&mut self, min: u32, max: u32) -> u32 {
+        if max <= min {
+            return min;
+        }
+
+        let range = (max - min) as u64 + 1;
+        min + (self.next_u64() % range) as u32
+    }
+
+    // This picks the index of one of `choices`, by the relative weights returned by `f`. Choices
+    // with a weight of zero, or less, will never be picked.
+    pub fn choose_weighted_index<T, F>(&mut self, choices: &[T], f: F) -> Option<usize>
+    where
+        F: Fn(&T) -> f32,
+    {
+        let total: f32 = choices.iter().map(|choice| f(choice).max(0.0)).sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut roll = self.gen_range_f32(0.0, total);
+        for (i, choice) in choices.iter().enumerate() {
+            let weight = f(choice).max(0.0);
+            if roll < weight {
+                return Some(i);
+            }
+            roll -= weight;
+        }
+
+        choices.iter().rposition(|choice| f(choice) > 0.0)
+    }
+
+    pub fn choose_weighted<'a, T, F>(&mut self, choices: &'a [T], f: F) -> Option<&'a T>
+    where
+        F: Fn(&T) -> f32,
+    {
+        self.choose_weighted_index(choices, f).map(|i| &choices[i])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LootRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl LootRange {
+    pub fn new(min: u32, max: u32) -> Self {
+        LootRange { min, max }
+    }
+
+    pub fn roll(&self, rng: &mut LootRng) -> u32 {
+        rng.gen_range_u32(self.min, self.max)
+    }
+}
+
+impl Default for LootRange {
+    fn default() -> Self {
+        LootRange { min: 1, max: 1 }
+    }
+}
+
+// The state of the actor that loot is rolled for, which is checked against the conditions of
+// loot entries
+#[derive(Debug, Default, Clone)]
+pub struct LootContext {
+    pub level: u32,
+    pub class_id: Option<String>,
+    pub active_missions: Vec<String>,
+    pub completed_missions: Vec<String>,
+}
+
+impl LootContext {
+    pub fn from_actor(actor: &Actor) -> Self {
+        LootContext {
+            level: actor.level,
+            class_id: actor.class_id.clone(),
+            active_missions: actor
+                .active_missions
+                .iter()
+                .map(|mission| mission.id.clone())
+                .collect(),
+            completed_missions: actor
+                .completed_missions
+                .iter()
+                .map(|mission| mission.id.clone())
+                .collect(),
+        }
+    }
+
+    pub fn from_character(character: &Character) -> Self {
+        LootContext {
+            level: character.actor.level,
+            class_id: character.actor.class_id.clone(),
+            active_missions: character.active_missions.clone(),
+            completed_missions: character.completed_missions.clone(),
+        }
+    }
+
+    // Loot is rolled for the local player, if there is one
+    pub fn from_player() -> Self {
+        get_player_actor()
+            .map(|player| Self::from_actor(&player))
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LootCondition {
+    MinLevel { level: u32 },
+    MaxLevel { level: u32 },
+    Class { class_id: String },
+    MissionActive { mission_id: String },
+    MissionCompleted { mission_id: String },
+}
+
+impl LootCondition {
+    pub fn is_met(&self, context: &LootContext) -> bool {
+        match self {
+            LootCondition::MinLevel { level } => context.level >= *level,
+            LootCondition::MaxLevel { level } => context.level <= *level,
+            LootCondition::Class { class_id } => context.class_id.as_ref() == Some(class_id),
+            LootCondition::MissionActive { mission_id } => {
+                context.active_missions.contains(mission_id)
+            }
+            LootCondition::MissionCompleted { mission_id } => {
+                context.completed_missions.contains(mission_id)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LootEntryKind {
+    Item { prototype_id: String },
+    // Rolls another loot table, once for every quantity
+    Table { table_id: String },
+    Credits,
+    Nothing,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LootEntry {
+    #[serde(flatten)]
+    pub kind: LootEntryKind,
+    // The relative chance of the entry being rolled. This is ignored for guaranteed entries.
+    #[serde(default = "LootEntry::default_weight")]
+    pub weight: f32,
+    #[serde(default)]
+    pub quantity: LootRange,
+    // Entries with conditions that are not met will never be rolled
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<LootCondition>,
+}
+
+impl LootEntry {
+    pub fn default_weight() -> f32 {
+        1.0
+    }
+
+    pub fn is_available(&self, context: &LootContext) -> bool {
+        self.conditions
+            .iter()
+            .all(|condition| condition.is_met(context))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LootTableParams {
+    pub id: String,
+    // The number of times an entry is picked from `entries`
+    #[serde(default)]
+    pub rolls: LootRange,
+    // Entries that are always dropped, as long as their conditions are met
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub guaranteed: Vec<LootEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entries: Vec<LootEntry>,
+    // Credits that are always dropped, in addition to any credit entries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credits: Option<LootRange>,
+}
+
+impl LootTableParams {
+    pub fn roll(
+        &self,
+        tables: &HashMap<String, LootTableParams>,
+        context: &LootContext,
+        rng: &mut LootRng,
+    ) -> Loot {
+        let mut loot = Loot::default();
+        self.roll_into(&mut loot, tables, context, rng, 0);
+        loot
+    }
+
+    fn roll_into(
+        &self,
+        loot: &mut Loot,
+        tables: &HashMap<String, LootTableParams>,
+        context: &LootContext,
+        rng: &mut LootRng,
+        depth: u32,
+    ) {
+        if let Some(credits) = self.credits {
+            loot.credits += credits.roll(rng);
+        }
+
+        for entry in &self.guaranteed {
+            if entry.is_available(context) {
+                Self::apply_entry(entry, loot, tables, context, rng, depth);
+            }
+        }
+
+        let available: Vec<&LootEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.is_available(context))
+            .collect();
+
+        for _ in 0..self.rolls.roll(rng) {
+            if let Some(entry) = rng.choose_weighted(&available, |entry| entry.weight) {
+                Self::apply_entry(entry, loot, tables, context, rng, depth);
+            }
+        }
+    }
+
+    fn apply_entry(
+        entry: &LootEntry,
+        loot: &mut Loot,
+        tables: &HashMap<String, LootTableParams>,
+        context: &LootContext,
+        rng: &mut LootRng,
+        depth: u32,
+    ) {
+        let quantity = entry.quantity.roll(rng);
+        match &entry.kind {
+            LootEntryKind::Item { prototype_id } => {
+                if quantity > 0 {
+                    loot.add_item(prototype_id, quantity);
+                }
+            }
+            LootEntryKind::Table { table_id } => {
+                if depth >= MAX_LOOT_TABLE_DEPTH {
+                    return;
+                }
+
+                let table = tables
+                    .get(table_id)
+                    .unwrap_or_else(|| panic!("Unable to find loot table with id '{}'", table_id));
+                for _ in 0..quantity {
+                    table.roll_into(loot, tables, context, rng, depth + 1);
+                }
+            }
+            LootEntryKind::Credits => loot.credits += quantity,
+            LootEntryKind::Nothing => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LootDrop {
+    pub prototype_id: String,
+    pub quantity: u32,
+}
+
+// The result of rolling a loot table, before the items are instantiated
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Loot {
+    pub drops: Vec<LootDrop>,
+    pub credits: u32,
+}
+
+impl Loot {
+    pub fn is_empty(&self) -> bool {
+        self.drops.is_empty() && self.credits == 0
+    }
+
+    // Drops of the same item are combined
+    pub fn add_item(&mut self, prototype_id: &str, quantity: u32) {
+        if let Some(drop) = self
+            .drops
+            .iter_mut()
+            .find(|drop| drop.prototype_id == prototype_id)
+        {
+            drop.quantity += quantity;
+        } else {
+            self.drops.push(LootDrop {
+                prototype_id: prototype_id.to_string(),
+                quantity,
+            });
+        }
+    }
+
+    // This creates the item instances of the loot, split into stacks of no more than the
+    // `max_stack` of the item. Items that can not be stacked are generated one by one, so they
+    // will all have their own rarity and affixes.
+    pub fn instantiate(
+        &self,
+        items: &HashMap<String, ItemParams>,
+        affixes: &AffixTables,
+        rng: &mut LootRng,
+    ) -> Vec<ItemParams> {
+        let mut res = Vec::new();
+        for drop in &self.drops {
+            let prototype = items
+                .get(&drop.prototype_id)
+                .unwrap_or_else(|| panic!("Unable to find item with id '{}'", &drop.prototype_id));
+
+            let mut remaining = drop.quantity;
+            while remaining > 0 {
+                let quantity = remaining.min(prototype.max_stack.max(1));
+                res.push(ItemParams {
+                    quantity,
+                    ..affixes.generate_with_rng(prototype, rng)
+                });
+                remaining -= quantity;
+            }
+        }
+        res
+    }
+}
+
+// This rolls the loot table with `table_id` for `context` and returns the instantiated items,
+// along with the amount of credits
+pub fn roll_loot_table(table_id: &str, context: &LootContext) -> (Vec<ItemParams>, u32) {
+    roll_loot_table_with_rng(table_id, context, &mut LootRng::from_global())
+}
+
+pub fn roll_loot_table_with_rng(
+    table_id: &str,
+    context: &LootContext,
+    rng: &mut LootRng,
+) -> (Vec<ItemParams>, u32) {
+    let resources = storage::get::<Resources>();
+    let table = resources
+        .loot_tables
+        .get(table_id)
+        .unwrap_or_else(|| panic!("Unable to find loot table with id '{}'", table_id));
+
+    let loot = table.roll(&resources.loot_tables, context, rng);
+    let items = loot.instantiate(&resources.items, &resources.affixes, rng);
+    (items, loot.credits)
+}
+
+// This rolls the loot table with `table_id` and drops the loot around `position`
+pub fn drop_loot_table(table_id: &str, context: &LootContext, position: Vec2) {
+    drop_loot_table_with_rng(table_id, context, position, &mut LootRng::from_global());
+}
+
+pub fn drop_loot_table_with_rng(
+    table_id: &str,
+    context: &LootContext,
+    position: Vec2,
+    rng: &mut LootRng,
+) {
+    let (items, credits) = roll_loot_table_with_rng(table_id, context, rng);
+    for params in items {
+        Item::add_node(ItemParams {
+            position: Some(Inventory::randomize_drop_position(position)),
+            ..params
+        });
+    }
+    if credits > 0 {
+        Credits::add_node(Inventory::randomize_drop_position(position), credits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_tables() -> HashMap<String, LootTableParams> {
+        let json = r#"[
+            {
+                "id": "bandit",
+                "rolls": { "min": 2, "max": 4 },
+                "credits": { "min": 5, "max": 10 },
+                "guaranteed": [
+                    { "type": "item", "prototype_id": "bandage", "quantity": { "min": 1, "max": 3 } },
+                    {
+                        "type": "item",
+                        "prototype_id": "letter",
+                        "conditions": [{ "type": "mission_active", "mission_id": "find_letter" }]
+                    }
+                ],
+                "entries": [
+                    { "type": "table", "table_id": "weapons", "weight": 2.0 },
+                    { "type": "credits", "quantity": { "min": 10, "max": 20 } },
+                    { "type": "nothing", "weight": 3.0 }
+                ]
+            },
+            {
+                "id": "weapons",
+                "entries": [
+                    { "type": "item", "prototype_id": "pistol" },
+                    {
+                        "type": "item",
+                        "prototype_id": "rifle",
+                        "conditions": [{ "type": "min_level", "level": 5 }]
+                    }
+                ]
+            },
+            {
+                "id": "recursive",
+                "entries": [{ "type": "table", "table_id": "recursive" }]
+            }
+        ]"#;
+        let tables: Vec<LootTableParams> = serde_json::from_str(json).unwrap();
+        tables
+            .into_iter()
+            .map(|table| (table.id.clone(), table))
+            .collect()
+    }
+
+    #[test]
+    fn test_loot_rng() {
+        let mut rng = LootRng::new(42);
+        let values: Vec<u64> = (0..8).map(|_| rng.next_u64()).collect();
+        let mut other = LootRng::new(42);
+        assert!(values.iter().all(|value| *value == other.next_u64()));
+
+        for _ in 0..100 {
+            let value = rng.gen_range_u32(2, 4);
+            assert!((2..=4).contains(&value));
+            let value = rng.gen_f32();
+            assert!((0.0..1.0).contains(&value));
+        }
+
+        let choices = [0.0, 1.0, 0.0];
+        for _ in 0..10 {
+            assert_eq!(
+                rng.choose_weighted_index(&choices, |weight| *weight),
+                Some(1)
+            );
+        }
+        assert_eq!(rng.choose_weighted_index(&[0.0], |weight| *weight), None);
+
+        let mut rng = LootRng::from_id("map_1_crate_loot");
+        let mut other = LootRng::from_id("map_1_crate_loot");
+        assert!((0..8).all(|_| rng.next_u64() == other.next_u64()));
+        let mut other = LootRng::from_id("map_2_crate_loot");
+        assert!((0..8).any(|_| rng.next_u64() != other.next_u64()));
+    }
+
+    #[test]
+    fn test_roll_loot_table() {
+        let tables = get_tables();
+        let table = tables.get("bandit").unwrap();
+        let context = LootContext {
+            level: 1,
+            ..Default::default()
+        };
+
+        for seed in 0..50 {
+            let loot = table.roll(&tables, &context, &mut LootRng::new(seed));
+            assert_eq!(loot, table.roll(&tables, &context, &mut LootRng::new(seed)));
+
+            let bandages = loot
+                .drops
+                .iter()
+                .find(|drop| drop.prototype_id == "bandage")
+                .unwrap();
+            assert!(bandages.quantity >= 1 && bandages.quantity <= 3);
+            assert!(loot.credits >= 5);
+            assert!(loot.drops.iter().all(|drop| drop.prototype_id != "letter"));
+            assert!(loot.drops.iter().all(|drop| drop.prototype_id != "rifle"));
+        }
+
+        let context = LootContext {
+            level: 5,
+            active_missions: vec!["find_letter".to_string()],
+            ..Default::default()
+        };
+        let loot = table.roll(&tables, &context, &mut LootRng::new(1));
+        assert!(loot.drops.iter().any(|drop| drop.prototype_id == "letter"));
+    }
+
+    #[test]
+    fn test_recursive_loot_table() {
+        let tables = get_tables();
+        let table = tables.get("recursive").unwrap();
+        let loot = table.roll(&tables, &LootContext::default(), &mut LootRng::new(1));
+        assert!(loot.is_empty());
+    }
+
+    #[test]
+    fn test_instantiate_loot() {
+        let mut items = HashMap::new();
+        items.insert(
+            "ammo".to_string(),
+            ItemParams {
+                id: "ammo".to_string(),
+                max_stack: 10,
+                ..Default::default()
+            },
+        );
+        items.insert(
+            "pistol".to_string(),
+            ItemParams {
+                id: "pistol".to_string(),
+                kind: ItemKind::OneHandedWeapon,
+                ..Default::default()
+            },
+        );
+
+        let mut loot = Loot::default();
+        loot.add_item("ammo", 15);
+        loot.add_item("ammo", 10);
+        loot.add_item("pistol", 2);
+
+        let res = loot.instantiate(&items, &AffixTables::default(), &mut LootRng::new(1));
+        let quantities: Vec<u32> = res.iter().map(|params| params.quantity).collect();
+        assert_eq!(quantities, vec![10, 10, 5, 1, 1]);
+        assert_ne!(res[3].id, res[4].id);
+        assert_eq!(res[4].prototype_id, Some("pistol".to_string()));
+    }
+}
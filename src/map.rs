@@ -15,6 +15,10 @@ pub enum ObjectLayerKind {
     Items,
     SpawnPoints,
     LightSources,
+    Containers,
+    // The objects of a collider layer are polylines and polygons, or rectangles, whose edges are
+    // collided with, using the collision kind and collision layers of the layer
+    Colliders,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +40,10 @@ pub struct MapLayer {
     pub kind: MapLayerKind,
     #[serde(default, skip_serializing_if = "CollisionKind::is_none")]
     pub collision: CollisionKind,
+    // Additional collision layers that the tiles of this layer belong to, on top of the layer
+    // of its collision kind
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collision_layers: Vec<String>,
     #[serde(with = "json::def_uvec2")]
     pub grid_size: UVec2,
     pub tiles: Vec<Option<MapTile>>,
@@ -51,6 +59,7 @@ impl Default for MapLayer {
         MapLayer {
             id: "".to_string(),
             collision: CollisionKind::None,
+            collision_layers: Vec::new(),
             kind: MapLayerKind::TileLayer,
             grid_size: UVec2::ZERO,
             tiles: Vec::new(),
@@ -61,6 +70,16 @@ impl Default for MapLayer {
     }
 }
 
+impl MapLayer {
+    pub fn is_in_collision_mask(&self, mask: &[String]) -> bool {
+        self.collision
+            .get_collision_layer()
+            .into_iter()
+            .chain(self.collision_layers.iter().map(|layer| layer.as_str()))
+            .any(|layer| is_layer_in_mask(layer, mask))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MapTile {
     pub tile_id: u32,
@@ -83,10 +102,53 @@ pub struct MapObject {
         skip_serializing_if = "Option::is_none"
     )]
     pub size: Option<Vec2>,
+    // The points of a polyline, or polygon, relative to the position of the object
+    #[serde(
+        default,
+        with = "json::vec_vec2",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub points: Vec<Vec2>,
+    // If this is `true`, the points form a polygon, so the last point is connected to the first
+    #[serde(default, skip_serializing_if = "helpers::is_false")]
+    pub is_closed: bool,
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub properties: HashMap<String, MapProperty>,
 }
 
+impl MapObject {
+    // Returns the edges of the polyline, or polygon, of the object, or of its rectangle, if it has
+    // no points
+    pub fn get_edges(&self) -> Vec<Collider> {
+        let points: Vec<Vec2> = if !self.points.is_empty() {
+            self.points
+                .iter()
+                .map(|point| self.position + *point)
+                .collect()
+        } else if let Some(size) = self.size {
+            vec![
+                self.position,
+                self.position + vec2(size.x, 0.0),
+                self.position + size,
+                self.position + vec2(0.0, size.y),
+            ]
+        } else {
+            return Vec::new();
+        };
+
+        let is_closed = self.is_closed || self.points.is_empty();
+        let mut edges: Vec<Collider> = points
+            .windows(2)
+            .map(|pair| Collider::edge(pair[0].x, pair[0].y, pair[1].x, pair[1].y))
+            .collect();
+        if is_closed && points.len() > 2 {
+            let (first, last) = (points[0], points[points.len() - 1]);
+            edges.push(Collider::edge(last.x, last.y, first.x, first.y));
+        }
+        edges
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum MapProperty {
@@ -190,8 +252,8 @@ impl Map {
 
     pub fn to_grid(&self, rect: Rect) -> URect {
         let p = self.to_coords(rect.point());
-        let w = ((rect.w / self.tile_size.x) as u32).clamp(0, self.grid_size.x - p.x - 1);
-        let h = ((rect.h / self.tile_size.y) as u32).clamp(0, self.grid_size.y - p.y - 1);
+        let w = ((rect.w / self.tile_size.x) as u32).clamp(0, self.grid_size.x - p.x);
+        let h = ((rect.h / self.tile_size.y) as u32).clamp(0, self.grid_size.y - p.y);
         URect::new(p.x, p.y, w, h)
     }
 
@@ -269,10 +331,30 @@ impl Map {
     }
 
     pub fn get_collisions(&self, collider: Collider) -> Vec<(Vec2, CollisionKind)> {
+        self.find_collisions(collider, |_| true)
+    }
+
+    // This will only return collisions with layers that are in the collision mask
+    pub fn get_masked_collisions(
+        &self,
+        collider: Collider,
+        mask: &[String],
+    ) -> Vec<(Vec2, CollisionKind)> {
+        self.find_collisions(collider, |layer| layer.is_in_collision_mask(mask))
+    }
+
+    fn find_collisions<F>(&self, collider: Collider, filter: F) -> Vec<(Vec2, CollisionKind)>
+    where
+        F: Fn(&MapLayer) -> bool,
+    {
         let rect = self.to_grid(collider.with_padding(self.tile_size.x * 2.0).into());
         let mut collisions = Vec::new();
         for layer in self.layers.values() {
-            if layer.is_visible && layer.collision != CollisionKind::None {
+            if layer.is_visible
+                && layer.collision != CollisionKind::None
+                && matches!(layer.kind, MapLayerKind::TileLayer)
+                && filter(layer)
+            {
                 for (x, y, tile) in self.get_tiles(&layer.id, Some(rect)) {
                     if tile.is_some() {
                         let tile_position = self.to_position(uvec2(x, y));
@@ -293,6 +375,37 @@ impl Map {
         collisions
     }
 
+    // This returns the edges of collider layers, that are in the collision mask, whose bounding
+    // rects overlap the bounding rect of `collider`, so the edges still have to be tested against
+    // the collider, or swept, to find the actual collisions
+    pub fn get_masked_edges(
+        &self,
+        collider: Collider,
+        mask: &[String],
+    ) -> Vec<(Collider, CollisionKind)> {
+        let rect = Rect::from(collider);
+        let mut res = Vec::new();
+        for layer in self.layers.values() {
+            if layer.is_visible
+                && layer.collision != CollisionKind::None
+                && matches!(
+                    layer.kind,
+                    MapLayerKind::ObjectLayer(ObjectLayerKind::Colliders)
+                )
+                && layer.is_in_collision_mask(mask)
+            {
+                for object in &layer.objects {
+                    for edge in object.get_edges() {
+                        if Rect::from(edge).overlaps(&rect) {
+                            res.push((edge, layer.collision.clone()));
+                        }
+                    }
+                }
+            }
+        }
+        res
+    }
+
     pub fn draw(&self, rect: Option<URect>) {
         let rect = rect.unwrap_or_else(|| URect::new(0, 0, self.grid_size.x, self.grid_size.y));
         draw_rectangle(
@@ -367,8 +480,8 @@ impl Map {
 
 impl BaseMap for Map {
     fn is_opaque(&self, idx: usize) -> bool {
-        let x = idx as u32 % self.grid_size.y;
-        let y = idx as u32 / self.grid_size.y;
+        let x = idx as u32 % self.grid_size.x;
+        let y = idx as u32 / self.grid_size.x;
         for (layer_id, layer) in &self.layers {
             if layer.is_visible
                 && layer.collision == CollisionKind::Solid
@@ -392,16 +505,21 @@ impl BaseMap for Map {
         let sw = s - 1;
         let nw = n - 1;
 
+        // Exits to the east or west would wrap around to the next or previous row, at the edges
+        let column = idx as u32 % self.grid_size.x;
+        let has_east = column + 1 < self.grid_size.x;
+        let has_west = column > 0;
+
         // N, NE, E, SE, S, SW, W, NW
         let mut exits = (
             n >= 0 && n < len,
-            ne >= 0 && ne < len,
-            e >= 0 && e < len,
-            se >= 0 && se < len,
+            has_east && ne >= 0 && ne < len,
+            has_east && e >= 0 && e < len,
+            has_east && se >= 0 && se < len,
             s >= 0 && s < len,
-            sw >= 0 && sw < len,
-            w >= 0 && w < len,
-            nw >= 0 && nw < len,
+            has_west && sw >= 0 && sw < len,
+            has_west && w >= 0 && w < len,
+            has_west && nw >= 0 && nw < len,
         );
 
         // Navigation layers
@@ -42,8 +42,8 @@ impl Circle {
 
     /// Checks whether the `Circle` overlaps a `Rect`
     pub fn overlaps_rect(&self, rect: &Rect) -> bool {
-        let dist_x = (self.x - rect.x).abs();
-        let dist_y = (self.y - rect.y).abs();
+        let dist_x = (self.x - (rect.x + rect.w / 2.0)).abs();
+        let dist_y = (self.y - (rect.y + rect.h / 2.0)).abs();
         if dist_x > rect.w / 2.0 + self.r || dist_y > rect.h / 2.0 + self.r {
             return false;
         }
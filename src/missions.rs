@@ -66,6 +66,10 @@ pub enum MissionReward {
     Credits {
         amount: u32,
     },
+    // Rolls a loot table for the actor that completed the mission
+    LootTable {
+        table_id: String,
+    },
     #[serde(rename = "xp", alias = "experience")]
     Experience {
         amount: u32,
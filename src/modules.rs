@@ -1,5 +1,6 @@
 use std::path::Path;
 
+use crate::inventory::extend_equipment_slots;
 use crate::prelude::*;
 
 use crate::resources::{
@@ -20,6 +21,13 @@ pub(crate) enum ModuleDataFileKind {
     Items,
     Abilities,
     Scenario,
+    Stats,
+    EquipmentSlots,
+    Affixes,
+    LootTables,
+    Containers,
+    Vendors,
+    Locales,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -291,6 +299,81 @@ pub(crate) async fn load_modules(
                         }
                     }
                 }
+                ModuleDataFileKind::Stats => {
+                    let params: StatFormulas = serde_json::from_slice(&bytes)?;
+                    match data.integration {
+                        ModuleIntegration::Extend => {
+                            resources.stat_formulas.extend(params);
+                        }
+                        ModuleIntegration::Replace => {
+                            // The toolkit's own derived stats will still fall back to the defaults
+                            let mut stat_formulas = StatFormulas::default();
+                            stat_formulas.extend(params);
+                            resources.stat_formulas = stat_formulas;
+                        }
+                    }
+
+                    resources.stat_formulas.validate()?;
+                }
+                ModuleDataFileKind::EquipmentSlots => {
+                    let slots: Vec<EquipmentSlotParams> = serde_json::from_slice(&bytes)?;
+                    match data.integration {
+                        ModuleIntegration::Extend => {
+                            extend_equipment_slots(&mut resources.equipment_slots, slots);
+                        }
+                        ModuleIntegration::Replace => {
+                            resources.equipment_slots = slots;
+                        }
+                    }
+                }
+                ModuleDataFileKind::Affixes => {
+                    let params: AffixTables = serde_json::from_slice(&bytes)?;
+                    match data.integration {
+                        ModuleIntegration::Extend => {
+                            resources.affixes.extend(params);
+                        }
+                        ModuleIntegration::Replace => {
+                            resources.affixes = params;
+                        }
+                    }
+                }
+                ModuleDataFileKind::LootTables => {
+                    let tables: Vec<LootTableParams> = serde_json::from_slice(&bytes)?;
+                    if data.integration == ModuleIntegration::Replace {
+                        resources.loot_tables = HashMap::new();
+                    }
+                    for table in tables {
+                        resources.loot_tables.insert(table.id.clone(), table);
+                    }
+                }
+                ModuleDataFileKind::Containers => {
+                    let containers: Vec<ContainerParams> = serde_json::from_slice(&bytes)?;
+                    if data.integration == ModuleIntegration::Replace {
+                        resources.containers = HashMap::new();
+                    }
+                    for params in containers {
+                        resources.containers.insert(params.id.clone(), params);
+                    }
+                }
+                ModuleDataFileKind::Vendors => {
+                    let vendors: Vec<VendorParams> = serde_json::from_slice(&bytes)?;
+                    if data.integration == ModuleIntegration::Replace {
+                        resources.vendors = HashMap::new();
+                    }
+                    for params in vendors {
+                        resources.vendors.insert(params.id.clone(), params);
+                    }
+                }
+                ModuleDataFileKind::Locales => {
+                    // The paths of the string tables are relative to the module directory
+                    let params: Vec<LocaleParams> = serde_json::from_slice(&bytes)?;
+                    let mut localization = storage::get::<Localization>().clone();
+                    if data.integration == ModuleIntegration::Replace {
+                        localization.locales = HashMap::new();
+                    }
+                    localization.add_locales(&module_path, params).await?;
+                    storage::store(localization);
+                }
                 ModuleDataFileKind::Scenario => {
                     let scenario_params: Vec<ChapterParams> = serde_json::from_slice(&bytes)?;
 
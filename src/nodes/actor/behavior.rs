@@ -34,7 +34,7 @@ pub struct ActorBehaviorParams {
     #[serde(skip)]
     pub attackers: HashMap<String, Handle<Actor>>,
     #[serde(skip)]
-    pub collisions: Vec<(Collider, CollisionKind)>,
+    pub collisions: Vec<Collision>,
 }
 
 impl Default for ActorBehaviorParams {
@@ -29,6 +29,7 @@ pub struct ActorController {
     pub is_sprint_locked: bool,
     pub should_respawn: bool,
     pub equip_weapon: Option<String>,
+    pub use_item: Option<String>,
 }
 
 impl ActorController {
@@ -46,6 +47,7 @@ impl ActorController {
             is_sprint_locked: false,
             should_respawn: false,
             equip_weapon: None,
+            use_item: None,
         }
     }
 
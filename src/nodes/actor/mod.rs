@@ -40,6 +40,9 @@ pub struct ActorParams {
     pub willpower: u32,
     pub perception: u32,
     pub charisma: u32,
+    // Values for custom attributes, as defined in a game's `stats.json`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub attributes: HashMap<String, u32>,
     #[serde(default)]
     pub current_health: f32,
     #[serde(default)]
@@ -47,7 +50,9 @@ pub struct ActorParams {
     #[serde(default)]
     pub current_energy: f32,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub collider: Option<Collider>,
+    pub collider: Option<ColliderParams>,
+    #[serde(default = "ActorParams::default_mass")]
+    pub mass: f32,
     #[serde(default, flatten)]
     pub inventory: InventoryParams,
     #[serde(default)]
@@ -57,8 +62,42 @@ pub struct ActorParams {
     pub experience: u32,
     #[serde(default, skip_serializing_if = "helpers::is_false")]
     pub can_level_up: bool,
+    #[serde(default = "ActorParams::default_level")]
+    pub level: u32,
+    #[serde(default)]
+    pub attribute_points: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unlocked_abilities: Vec<String>,
+    #[serde(
+        default,
+        rename = "selected_ability",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub selected_ability_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub status_effects: Vec<StatusEffect>,
+    // The ids of the items that are assigned to each of the slots of the hotbar
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hotbar: Vec<Option<String>>,
     #[serde(default, rename = "dialogue", skip_serializing_if = "Option::is_none")]
     pub dialogue_id: Option<String>,
+    // A loot table that is rolled when the actor dies, in addition to dropping its inventory
+    #[serde(
+        default,
+        rename = "loot_table",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub loot_table_id: Option<String>,
+    // If this is `true`, the actor will leave a corpse that can be looted when it dies, instead of
+    // dropping its inventory and loot on the ground
+    #[serde(default, skip_serializing_if = "helpers::is_false")]
+    pub leaves_corpse: bool,
+    // The reputation of the actor with factions, by faction id
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub reputation: HashMap<String, i32>,
+    // A vendor that the player can trade with, through the `open_trade` dialogue action
+    #[serde(default, rename = "vendor", skip_serializing_if = "Option::is_none")]
+    pub vendor_id: Option<String>,
 }
 
 impl Default for ActorParams {
@@ -78,20 +117,42 @@ impl Default for ActorParams {
             willpower: 8,
             perception: 8,
             charisma: 8,
+            attributes: HashMap::new(),
             current_health: 0.0,
             current_stamina: 0.0,
             current_energy: 0.0,
             collider: None,
+            mass: DEFAULT_MASS,
             inventory: Default::default(),
             equipped_items: Default::default(),
             animation_player: Default::default(),
             experience: 0,
             can_level_up: false,
+            level: STARTING_LEVEL,
+            attribute_points: 0,
+            unlocked_abilities: Vec::new(),
+            selected_ability_id: None,
+            status_effects: Vec::new(),
+            hotbar: Vec::new(),
             dialogue_id: None,
+            loot_table_id: None,
+            leaves_corpse: false,
+            reputation: HashMap::new(),
+            vendor_id: None,
         }
     }
 }
 
+impl ActorParams {
+    pub fn default_mass() -> f32 {
+        DEFAULT_MASS
+    }
+
+    pub fn default_level() -> u32 {
+        STARTING_LEVEL
+    }
+}
+
 impl From<ActorParams> for ActorStats {
     fn from(params: ActorParams) -> ActorStats {
         ActorStats {
@@ -102,6 +163,7 @@ impl From<ActorParams> for ActorStats {
             willpower: params.willpower,
             perception: params.perception,
             charisma: params.charisma,
+            attributes: params.attributes,
             current_health: params.current_health,
             current_stamina: params.current_stamina,
             current_energy: params.current_energy,
@@ -115,25 +177,38 @@ impl From<ActorParams> for Character {
         let game_params = storage::get::<GameParams>();
         let resources = storage::get::<Resources>();
 
-        let mut item_ids = Vec::new();
-        let mut items = Vec::new();
+        let inventory = Inventory::from_prototypes(&params.inventory);
+        let items = inventory
+            .items
+            .iter()
+            .map(|entry| entry.params.clone())
+            .collect();
 
-        for entry in params.inventory.items {
-            let id = generate_id();
-            let params = resources.items.get(&entry).cloned().unwrap();
-            items.push(ItemParams {
-                id: id.clone(),
-                ..params
-            });
-            item_ids.push(id);
+        // Abilities that the class unlocks at, or below, the starting level of the character
+        let mut unlocked_abilities = params.unlocked_abilities.clone();
+        if let Some(class) = params
+            .class_id
+            .as_ref()
+            .and_then(|class_id| resources.character_classes.get(class_id))
+        {
+            for unlock in &class.ability_unlocks {
+                if unlock.level <= params.level && !unlocked_abilities.contains(&unlock.ability_id)
+                {
+                    unlocked_abilities.push(unlock.ability_id.clone());
+                }
+            }
         }
 
+        let selected_ability_id = params
+            .selected_ability_id
+            .clone()
+            .or_else(|| unlocked_abilities.first().cloned());
+
         let actor = ActorParams {
             id: generate_id(),
-            inventory: InventoryParams {
-                items: item_ids,
-                credits: params.inventory.credits,
-            },
+            inventory: inventory.to_params(),
+            unlocked_abilities,
+            selected_ability_id,
             ..params
         };
 
@@ -150,6 +225,10 @@ impl From<ActorParams> for Character {
             chapter_index,
             map_id,
             is_permadeath: false,
+            completed_chapters: Vec::new(),
+            variables: HashMap::new(),
+            containers: HashMap::new(),
+            vendors: HashMap::new(),
         }
     }
 }
@@ -170,14 +249,29 @@ pub struct Actor {
     pub equipped_items: EquippedItems,
     pub weapon_ability: EquippedWeaponsAbilities,
     pub selected_ability: Option<Ability>,
+    pub status_effects: Vec<StatusEffect>,
+    pub hotbar: Vec<Option<String>>,
     pub controller: ActorController,
     pub experience: u32,
+    pub level: u32,
+    pub attribute_points: u32,
+    pub unlocked_abilities: Vec<String>,
     pub dialogue: Option<Dialogue>,
     pub current_dialogue: Option<Dialogue>,
+    pub loot_table_id: Option<String>,
+    pub leaves_corpse: bool,
+    // The container that the actor currently has open
+    pub current_container: Option<Handle<Container>>,
+    pub reputation: HashMap<String, i32>,
+    pub vendor_id: Option<String>,
+    // The vendor that the actor is currently trading with
+    pub current_vendor: Option<String>,
     pub game_state: Handle<GameState>,
     animation_player: SpriteAnimationPlayer,
     automaton: Automaton<ActorBehaviorFamily>,
     noise_level_timer: f32,
+    dash_timer: f32,
+    item_cooldowns: HashMap<String, f32>,
     can_level_up: bool,
 }
 
@@ -196,8 +290,18 @@ impl Actor {
 
     const NOISE_LEVEL_COOLDOWN: f32 = 1.5;
 
+    const MAX_SPEED: f32 = 12.0;
+
+    const DASH_IMPULSE: f32 = 6.0;
+    const DASH_STAMINA_COST: f32 = 20.0;
+    const DASH_COOLDOWN: f32 = 0.75;
+    const DASH_NOISE_LEVEL: NoiseLevel = NoiseLevel::Moderate;
+
+    pub const HOTBAR_SIZE: usize = 5;
+
     const PICK_UP_RADIUS: f32 = 36.0;
     const INTERACT_RADIUS: f32 = 36.0;
+    const CONTAINER_CLOSE_DISTANCE: f32 = 64.0;
 
     pub fn new(
         game_state: Handle<GameState>,
@@ -212,7 +316,9 @@ impl Actor {
         let dialogue = if let Some(dialogue_id) = params.dialogue_id.clone() {
             let resources = storage::get::<Resources>();
             let mut dialogue = resources.dialogue.get(&dialogue_id).cloned().unwrap();
+            dialogue.actor_id = params.id.clone();
             dialogue.actor_name = params.name.clone();
+            dialogue.vendor_id = params.vendor_id.clone();
             Some(dialogue)
         } else {
             None
@@ -222,10 +328,17 @@ impl Actor {
 
         let inventory = Inventory::from_prototypes(&params.inventory);
 
+        let body = Self::create_body(position, &params);
+
+        let selected_ability = params
+            .selected_ability_id
+            .as_deref()
+            .map(Self::create_ability);
+
         let behavior_set_id = &params.behavior.behavior_set_id;
         let behavior_constructor = get_behavior_set(behavior_set_id);
 
-        Actor {
+        let mut actor = Actor {
             id: params.id,
             is_essential: params.is_essential,
             class_id: params.class_id,
@@ -236,24 +349,42 @@ impl Actor {
             behavior: params.behavior,
             stats,
             factions: params.factions,
-            body: PhysicsBody::new(position, 0.0, params.collider),
+            body,
             inventory,
             equipped_items: params.equipped_items,
             weapon_ability: EquippedWeaponsAbilities {
                 main_hand: None,
                 offhand: None,
             },
-            selected_ability: None,
+            selected_ability,
+            status_effects: params.status_effects,
+            hotbar: Self::create_hotbar(&params.hotbar),
             controller: ActorController::new(controller_kind),
             experience: params.experience,
+            level: params.level,
+            attribute_points: params.attribute_points,
+            unlocked_abilities: params.unlocked_abilities,
             dialogue,
             current_dialogue: None,
+            loot_table_id: params.loot_table_id,
+            leaves_corpse: params.leaves_corpse,
+            current_container: None,
+            reputation: params.reputation.clone(),
+            vendor_id: params.vendor_id.clone(),
+            current_vendor: None,
             animation_player: SpriteAnimationPlayer::new(params.animation_player),
             noise_level_timer: 0.0,
+            dash_timer: Self::DASH_COOLDOWN,
+            item_cooldowns: HashMap::new(),
             can_level_up: params.can_level_up,
             automaton: ActorBehaviorFamily::automaton_with_mode(behavior_constructor()),
             game_state,
-        }
+        };
+
+        actor.reequip_items();
+        actor.apply_class_perks();
+        actor.apply_status_effect_modifiers();
+        actor
     }
 
     pub fn add_node(
@@ -264,6 +395,47 @@ impl Actor {
         scene::add_node(Self::new(game_state, controller_kind, params))
     }
 
+    fn create_body(position: Vec2, params: &ActorParams) -> PhysicsBody {
+        let collider = params.collider.as_ref().map(|params| params.collider);
+        let mut body = PhysicsBody::new(position, 0.0, collider);
+        body.mass = params.mass;
+        body.max_speed = Some(Self::MAX_SPEED);
+
+        if let Some(params) = &params.collider {
+            if let Some(collision_layer) = &params.collision_layer {
+                body.collision_layer = collision_layer.clone();
+            }
+            if let Some(collision_mask) = &params.collision_mask {
+                body.collision_mask = collision_mask.clone();
+            }
+        }
+
+        body
+    }
+
+    // Collision layer and mask will only be exported if they differ from the defaults
+    fn get_collider_params(&self) -> Option<ColliderParams> {
+        self.body.collider.map(|collider| {
+            let collision_layer = if self.body.collision_layer != ACTOR_COLLISION_LAYER {
+                Some(self.body.collision_layer.clone())
+            } else {
+                None
+            };
+
+            let collision_mask = if self.body.collision_mask != default_body_collision_mask() {
+                Some(self.body.collision_mask.clone())
+            } else {
+                None
+            };
+
+            ColliderParams {
+                collider,
+                collision_layer,
+                collision_mask,
+            }
+        })
+    }
+
     pub fn to_params(&self) -> ActorParams {
         if self.can_level_up {
             assert!(self.class_id.is_some(), "Actor id '{}' has `can_level_up` set to `true`, even though no `class_id` has been specified!", &self.id);
@@ -271,6 +443,17 @@ impl Actor {
 
         let dialogue_id = self.dialogue.as_ref().map(|dialogue| dialogue.id.clone());
 
+        let selected_ability_id = self
+            .selected_ability
+            .as_ref()
+            .map(|ability| ability.id.clone());
+
+        let hotbar = if self.hotbar.iter().any(Option::is_some) {
+            self.hotbar.clone()
+        } else {
+            Vec::new()
+        };
+
         ActorParams {
             id: self.id.clone(),
             class_id: self.class_id.clone(),
@@ -285,17 +468,29 @@ impl Actor {
             willpower: self.stats.willpower,
             perception: self.stats.perception,
             charisma: self.stats.charisma,
+            attributes: self.stats.attributes.clone(),
             current_health: self.stats.current_health,
             current_stamina: self.stats.current_stamina,
             current_energy: self.stats.current_energy,
             factions: self.factions.clone(),
-            collider: self.body.collider,
+            collider: self.get_collider_params(),
+            mass: self.body.mass,
             inventory: self.inventory.to_params(),
             equipped_items: self.equipped_items.clone(),
             animation_player: self.animation_player.clone().into(),
             experience: self.experience,
             can_level_up: self.can_level_up,
+            level: self.level,
+            attribute_points: self.attribute_points,
+            unlocked_abilities: self.unlocked_abilities.clone(),
+            selected_ability_id,
+            status_effects: self.status_effects.clone(),
+            hotbar,
             dialogue_id,
+            loot_table_id: self.loot_table_id.clone(),
+            leaves_corpse: self.leaves_corpse,
+            reputation: self.reputation.clone(),
+            vendor_id: self.vendor_id.clone(),
         }
     }
 
@@ -325,7 +520,7 @@ impl Actor {
             })
             .collect();
 
-        let body = PhysicsBody::new(position, 0.0, character.actor.collider);
+        let body = Self::create_body(position, &character.actor);
 
         let dialogue = if let Some(dialogue_id) = &character.actor.dialogue_id {
             resources.dialogue.get(dialogue_id).cloned()
@@ -333,12 +528,18 @@ impl Actor {
             None
         };
 
+        let selected_ability = character
+            .actor
+            .selected_ability_id
+            .as_deref()
+            .map(Self::create_ability);
+
         let behavior_set_id = &character.actor.behavior.behavior_set_id;
         let behavior_constructor = get_behavior_set(behavior_set_id);
 
         let stats = character.actor.clone().into();
 
-        Actor {
+        let mut actor = Actor {
             id: character.actor.id.clone(),
             class_id: character.actor.class_id.clone(),
             is_essential: character.actor.is_essential,
@@ -356,17 +557,35 @@ impl Actor {
                 main_hand: None,
                 offhand: None,
             },
-            selected_ability: None,
+            selected_ability,
+            status_effects: character.actor.status_effects.clone(),
+            hotbar: Self::create_hotbar(&character.actor.hotbar),
             controller: ActorController::new(controller_kind),
             experience: character.actor.experience,
+            level: character.actor.level,
+            attribute_points: character.actor.attribute_points,
+            unlocked_abilities: character.actor.unlocked_abilities.clone(),
             dialogue,
             current_dialogue: None,
+            loot_table_id: character.actor.loot_table_id.clone(),
+            leaves_corpse: character.actor.leaves_corpse,
+            current_container: None,
+            reputation: character.actor.reputation.clone(),
+            vendor_id: character.actor.vendor_id.clone(),
+            current_vendor: None,
             animation_player: SpriteAnimationPlayer::new(character.actor.animation_player.clone()),
             noise_level_timer: 0.0,
+            dash_timer: Self::DASH_COOLDOWN,
+            item_cooldowns: HashMap::new(),
             can_level_up: character.actor.can_level_up,
             automaton: ActorBehaviorFamily::automaton_with_mode(behavior_constructor()),
             game_state,
-        }
+        };
+
+        actor.reequip_items();
+        actor.apply_class_perks();
+        actor.apply_status_effect_modifiers();
+        actor
     }
 
     pub fn to_character(
@@ -407,20 +626,82 @@ impl Actor {
             chapter_index,
             map_id,
             is_permadeath,
+            completed_chapters: Vec::new(),
+            variables: HashMap::new(),
+            containers: HashMap::new(),
+            vendors: HashMap::new(),
         }
     }
 
+    // This rolls for evasion and applies resistance and armor, before subtracting the damage from
+    // the actor's health. The result is returned and dispatched with `Event::DamageTaken`.
     pub fn take_damage(
         &mut self,
         actor_id: &str,
         actor: Handle<Actor>,
-        _damage_type: DamageType,
+        damage_type: DamageType,
         amount: f32,
-    ) {
-        self.behavior.attackers.insert(actor_id.to_string(), actor);
+        critical_multiplier: Option<f32>,
+        is_evaded: bool,
+    ) -> DamageResult {
+        if actor_id != self.id {
+            self.behavior.attackers.insert(actor_id.to_string(), actor);
+        }
+
+        let result = if is_evaded {
+            DamageResult::evaded(damage_type, amount, critical_multiplier)
+        } else {
+            DamageResult::new(&self.stats, damage_type, amount, critical_multiplier)
+        };
+
+        self.apply_damage(actor_id, result.clone());
+        result
+    }
+
+    fn apply_damage(&mut self, source_id: &str, result: DamageResult) {
         if !self.is_essential {
-            self.stats.current_health -= amount;
+            self.stats.current_health -= result.amount;
         }
+
+        dispatch_event(Event::DamageTaken {
+            actor_id: self.id.clone(),
+            source_id: source_id.to_string(),
+            result,
+        });
+    }
+
+    // This applies all the effects of a hit, like a projectile or a melee attack, and returns
+    // `true` if any of them applied to the actor. Evasion is rolled once for the whole hit, and if
+    // it is evaded, no damage will be taken and no knockback will be applied.
+    pub fn apply_hit(
+        &mut self,
+        actor_id: &str,
+        actor: Handle<Actor>,
+        factions: &[String],
+        direction: Vec2,
+        effects: &[AbilityEffect],
+    ) -> bool {
+        let is_evadable = effects.iter().any(|effect| {
+            matches!(effect.effect, Effect::Damage { .. })
+                && self.is_valid_target(actor_id, factions, effect.target)
+        });
+        let is_evaded = is_evadable && roll_evasion(&self.stats);
+
+        let mut is_applied = false;
+        for effect in effects {
+            if self.apply_effect(
+                actor_id,
+                actor,
+                factions,
+                direction,
+                effect.clone(),
+                is_evaded,
+            ) {
+                is_applied = true;
+            }
+        }
+
+        is_applied
     }
 
     pub fn apply_effect(
@@ -428,24 +709,238 @@ impl Actor {
         actor_id: &str,
         actor: Handle<Actor>,
         factions: &[String],
-        effect: Effect,
+        direction: Vec2,
+        effect: AbilityEffect,
+        is_evaded: bool,
     ) -> bool {
-        match effect {
+        if !self.is_valid_target(actor_id, factions, effect.target) {
+            return false;
+        }
+
+        match effect.effect {
             Effect::Damage {
                 damage_type,
                 amount,
+                critical_multiplier,
             } => {
-                if actor_id != self.id {
+                self.take_damage(
+                    actor_id,
+                    actor,
+                    damage_type,
+                    amount,
+                    critical_multiplier,
+                    is_evaded,
+                );
+            }
+            Effect::Knockback { force } => {
+                if !is_evaded {
+                    self.body
+                        .apply_impulse(direction.normalize_or_zero() * force);
+                }
+            }
+            Effect::ApplyStatus { status } => {
+                self.apply_status_effect(actor_id, Some(actor), status);
+            }
+            Effect::Heal { amount } => {
+                self.stats.restore_health(amount);
+            }
+            Effect::RestoreStamina { amount } => {
+                self.stats.restore_stamina(amount);
+            }
+            Effect::RestoreEnergy { amount } => {
+                self.stats.restore_energy(amount);
+            }
+            Effect::Teleport { distance } => {
+                let map = storage::get::<Map>();
+                self.body.teleport(&map, direction, distance);
+            }
+            Effect::SpawnActor {
+                prototype_id,
+                inherit_factions,
+            } => {
+                let resources = storage::get::<Resources>();
+                let params = resources
+                    .actors
+                    .get(&prototype_id)
+                    .cloned()
+                    .unwrap_or_else(|| panic!("Unable to find actor with id '{}'", &prototype_id));
+
+                let mut params = ActorParams {
+                    id: generate_id(),
+                    position: Some(self.body.position),
+                    ..params
+                };
+
+                if inherit_factions {
                     for faction in factions {
-                        if self.factions.contains(faction) {
-                            return false;
+                        if !params.factions.contains(faction) {
+                            params.factions.push(faction.clone());
                         }
                     }
-                    self.take_damage(actor_id, actor, damage_type, amount);
-                    return true;
                 }
-                false
+
+                let mut actor = Actor::new(self.game_state, ActorControllerKind::Computer, params);
+                actor.stats.recalculate_derived();
+                actor.stats.restore_vitals();
+                scene::add_node(actor);
+            }
+            Effect::SpawnItem { prototype_id } => {
+                let resources = storage::get::<Resources>();
+                let params = resources
+                    .items
+                    .get(&prototype_id)
+                    .cloned()
+                    .unwrap_or_else(|| panic!("Unable to find item with id '{}'", &prototype_id));
+
+                Item::add_node(ItemParams {
+                    position: Some(self.body.position),
+                    ..resources.affixes.generate(&params)
+                });
+            }
+        }
+
+        true
+    }
+
+    // This checks whether an effect with `target` can be applied to this actor, when its source
+    // is the actor with `actor_id` and `factions`
+    pub fn is_valid_target(
+        &self,
+        actor_id: &str,
+        factions: &[String],
+        target: EffectTarget,
+    ) -> bool {
+        let is_source = actor_id == self.id;
+        let is_ally = factions
+            .iter()
+            .any(|faction| self.factions.contains(faction));
+
+        match target {
+            EffectTarget::Source => is_source,
+            EffectTarget::Allies => !is_source && is_ally,
+            EffectTarget::Enemies => !is_source && !is_ally,
+            EffectTarget::All => !is_source,
+        }
+    }
+
+    pub fn apply_status_effect(
+        &mut self,
+        source_id: &str,
+        source: Option<Handle<Actor>>,
+        params: StatusEffectParams,
+    ) {
+        if let Some(status_effect) = self
+            .status_effects
+            .iter_mut()
+            .find(|status_effect| status_effect.params.id == params.id)
+        {
+            match status_effect.params.stacking {
+                StatusEffectStacking::Ignore => return,
+                StatusEffectStacking::Refresh => {}
+                StatusEffectStacking::Stack => {
+                    if status_effect.stacks < status_effect.params.max_stacks {
+                        status_effect.stacks += 1;
+                    }
+                }
+            }
+            status_effect.time_remaining = status_effect.params.duration;
+            status_effect.source_id = source_id.to_string();
+            status_effect.source = source;
+        } else {
+            self.status_effects
+                .push(StatusEffect::new(source_id, source, params));
+        }
+
+        self.apply_status_effect_modifiers();
+    }
+
+    pub fn remove_status_effect(&mut self, id: &str) {
+        if let Some(i) = self
+            .status_effects
+            .iter()
+            .position(|status_effect| status_effect.params.id == id)
+        {
+            let status_effect = self.status_effects.remove(i);
+            self.stats
+                .modifiers
+                .remove(&status_effect.get_modifier_source());
+            self.stats.recalculate_derived();
+        }
+    }
+
+    pub fn has_status_effect(&self, id: &str) -> bool {
+        self.status_effects
+            .iter()
+            .any(|status_effect| status_effect.params.id == id)
+    }
+
+    pub fn is_stunned(&self) -> bool {
+        self.status_effects
+            .iter()
+            .any(|status_effect| status_effect.params.kind == StatusEffectKind::Stun)
+    }
+
+    pub fn is_silenced(&self) -> bool {
+        self.status_effects
+            .iter()
+            .any(|status_effect| status_effect.params.kind == StatusEffectKind::Silence)
+    }
+
+    fn apply_status_effect_modifiers(&mut self) {
+        for status_effect in &self.status_effects {
+            self.stats.modifiers.add(
+                &status_effect.get_modifier_source(),
+                &status_effect.get_modifiers(),
+            );
+        }
+        self.stats.recalculate_derived();
+    }
+
+    // This applies damage and healing over time and removes expired status effects
+    fn update_status_effects(&mut self, dt: f32) {
+        let mut status_effects = Vec::new();
+        std::mem::swap(&mut status_effects, &mut self.status_effects);
+
+        let mut is_modified = false;
+        for status_effect in &mut status_effects {
+            let ticks = status_effect.update(dt);
+            let stacks = status_effect.stacks as f32;
+            for _ in 0..ticks {
+                match status_effect.params.kind.clone() {
+                    StatusEffectKind::DamageOverTime {
+                        damage_type,
+                        amount,
+                    } => {
+                        // Damage over time can not be evaded, but it is affected by resistance
+                        if let Some(source) = status_effect.source {
+                            self.behavior
+                                .attackers
+                                .insert(status_effect.source_id.clone(), source);
+                        }
+                        let result =
+                            DamageResult::new(&self.stats, damage_type, amount * stacks, None);
+                        self.apply_damage(&status_effect.source_id, result);
+                    }
+                    StatusEffectKind::HealOverTime { amount } => {
+                        self.stats.restore_health(amount * stacks);
+                    }
+                    _ => {}
+                }
             }
+
+            if status_effect.is_expired() {
+                self.stats
+                    .modifiers
+                    .remove(&status_effect.get_modifier_source());
+                is_modified = true;
+            }
+        }
+
+        status_effects.retain(|status_effect| !status_effect.is_expired());
+        self.status_effects = status_effects;
+
+        if is_modified {
+            self.stats.recalculate_derived();
         }
     }
 
@@ -477,6 +972,25 @@ impl Actor {
         None
     }
 
+    // This collects the colliders of all other actors, releasing each node as soon as it has been
+    // read, so that it doesn't matter which nodes the caller is already holding
+    #[cfg(feature = "collision-between-actors")]
+    fn get_actor_obstacles(exclude_id: &str) -> Vec<ActorObstacle> {
+        let mut res = Vec::new();
+        for actor in scene::find_nodes_by_type::<Actor>() {
+            if actor.id != exclude_id {
+                if let Some(collider) = actor.body.get_offset_collider() {
+                    res.push(ActorObstacle {
+                        collider,
+                        collision_layer: actor.body.collision_layer.clone(),
+                        mass: actor.body.mass,
+                    });
+                }
+            }
+        }
+        res
+    }
+
     pub fn is_local_player(&self) -> bool {
         use ActorControllerKind::*;
         matches!(&self.controller.kind, LocalPlayer { player_id: _ })
@@ -524,10 +1038,75 @@ impl Actor {
             && self.body.raycast(target, true, true).is_none()
     }
 
+    // Every level gained will award the attribute points, and unlock the abilities, defined by
+    // the actor's class, and dispatch an `Event::LevelUp`
     pub fn add_experience(&mut self, amount: u32) {
         if self.can_level_up {
-            self.experience += amount;
+            self.experience = self.experience.saturating_add(amount);
+
+            let resources = storage::get::<Resources>();
+            let class_id = self.class_id.clone().unwrap();
+            let class = resources
+                .character_classes
+                .get(&class_id)
+                .unwrap_or_else(|| {
+                    panic!("Unable to find character class with id '{}'", &class_id)
+                });
+
+            let level = class.get_level(self.experience);
+            while self.level < level {
+                self.level += 1;
+                self.attribute_points += class.attribute_points_per_level;
+
+                for unlock in &class.ability_unlocks {
+                    if unlock.level == self.level
+                        && !self.unlocked_abilities.contains(&unlock.ability_id)
+                    {
+                        self.unlocked_abilities.push(unlock.ability_id.clone());
+                        if self.selected_ability.is_none() {
+                            self.selected_ability = Some(Self::create_ability(&unlock.ability_id));
+                        }
+                    }
+                }
+
+                dispatch_event(Event::LevelUp {
+                    actor_id: self.id.clone(),
+                    level: self.level,
+                });
+            }
+
+            drop(resources);
+            self.apply_class_perks();
+        }
+    }
+
+    // This applies the modifiers of all the perks of the actor's class, that it has the level for
+    fn apply_class_perks(&mut self) {
+        if let Some(class_id) = &self.class_id {
+            let resources = storage::get::<Resources>();
+            if let Some(class) = resources.character_classes.get(class_id) {
+                for perk in &class.perks {
+                    if perk.level <= self.level && !self.stats.modifiers.has_source(&perk.id) {
+                        self.stats.modifiers.add(&perk.id, &perk.modifiers);
+                    }
+                }
+            }
         }
+        self.stats.recalculate_derived();
+    }
+
+    pub fn select_ability(&mut self, ability_id: &str) {
+        self.selected_ability = Some(Self::create_ability(ability_id));
+    }
+
+    fn create_ability(ability_id: &str) -> Ability {
+        let resources = storage::get::<Resources>();
+        let params = resources
+            .abilities
+            .get(ability_id)
+            .cloned()
+            .unwrap_or_else(|| panic!("Unable to find ability with id '{}'", ability_id));
+        Ability::new(params)
     }
 
     fn update_noise_level(&mut self) {
@@ -543,6 +1122,35 @@ impl Actor {
         }
     }
 
+    // Dashes are applied as an impulse in the direction the actor is moving, or aiming if it is
+    // standing still. The impulse is scaled by mass, so that all actors dash the same distance.
+    fn dash(&mut self) -> bool {
+        if self.dash_timer < Self::DASH_COOLDOWN
+            || self.stats.current_stamina < Self::DASH_STAMINA_COST
+        {
+            return false;
+        }
+
+        let direction = if self.controller.move_direction != Vec2::ZERO {
+            self.controller.move_direction
+        } else {
+            self.controller.aim_direction
+        }
+        .normalize_or_zero();
+
+        if direction == Vec2::ZERO {
+            return false;
+        }
+
+        self.body
+            .apply_impulse(direction * Self::DASH_IMPULSE * self.body.mass);
+        self.stats.current_stamina -= Self::DASH_STAMINA_COST;
+        self.set_noise_level(Self::DASH_NOISE_LEVEL);
+        self.dash_timer = 0.0;
+
+        true
+    }
+
     fn update_missions(&mut self) {
         let mut active_missions = self.active_missions.clone();
         for i in 0..active_missions.len() {
@@ -592,14 +1200,36 @@ impl Actor {
                                 prototype_id,
                                 amount,
                             } => {
-                                let params = resources.items.get(prototype_id).unwrap();
-                                for _ in 0..*amount {
-                                    self.inventory.add_item(params.clone());
+                                let params = ItemParams {
+                                    quantity: *amount,
+                                    ..resources.items.get(prototype_id).unwrap().instantiate()
+                                };
+                                // Rewards that don't fit in the inventory are dropped
+                                if let Some(remainder) = self.inventory.add_item(params) {
+                                    let position = self.body.position;
+                                    Item::add_node(ItemParams {
+                                        position: Some(position),
+                                        ..remainder
+                                    });
                                 }
                             }
                             MissionReward::Credits { amount } => {
                                 self.inventory.add_credits(*amount);
                             }
+                            MissionReward::LootTable { table_id } => {
+                                let context = LootContext::from_actor(self);
+                                let (items, credits) = roll_loot_table(table_id, &context);
+                                for params in items {
+                                    if let Some(remainder) = self.inventory.add_item(params) {
+                                        let position = self.body.position;
+                                        Item::add_node(ItemParams {
+                                            position: Some(position),
+                                            ..remainder
+                                        });
+                                    }
+                                }
+                                self.inventory.add_credits(credits);
+                            }
                             MissionReward::Experience { amount } => {
                                 self.add_experience(*amount);
                             }
@@ -625,56 +1255,161 @@ impl Actor {
         self.completed_missions.append(&mut completed_missions);
     }
 
-    pub fn equip_item(&mut self, item_id: &str) {
-        let item_id = item_id.to_string();
-        let mut found_entry = None;
-        for entry in &self.inventory.items {
-            if entry.params.id == item_id {
-                found_entry = Some(entry.clone());
+    // Corpses that have been emptied are removed when they are closed
+    pub fn close_container(&mut self) {
+        if let Some(handle) = self.current_container.take() {
+            if let Some(container) = scene::try_get_node(handle) {
+                if container.is_corpse && container.is_empty() {
+                    container.delete();
+                }
             }
         }
-        let mut slot = EquipmentSlot::None;
-        if let Some(entry) = found_entry {
-            slot = match entry.params.kind {
-                ItemKind::OneHandedWeapon => {
-                    if self.equipped_items.main_hand.is_some()
-                        && self.equipped_items.off_hand.is_none()
-                    {
-                        EquipmentSlot::OffHand
-                    } else {
-                        EquipmentSlot::MainHand
-                    }
-                }
-                ItemKind::TwoHandedWeapon => EquipmentSlot::BothHands,
-                _ => EquipmentSlot::None,
-            };
-            self.unequip_slot(slot.clone());
-            match slot {
-                EquipmentSlot::MainHand => {
-                    self.equipped_items.main_hand = Some(entry.params.id.clone());
-                    self.weapon_ability.main_hand = entry.get_actor_ability();
+    }
+
+    // This moves the actor's inventory, along with any loot, to a new corpse container
+    fn leave_corpse(&mut self) {
+        let mut inventory = Inventory {
+            items: self
+                .inventory
+                .items
+                .drain(..)
+                .map(|entry| InventoryEntry::new(entry.params))
+                .collect(),
+            credits: self.inventory.credits,
+            ..Default::default()
+        };
+        self.inventory.credits = 0;
+
+        if let Some(loot_table_id) = &self.loot_table_id {
+            let (items, credits) = roll_loot_table(loot_table_id, &LootContext::from_player());
+            for params in items {
+                inventory.add_item(params);
+            }
+            inventory.add_credits(credits);
+        }
+
+        Container::add_node(Container::corpse(
+            &self.name,
+            self.body.position,
+            inventory,
+            self.animation_player.clone(),
+        ));
+    }
+
+    // Returns `true` if the actor meets the requirements of an item and, in the case of armor and
+    // accessories, if there is a slot that will accept it
+    pub fn can_equip(&self, item: &ItemParams) -> bool {
+        if !item
+            .requirements
+            .is_met(self.class_id.as_deref(), &self.stats)
+        {
+            return false;
+        }
+
+        match item.kind {
+            ItemKind::OneHandedWeapon | ItemKind::TwoHandedWeapon => true,
+            ItemKind::Armor | ItemKind::Accessory => self.find_equipment_slot(item).is_some(),
+            _ => false,
+        }
+    }
+
+    // This finds the slot that armor or an accessory will be equipped to, preferring slots that
+    // are empty, so that a second ring will go on the other hand
+    fn find_equipment_slot(&self, item: &ItemParams) -> Option<String> {
+        let slot_type = item.slot.as_ref()?;
+        let resources = storage::get::<Resources>();
+        let slots: Vec<&EquipmentSlotParams> = resources
+            .equipment_slots
+            .iter()
+            .filter(|slot| slot.accepts(slot_type))
+            .collect();
+
+        slots
+            .iter()
+            .find(|slot| !self.equipped_items.slots.contains_key(&slot.id))
+            .or_else(|| slots.first())
+            .map(|slot| slot.id.clone())
+    }
+
+    // Returns `true` if the item was equipped
+    pub fn equip_item(&mut self, item_id: &str) -> bool {
+        let entry = match self
+            .inventory
+            .items
+            .iter()
+            .find(|entry| entry.params.id == item_id)
+        {
+            Some(entry) => entry.clone(),
+            None => return false,
+        };
+
+        if !self.can_equip(&entry.params) {
+            return false;
+        }
+
+        let slot = match entry.params.kind {
+            ItemKind::OneHandedWeapon => {
+                if self.equipped_items.main_hand.is_some() && self.equipped_items.off_hand.is_none()
+                {
+                    EquipmentSlot::OffHand
+                } else {
+                    EquipmentSlot::MainHand
                 }
-                EquipmentSlot::OffHand => {
-                    self.equipped_items.off_hand = Some(entry.params.id.clone());
-                    self.weapon_ability.offhand = entry.get_actor_ability();
+            }
+            ItemKind::TwoHandedWeapon => EquipmentSlot::BothHands,
+            ItemKind::Armor | ItemKind::Accessory => {
+                if entry.equipped_to != EquipmentSlot::None {
+                    return true;
                 }
-                EquipmentSlot::BothHands => {
-                    self.equipped_items.main_hand = Some(entry.params.id.clone());
-                    self.equipped_items.off_hand = Some(entry.params.id.clone());
-                    self.weapon_ability.main_hand = entry.get_actor_ability();
-                    self.weapon_ability.offhand = None;
+                match self.find_equipment_slot(&entry.params) {
+                    Some(slot_id) => EquipmentSlot::Other(slot_id),
+                    None => return false,
                 }
-                EquipmentSlot::None => {}
             }
+            _ => return false,
+        };
+
+        self.unequip_slot(slot.clone());
+        self.equip_item_to_slot(&entry, slot);
+        true
+    }
+
+    fn equip_item_to_slot(&mut self, entry: &InventoryEntry, slot: EquipmentSlot) {
+        match &slot {
+            EquipmentSlot::MainHand => {
+                self.equipped_items.main_hand = Some(entry.params.id.clone());
+                self.weapon_ability.main_hand = entry.get_actor_ability();
+            }
+            EquipmentSlot::OffHand => {
+                self.equipped_items.off_hand = Some(entry.params.id.clone());
+                self.weapon_ability.offhand = entry.get_actor_ability();
+            }
+            EquipmentSlot::BothHands => {
+                self.equipped_items.main_hand = Some(entry.params.id.clone());
+                self.equipped_items.off_hand = Some(entry.params.id.clone());
+                self.weapon_ability.main_hand = entry.get_actor_ability();
+                self.weapon_ability.offhand = None;
+            }
+            EquipmentSlot::Other(slot_id) => {
+                self.equipped_items
+                    .slots
+                    .insert(slot_id.clone(), entry.params.id.clone());
+            }
+            EquipmentSlot::None => return,
         }
 
-        if let Some(entry) = self
+        self.stats
+            .modifiers
+            .add(&entry.params.id, &entry.params.get_modifiers());
+        self.stats.recalculate_derived();
+
+        if let Some(found) = self
             .inventory
             .items
             .iter_mut()
-            .find(|entry| entry.params.id == item_id)
+            .find(|found| found.params.id == entry.params.id)
         {
-            entry.equipped_to = slot;
+            found.equipped_to = slot;
         }
     }
 
@@ -704,6 +1439,13 @@ impl Actor {
                 }
                 item_ids
             }
+            EquipmentSlot::Other(slot_id) => self
+                .equipped_items
+                .slots
+                .get(&slot_id)
+                .cloned()
+                .into_iter()
+                .collect(),
             EquipmentSlot::None => Vec::new(),
         };
 
@@ -725,6 +1467,9 @@ impl Actor {
                 self.weapon_ability.offhand = None;
             }
         }
+        if let Some(slot_id) = self.equipped_items.get_slot_of(item_id) {
+            self.equipped_items.slots.remove(&slot_id);
+        }
         if let Some(entry) = self
             .inventory
             .items
@@ -733,6 +1478,199 @@ impl Actor {
         {
             entry.equipped_to = EquipmentSlot::None;
         }
+
+        if self.stats.modifiers.remove(item_id) {
+            self.stats.recalculate_derived();
+        }
+    }
+
+    fn create_hotbar(item_ids: &[Option<String>]) -> Vec<Option<String>> {
+        let mut hotbar = item_ids.to_vec();
+        hotbar.resize(Self::HOTBAR_SIZE, None);
+        hotbar
+    }
+
+    // This assigns an item to a slot of the hotbar, removing it from any other slot that it was
+    // assigned to. If `slot` is `None`, the item is just removed from the hotbar.
+    pub fn assign_to_hotbar(&mut self, item_id: &str, slot: Option<usize>) {
+        for assigned in self.hotbar.iter_mut() {
+            if assigned.as_deref() == Some(item_id) {
+                *assigned = None;
+            }
+        }
+        if let Some(assigned) = slot.and_then(|slot| self.hotbar.get_mut(slot)) {
+            *assigned = Some(item_id.to_string());
+        }
+    }
+
+    pub fn get_hotbar_slot(&self, item_id: &str) -> Option<usize> {
+        self.hotbar
+            .iter()
+            .position(|assigned| assigned.as_deref() == Some(item_id))
+    }
+
+    // Returns the remaining cooldown of a consumable, which is shared with all other consumables
+    // of the same category
+    pub fn get_item_cooldown(&self, item: &ItemParams) -> f32 {
+        self.item_cooldowns
+            .get(&item.get_cooldown_id())
+            .cloned()
+            .unwrap_or(0.0)
+    }
+
+    fn update_item_cooldowns(&mut self, dt: f32) {
+        for cooldown in self.item_cooldowns.values_mut() {
+            *cooldown -= dt;
+        }
+        self.item_cooldowns.retain(|_, cooldown| *cooldown > 0.0);
+    }
+
+    // This uses a charge of a consumable, activating its ability and applying its effects to the
+    // actor, and returns `true` if it was used. The item is removed when it runs out of charges.
+    fn use_item(node: &mut RefMut<Self>, item_id: &str, direction: Vec2) -> bool {
+        let params = match node
+            .inventory
+            .items
+            .iter()
+            .find(|entry| entry.params.id == item_id)
+        {
+            Some(entry)
+                if entry.params.kind == ItemKind::Consumable && entry.params.charges > 0 =>
+            {
+                entry.params.clone()
+            }
+            _ => return false,
+        };
+
+        if node.get_item_cooldown(&params) > 0.0 {
+            return false;
+        }
+
+        if let Some(ability_id) = &params.ability_id {
+            let mut ability = Self::create_ability(ability_id);
+            let origin = node.body.position;
+            if !ability.activate(node, origin, direction) {
+                return false;
+            }
+        }
+
+        let actor_id = node.id.clone();
+        let handle = node.handle();
+        let factions = node.factions.clone();
+        for effect in params.effects.clone() {
+            let effect = AbilityEffect {
+                target: EffectTarget::Source,
+                effect,
+            };
+            node.apply_effect(&actor_id, handle, &factions, direction, effect, false);
+        }
+
+        if params.cooldown > 0.0 {
+            node.item_cooldowns
+                .insert(params.get_cooldown_id(), params.cooldown);
+        }
+
+        // The charges of a stack are those of the item on top, so when it runs out, the next item
+        // in the stack will start with the charges of the prototype
+        if params.charges > 1 || params.quantity > 1 {
+            let charges = params
+                .prototype_id
+                .as_ref()
+                .and_then(|prototype_id| {
+                    let resources = storage::get::<Resources>();
+                    resources
+                        .items
+                        .get(prototype_id)
+                        .map(|params| params.charges)
+                })
+                .unwrap_or_else(ItemParams::default_charges);
+            if let Some(entry) = node
+                .inventory
+                .items
+                .iter_mut()
+                .find(|entry| entry.params.id == item_id)
+            {
+                if entry.params.charges > 1 {
+                    entry.params.charges -= 1;
+                } else {
+                    entry.params.quantity -= 1;
+                    entry.params.charges = charges;
+                }
+            }
+        } else {
+            node.inventory.remove_item(item_id);
+            node.assign_to_hotbar(item_id, None);
+        }
+
+        true
+    }
+
+    // Equipped items are stored by id, so this is used to equip them again when an actor is
+    // created, to apply their abilities and stat modifiers. Items are returned to the slots they
+    // were saved in, without checking their requirements.
+    fn reequip_items(&mut self) {
+        let equipped_items = std::mem::take(&mut self.equipped_items);
+
+        let mut slots = Vec::new();
+        match (equipped_items.main_hand, equipped_items.off_hand) {
+            (Some(main_hand), Some(off_hand)) if main_hand == off_hand => {
+                slots.push((main_hand, EquipmentSlot::BothHands));
+            }
+            (main_hand, off_hand) => {
+                if let Some(item_id) = main_hand {
+                    slots.push((item_id, EquipmentSlot::MainHand));
+                }
+                if let Some(item_id) = off_hand {
+                    slots.push((item_id, EquipmentSlot::OffHand));
+                }
+            }
+        }
+        for (slot_id, item_id) in equipped_items.slots {
+            slots.push((item_id, EquipmentSlot::Other(slot_id)));
+        }
+
+        for (item_id, slot) in slots {
+            let entry = self
+                .inventory
+                .items
+                .iter()
+                .find(|entry| entry.params.id == item_id)
+                .cloned();
+            if let Some(entry) = entry {
+                self.equip_item_to_slot(&entry, slot);
+            }
+        }
+    }
+
+    // Returns the textures of equipped items that should be drawn over the actor, ordered by the
+    // layer of their slot. Weapons are drawn on top of everything else.
+    fn get_equipped_textures(&self) -> Vec<String> {
+        let resources = storage::get::<Resources>();
+        let mut textures: Vec<(i32, String)> = self
+            .inventory
+            .items
+            .iter()
+            .filter_map(|entry| {
+                let texture_id = entry.params.equipped_texture_id.clone()?;
+                let layer = match &entry.equipped_to {
+                    EquipmentSlot::None => return None,
+                    EquipmentSlot::Other(slot_id) => resources
+                        .equipment_slots
+                        .iter()
+                        .find(|slot| slot.id == *slot_id)
+                        .map(|slot| slot.layer)
+                        .unwrap_or_default(),
+                    _ => i32::MAX,
+                };
+                Some((layer, texture_id))
+            })
+            .collect();
+
+        textures.sort_by_key(|(layer, _)| *layer);
+        textures
+            .into_iter()
+            .map(|(_, texture_id)| texture_id)
+            .collect()
     }
 }
 
@@ -751,7 +1689,18 @@ impl BufferedDraw for Actor {
             1.0,
         );
 
-        self.animation_player.draw(position, rotation);
+        // Actors are tinted with the color of their newest status effect that has one
+        let color = self
+            .status_effects
+            .iter()
+            .rev()
+            .find_map(|status_effect| status_effect.params.color);
+        self.animation_player
+            .draw_with_color(position, rotation, color);
+        for texture_id in self.get_equipped_textures() {
+            self.animation_player
+                .draw_layer(&texture_id, position, rotation, color);
+        }
 
         if !self.is_local_player() && self.stats.current_health < self.stats.max_health {
             draw_progress_bar(
@@ -862,7 +1811,14 @@ impl Node for Actor {
             let mut game_state = scene::get_node(node.game_state);
             let position = node.body.position;
             if !node.is_player() {
-                node.inventory.drop_all(position, true);
+                if node.leaves_corpse {
+                    node.leave_corpse();
+                } else {
+                    node.inventory.drop_all(position, true);
+                    if let Some(loot_table_id) = &node.loot_table_id {
+                        drop_loot_table(loot_table_id, &LootContext::from_player(), position);
+                    }
+                }
                 game_state.dead_actors.push(node.id.clone());
             }
             node.delete();
@@ -870,6 +1826,7 @@ impl Node for Actor {
         }
 
         node.update_noise_level();
+        node.dash_timer += get_frame_time();
         node.animation_player.update();
         node.update_missions();
 
@@ -884,6 +1841,8 @@ impl Node for Actor {
             ability.update();
         }
 
+        node.update_item_cooldowns(get_frame_time());
+
         node.controller.should_use_weapon = false;
         node.controller.should_use_selected_ability = false;
         node.controller.move_direction = Vec2::ZERO;
@@ -958,7 +1917,7 @@ impl Node for Actor {
             ActorControllerKind::None => {}
         }
 
-        if !node.controller.is_attacking() {
+        if !node.controller.is_attacking() && node.controller.use_item.is_none() {
             node.controller.aim_direction = node.controller.move_direction;
         }
 
@@ -970,9 +1929,26 @@ impl Node for Actor {
             }
         }
 
+        // Containers are closed when the actor moves away from them
+        if let Some(handle) = node.current_container {
+            let is_in_range = scene::try_get_node(handle)
+                .map(|container| {
+                    container.position.distance(node.body.position)
+                        <= Self::CONTAINER_CLOSE_DISTANCE
+                })
+                .unwrap_or(false);
+            if !is_in_range {
+                node.close_container();
+            }
+        }
+
         if node.controller.should_start_interaction {
             if node.current_dialogue.is_some() {
                 node.current_dialogue = None;
+            } else if node.current_vendor.is_some() {
+                node.current_vendor = None;
+            } else if node.current_container.is_some() {
+                node.close_container();
             } else {
                 let collider = Collider::circle(0.0, 0.0, Self::INTERACT_RADIUS)
                     .with_offset(node.body.position);
@@ -989,21 +1965,47 @@ impl Node for Actor {
                         }
                     }
                 }
+
+                if node.controller.should_start_interaction {
+                    for mut container in scene::find_nodes_by_type::<Container>() {
+                        if collider.contains(container.position) {
+                            container.open(&node);
+                            node.current_container = Some(container.handle());
+                            node.controller.should_start_interaction = false;
+                            break;
+                        }
+                    }
+                }
             }
         }
 
-        let current_dialogue = node.current_dialogue.clone();
-        if let Some(mut dialogue) = current_dialogue {
+        // Actions can close the dialogue, or replace it, so the flag is cleared before they are
+        // applied. If the actions are aborted, the dialogue is closed, so that the response of the
+        // option isn't shown.
+        if let Some(dialogue) = node.current_dialogue.as_mut() {
             if dialogue.should_apply {
                 dialogue.should_apply = false;
-                dialogue.apply_action(&mut *node);
-                node.current_dialogue = Some(dialogue);
+                let dialogue = dialogue.clone();
+                if !dialogue.apply_actions(&mut node) {
+                    node.current_dialogue = None;
+                }
             }
         }
     }
 
     fn fixed_update(mut node: RefMut<Self>) {
         node.stats.update();
+        node.update_status_effects(get_frame_time());
+
+        if node.is_stunned() {
+            node.controller.move_direction = Vec2::ZERO;
+            node.controller.should_use_weapon = false;
+            node.controller.should_use_selected_ability = false;
+            node.controller.should_dash = false;
+            node.controller.use_item = None;
+        } else if node.is_silenced() {
+            node.controller.should_use_selected_ability = false;
+        }
 
         let controller = node.controller.clone();
         node.set_animation(
@@ -1032,6 +2034,11 @@ impl Node for Actor {
             node.selected_ability = secondary_ability;
         }
 
+        if let Some(item_id) = &controller.use_item {
+            Self::use_item(&mut node, item_id, controller.aim_direction);
+            node.controller.use_item = None;
+        }
+
         let collider =
             Collider::circle(0.0, 0.0, Self::PICK_UP_RADIUS).with_offset(node.body.position);
         for credits in scene::find_nodes_by_type::<Credits>() {
@@ -1069,7 +2076,22 @@ impl Node for Actor {
             Vec2::ZERO
         };
 
-        node.body.integrate();
+        if controller.should_dash {
+            node.dash();
+            node.controller.should_dash = false;
+        }
+
+        let map = storage::get::<Map>();
+        let dt = get_frame_time();
+
+        #[cfg(feature = "collision-between-actors")]
+        {
+            let actors = Self::get_actor_obstacles(&node.id);
+            node.body.integrate_with_actors(&map, dt, &actors);
+        }
+
+        #[cfg(not(feature = "collision-between-actors"))]
+        node.body.integrate(&map, dt);
     }
 }
 
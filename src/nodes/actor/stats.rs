@@ -21,6 +21,10 @@ pub struct ActorStats {
     pub view_distance: f32,
     pub carry_capacity: f32,
     pub move_speed: f32,
+    // Custom attributes and derived stats, as defined in a game's `stats.json`
+    pub attributes: HashMap<String, u32>,
+    pub derived: HashMap<String, f32>,
+    pub modifiers: StatModifiers,
 }
 
 impl ActorStats {
@@ -56,21 +60,131 @@ impl ActorStats {
         stats
     }
 
+    // This uses the stat formulas from `Resources`, if they have been loaded, or the default
+    // formulas, if not
     pub fn recalculate_derived(&mut self) {
-        self.max_health =
-            (self.constitution + self.strength / 4 + self.willpower / 4) as f32 * 100.0;
-        self.max_stamina =
-            (self.constitution + self.dexterity / 4 + self.willpower / 4) as f32 * 100.0;
-        self.max_energy = (self.willpower + self.constitution / 2) as f32 * 100.0;
-        self.health_regen =
-            (self.constitution + self.strength / 4 + self.willpower / 4) as f32 * 0.1;
-        self.stamina_regen =
-            (self.constitution + self.dexterity / 4 + self.willpower / 4) as f32 * 8.0;
-        self.energy_regen = (self.willpower + self.constitution / 2) as f32 * 0.5;
-        self.move_speed = (self.dexterity + self.strength / 4 + self.willpower / 4) as f32 * 0.1;
-        self.view_distance = (self.perception + self.intelligence / 2) as f32 * 20.0;
-        self.carry_capacity =
-            (self.strength + self.constitution / 4 + self.willpower / 4) as f32 * 50.0;
+        if let Some(resources) = storage::try_get::<Resources>() {
+            self.recalculate_derived_with(&resources.stat_formulas);
+        } else {
+            self.recalculate_derived_with(&StatFormulas::default());
+        }
+    }
+
+    // Modifiers are applied to attributes before the formulas are evaluated, and to the results
+    // of the formulas, so derived stats will reflect both
+    pub fn recalculate_derived_with(&mut self, formulas: &StatFormulas) {
+        let modifiers = &self.modifiers;
+        self.derived.retain(|id, _| {
+            modifiers.is_modified(id) || formulas.derived.iter().any(|stat| stat.id == *id)
+        });
+
+        for stat in &formulas.derived {
+            let value = stat.formula.evaluate(&|id: &str| {
+                self.get_stat(id).or_else(|| {
+                    formulas
+                        .get_default_attribute(id)
+                        .map(|value| self.modifiers.apply(id, value as f32))
+                })
+            });
+            let value = self.modifiers.apply(&stat.id, value);
+            self.set_derived(&stat.id, value);
+        }
+
+        for id in self.modifiers.get_stat_ids() {
+            let is_calculated = formulas.derived.iter().any(|stat| stat.id == id);
+            let is_attribute = self.get_base_attribute(&id).is_some()
+                || formulas.get_default_attribute(&id).is_some();
+            if !is_calculated && !is_attribute {
+                let value = self.modifiers.apply(&id, 0.0);
+                self.set_derived(&id, value);
+            }
+        }
+
+        // Vitals are clamped, so that they don't exceed their maximums, if these drop, like when a
+        // modifier expires
+        if !Self::VITALS_CAN_OVERFLOW {
+            self.current_health = self.current_health.min(self.max_health);
+            self.current_stamina = self.current_stamina.min(self.max_stamina);
+            self.current_energy = self.current_energy.min(self.max_energy);
+        }
+    }
+
+    // Returns the value of an attribute, with modifiers applied, or of a derived stat, by its id
+    pub fn get_stat(&self, id: &str) -> Option<f32> {
+        if let Some(value) = self.get_base_attribute(id) {
+            return Some(self.modifiers.apply(id, value as f32));
+        }
+
+        let value = match id {
+            "max_health" => self.max_health,
+            "max_stamina" => self.max_stamina,
+            "max_energy" => self.max_energy,
+            "health_regen" => self.health_regen,
+            "stamina_regen" => self.stamina_regen,
+            "energy_regen" => self.energy_regen,
+            "move_speed" => self.move_speed,
+            "view_distance" => self.view_distance,
+            "carry_capacity" => self.carry_capacity,
+            _ => return self.derived.get(id).cloned(),
+        };
+
+        Some(value)
+    }
+
+    // Returns the value of an attribute, without modifiers, or of a derived stat, by its id
+    pub fn get_base_stat(&self, id: &str) -> Option<f32> {
+        self.get_base_attribute(id)
+            .map(|value| value as f32)
+            .or_else(|| self.get_stat(id))
+    }
+
+    fn get_base_attribute(&self, id: &str) -> Option<u32> {
+        let value = match id {
+            "strength" => self.strength,
+            "dexterity" => self.dexterity,
+            "constitution" => self.constitution,
+            "intelligence" => self.intelligence,
+            "willpower" => self.willpower,
+            "perception" => self.perception,
+            "charisma" => self.charisma,
+            _ => return self.attributes.get(id).cloned(),
+        };
+
+        Some(value)
+    }
+
+    // This sets the base value of an attribute, by its id. Derived stats are not recalculated, so
+    // `recalculate_derived` should be called after all attributes have been set.
+    pub fn set_attribute(&mut self, id: &str, value: u32) {
+        match id {
+            "strength" => self.strength = value,
+            "dexterity" => self.dexterity = value,
+            "constitution" => self.constitution = value,
+            "intelligence" => self.intelligence = value,
+            "willpower" => self.willpower = value,
+            "perception" => self.perception = value,
+            "charisma" => self.charisma = value,
+            _ => {
+                self.attributes.insert(id.to_string(), value);
+            }
+        }
+    }
+
+    fn set_derived(&mut self, id: &str, value: f32) {
+        match id {
+            "max_health" => self.max_health = value,
+            "max_stamina" => self.max_stamina = value,
+            "max_energy" => self.max_energy = value,
+            "health_regen" => self.health_regen = value,
+            "stamina_regen" => self.stamina_regen = value,
+            "energy_regen" => self.energy_regen = value,
+            "move_speed" => self.move_speed = value,
+            "view_distance" => self.view_distance = value,
+            "carry_capacity" => self.carry_capacity = value,
+            _ => {
+                self.derived.insert(id.to_string(), value);
+            }
+        }
     }
 
     pub fn update(&mut self) {
@@ -107,6 +221,18 @@ impl ActorStats {
         }
     }
 
+    pub fn restore_health(&mut self, amount: f32) {
+        self.current_health = (self.current_health + amount).min(self.max_health);
+    }
+
+    pub fn restore_stamina(&mut self, amount: f32) {
+        self.current_stamina = (self.current_stamina + amount).min(self.max_stamina);
+    }
+
+    pub fn restore_energy(&mut self, amount: f32) {
+        self.current_energy = (self.current_energy + amount).min(self.max_energy);
+    }
+
     pub fn restore_vitals(&mut self) {
         self.recalculate_derived();
         self.current_health = self.max_health;
@@ -137,6 +263,9 @@ impl Default for ActorStats {
             carry_capacity: 0.0,
             view_distance: 0.0,
             move_speed: 0.0,
+            attributes: HashMap::new(),
+            derived: HashMap::new(),
+            modifiers: StatModifiers::default(),
         }
     }
 }
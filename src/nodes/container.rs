@@ -0,0 +1,237 @@
+use crate::prelude::*;
+
+// A container prototype, like a chest or a crate, that can be placed on a map, in an object layer
+// with the `containers` kind
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerParams {
+    pub id: String,
+    pub name: String,
+    pub sprite: Sprite,
+    #[serde(default, flatten)]
+    pub inventory: InventoryParams,
+    // A loot table that is rolled the first time the container is opened
+    #[serde(
+        default,
+        rename = "loot_table",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub loot_table_id: Option<String>,
+    // The prototype id of an item that will unlock the container, if it is locked
+    #[serde(default, rename = "key", skip_serializing_if = "Option::is_none")]
+    pub key_id: Option<String>,
+    #[serde(default, skip_serializing_if = "helpers::is_false")]
+    pub is_locked: bool,
+}
+
+// The contents of a container, as it is saved with a character, so that the contents of
+// containers on a map are kept when the map is loaded again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerState {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<ItemParams>,
+    #[serde(default)]
+    pub credits: u32,
+    #[serde(default, skip_serializing_if = "helpers::is_false")]
+    pub is_locked: bool,
+    // The loot table, if it has not been rolled yet
+    #[serde(
+        default,
+        rename = "loot_table",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub loot_table_id: Option<String>,
+}
+
+enum ContainerSprite {
+    Sprite(Sprite),
+    // Corpses are drawn as the dead actor, lying down
+    Corpse(SpriteAnimationPlayer),
+}
+
+pub struct Container {
+    pub id: String,
+    pub name: String,
+    pub position: Vec2,
+    pub inventory: Inventory,
+    pub loot_table_id: Option<String>,
+    pub key_id: Option<String>,
+    pub is_locked: bool,
+    // Corpses are not saved, and they are removed when they have been emptied and closed
+    pub is_corpse: bool,
+    sprite: ContainerSprite,
+}
+
+impl Container {
+    const CORPSE_COLOR: Color = Color::new(0.6, 0.6, 0.6, 1.0);
+
+    pub fn new(id: &str, position: Vec2, params: ContainerParams) -> Self {
+        Container {
+            id: id.to_string(),
+            name: params.name,
+            position,
+            inventory: Inventory::from_prototypes(&params.inventory),
+            loot_table_id: params.loot_table_id,
+            key_id: params.key_id,
+            is_locked: params.is_locked,
+            is_corpse: false,
+            sprite: ContainerSprite::Sprite(params.sprite),
+        }
+    }
+
+    pub fn corpse(
+        name: &str,
+        position: Vec2,
+        inventory: Inventory,
+        animation_player: SpriteAnimationPlayer,
+    ) -> Self {
+        let mut animation_player = animation_player;
+        animation_player.stop();
+
+        Container {
+            id: generate_id(),
+            name: name.to_string(),
+            position,
+            inventory,
+            loot_table_id: None,
+            key_id: None,
+            is_locked: false,
+            is_corpse: true,
+            sprite: ContainerSprite::Corpse(animation_player),
+        }
+    }
+
+    pub fn add_node(container: Container) -> Handle<Self> {
+        scene::add_node(container)
+    }
+
+    pub fn apply_state(&mut self, state: &ContainerState) {
+        self.inventory.items = state
+            .items
+            .iter()
+            .cloned()
+            .map(InventoryEntry::new)
+            .collect();
+        self.inventory.credits = state.credits;
+        self.is_locked = state.is_locked;
+        self.loot_table_id = state.loot_table_id.clone();
+    }
+
+    pub fn to_state(&self) -> ContainerState {
+        ContainerState {
+            id: self.id.clone(),
+            items: self
+                .inventory
+                .items
+                .iter()
+                .map(|entry| entry.params.clone())
+                .collect(),
+            credits: self.inventory.credits,
+            is_locked: self.is_locked,
+            loot_table_id: self.loot_table_id.clone(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inventory.items.is_empty() && self.inventory.credits == 0
+    }
+
+    // This unlocks the container, if `actor` has the key, and rolls the loot table of the
+    // container, the first time it is opened. Returns `false` if the container is locked.
+    pub fn open(&mut self, actor: &Actor) -> bool {
+        if self.is_locked {
+            match &self.key_id {
+                Some(key_id) if actor.inventory.has_prototype(key_id) => self.is_locked = false,
+                _ => return false,
+            }
+        }
+
+        if let Some(loot_table_id) = self.loot_table_id.take() {
+            let (items, credits) = roll_loot_table(&loot_table_id, &LootContext::from_actor(actor));
+            for params in items {
+                self.inventory.add(params, false);
+            }
+            self.inventory.add_credits(credits);
+        }
+
+        true
+    }
+
+    // Returns the states of all the containers on the current map, except for corpses
+    pub fn get_states() -> Vec<ContainerState> {
+        scene::find_nodes_by_type::<Container>()
+            .filter(|container| !container.is_corpse)
+            .map(|container| container.to_state())
+            .collect()
+    }
+}
+
+impl BufferedDraw for Container {
+    fn buffered_draw(&mut self) {
+        match &mut self.sprite {
+            ContainerSprite::Sprite(sprite) => sprite.draw(self.position, 0.0),
+            ContainerSprite::Corpse(animation_player) => animation_player.draw_with_color(
+                self.position,
+                std::f32::consts::FRAC_PI_2,
+                Some(Self::CORPSE_COLOR),
+            ),
+        }
+    }
+
+    fn get_z_index(&self) -> f32 {
+        self.position.y
+    }
+
+    fn get_bounds(&self) -> Bounds {
+        Bounds::Point(self.position)
+    }
+}
+
+impl Node for Container {
+    fn draw(node: RefMut<Self>) {
+        let mut draw_buffer = scene::find_node_by_type::<DrawBuffer<Self>>().unwrap();
+        draw_buffer.buffered.push(node.handle());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_params() {
+        let json = r#"{
+            "id": "crate",
+            "name": "Crate",
+            "sprite": {
+                "texture_id": "items",
+                "texture_coords": { "x": 0, "y": 0 },
+                "tile_size": { "x": 16, "y": 16 }
+            },
+            "loot_table": "crate"
+        }"#;
+        let params: ContainerParams = serde_json::from_str(json).unwrap();
+        assert!(params.inventory.items.is_empty());
+        assert_eq!(params.loot_table_id, Some("crate".to_string()));
+        assert!(!params.is_locked);
+
+        let json = r#"{
+            "id": "chest",
+            "name": "Chest",
+            "sprite": {
+                "texture_id": "items",
+                "texture_coords": { "x": 0, "y": 0 },
+                "tile_size": { "x": 16, "y": 16 }
+            },
+            "items": ["key"],
+            "credits": 10,
+            "key": "chest_key",
+            "is_locked": true
+        }"#;
+        let params: ContainerParams = serde_json::from_str(json).unwrap();
+        assert_eq!(params.inventory.items, vec!["key".to_string()]);
+        assert_eq!(params.inventory.credits, 10);
+        assert_eq!(params.key_id, Some("chest_key".to_string()));
+        assert!(params.is_locked);
+    }
+}
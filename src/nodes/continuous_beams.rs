@@ -2,19 +2,26 @@ use std::ops::Sub;
 
 use macroquad::{
     color,
-    experimental::scene::{Handle, Node, RefMut},
+    experimental::{
+        collections::storage,
+        scene::{Handle, Node, RefMut},
+    },
     prelude::*,
 };
 
-use crate::ability::Effect;
-use crate::physics::get_beam_end;
+use crate::ability::AbilityEffect;
+use crate::map::Map;
+use crate::physics::{can_hit_actor, get_beam_end};
 use crate::{nodes::Actor, physics::beam_collision_check};
 
 pub struct ContinuousBeam {
     pub actor_id: String,
     pub actor: Handle<Actor>,
     pub factions: Vec<String>,
-    pub effects: Vec<Effect>,
+    pub effects: Vec<AbilityEffect>,
+    pub collision_mask: Vec<String>,
+    // Actors on this layer are passed through, as allies of the source
+    pub friendly_layer: Option<String>,
     pub color: Color,
     pub width: f32,
     pub origin: Vec2,
@@ -46,9 +53,11 @@ impl ContinuousBeams {
         actor_id: &str,
         actor: Handle<Actor>,
         factions: &[String],
-        effects: &[Effect],
+        effects: &[AbilityEffect],
         color_override: Option<Color>,
         width_override: Option<f32>,
+        collision_mask: &[String],
+        friendly_layer: Option<String>,
         origin: Vec2,
         end: Vec2,
     ) {
@@ -57,6 +66,8 @@ impl ContinuousBeams {
             actor,
             factions: factions.to_vec(),
             effects: effects.to_vec(),
+            collision_mask: collision_mask.to_vec(),
+            friendly_layer,
             color: color_override.unwrap_or(Self::DEFAULT_COLOR),
             width: width_override.unwrap_or(Self::DEFAULT_WIDTH),
             origin,
@@ -68,14 +79,27 @@ impl ContinuousBeams {
 
 impl Node for ContinuousBeams {
     fn fixed_update(mut node: RefMut<Self>) {
+        let map = storage::get::<Map>();
         for mut beam in &mut node.active {
             let mut cutoff = get_beam_end(
+                &map,
                 beam.origin,
                 beam.end,
                 beam.width,
                 Self::WIDTH_TOLERANCE_FACTOR,
+                &beam.collision_mask,
             );
-            'outer: for mut other_actor in scene::find_nodes_by_type::<Actor>() {
+            for mut other_actor in scene::find_nodes_by_type::<Actor>() {
+                if other_actor.id == beam.actor_id
+                    || !can_hit_actor(
+                        &beam.collision_mask,
+                        beam.friendly_layer.as_deref(),
+                        &other_actor.body.collision_layer,
+                    )
+                {
+                    continue;
+                }
+
                 let position = match other_actor.body.get_offset_collider() {
                     Some(collider) => collider.get_position(),
                     None => other_actor.body.position,
@@ -87,19 +111,16 @@ impl Node for ContinuousBeams {
                     beam.width,
                     Self::WIDTH_TOLERANCE_FACTOR,
                 ) {
-                    for effect in beam.effects.clone() {
-                        if other_actor.apply_effect(
-                            &beam.actor_id,
-                            beam.actor,
-                            &beam.factions,
-                            effect,
-                        ) {
-                            if beam.origin.distance(position) < beam.origin.distance(cutoff) {
-                                cutoff = position;
-                            }
-                        } else {
-                            continue 'outer;
-                        }
+                    other_actor.apply_hit(
+                        &beam.actor_id,
+                        beam.actor,
+                        &beam.factions,
+                        beam.end - beam.origin,
+                        &beam.effects,
+                    );
+
+                    if beam.origin.distance(position) < beam.origin.distance(cutoff) {
+                        cutoff = position;
                     }
                 }
             }
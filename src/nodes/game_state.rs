@@ -9,6 +9,13 @@ pub struct GameState {
     pub player_spawn_point: Vec2,
     pub character_name: String,
     pub is_permadeath: bool,
+    pub completed_chapters: Vec<usize>,
+    // World variables and flags, which are checked by dialogue requirements
+    pub variables: HashMap<String, i32>,
+    // The saved contents of containers on maps other than the current one, by map id
+    pub containers: HashMap<String, Vec<ContainerState>>,
+    // The stock of the vendors that have been traded with
+    pub vendors: HashMap<String, Vendor>,
     pub in_debug_mode: bool,
     pub gui_state: GuiState,
     time_since_save: f32,
@@ -23,6 +30,10 @@ impl GameState {
             player_spawn_point,
             character_name: character.actor.name.clone(),
             is_permadeath: character.is_permadeath,
+            completed_chapters: character.completed_chapters.clone(),
+            variables: character.variables.clone(),
+            containers: character.containers.clone(),
+            vendors: character.vendors.clone(),
             in_debug_mode: false,
             gui_state: GuiState::new(),
             time_since_save: 0.0,
@@ -36,17 +47,55 @@ impl GameState {
 
     pub fn get_player_character(&self) -> Option<Character> {
         if let Some(actor) = get_player_actor() {
-            let character =
+            let mut character =
                 actor.to_character(self.chapter_index, &self.map_id, self.is_permadeath);
+            character.completed_chapters = self.completed_chapters.clone();
+            character.variables = self.variables.clone();
+            character.containers = self.containers.clone();
+            character.vendors = self.vendors.clone();
+            character
+                .containers
+                .insert(self.map_id.clone(), Container::get_states());
             return Some(character);
         }
         None
     }
+
+    pub fn complete_chapter(&mut self) {
+        if !self.completed_chapters.contains(&self.chapter_index) {
+            self.completed_chapters.push(self.chapter_index);
+        }
+    }
+
+    // Returns the stock of a vendor, which is created the first time it is requested
+    pub fn get_vendor(&mut self, vendor_id: &str, context: &LootContext) -> &mut Vendor {
+        let resources = storage::get::<Resources>();
+        let params = resources
+            .vendors
+            .get(vendor_id)
+            .unwrap_or_else(|| panic!("Unable to find vendor with id '{}'", vendor_id));
+
+        self.vendors
+            .entry(vendor_id.to_string())
+            .or_insert_with(|| Vendor::new(params, context))
+    }
+
+    fn update_vendors(&mut self) {
+        let resources = storage::get::<Resources>();
+        let context = LootContext::from_player();
+        let dt = get_frame_time();
+        for vendor in self.vendors.values_mut() {
+            if let Some(params) = resources.vendors.get(&vendor.id) {
+                vendor.update(params, dt, &context);
+            }
+        }
+    }
 }
 
 impl Node for GameState {
     fn update(mut node: RefMut<Self>) {
         node.time_since_save += get_frame_time();
+        node.update_vendors();
 
         if get_player_actor().is_none() {
             if node.is_permadeath {
@@ -13,6 +13,79 @@ impl Hud {
     }
 }
 
+impl Hud {
+    const HOTBAR_SLOT_SIZE: f32 = 48.0;
+    const HOTBAR_MARGIN: f32 = 10.0;
+
+    // This draws the slots of the hotbar at the bottom of the screen, with the key that uses each
+    // slot, the sprite and quantity, or charges, of the item assigned to it, and any remaining
+    // cooldown
+    fn draw_hotbar(actor: &Actor) {
+        let size = Self::HOTBAR_SLOT_SIZE;
+        let width = actor.hotbar.len() as f32 * size;
+        let position = vec2(
+            (get_screen_width() - width) / 2.0,
+            get_screen_height() - size - Self::HOTBAR_MARGIN,
+        );
+
+        for (i, item_id) in actor.hotbar.iter().enumerate() {
+            let x = position.x + i as f32 * size;
+            draw_rectangle(x, position.y, size, size, color::BLACK);
+            draw_rectangle_lines(x, position.y, size, size, 2.0, color::GRAY);
+
+            let entry = item_id.as_ref().and_then(|item_id| {
+                actor
+                    .inventory
+                    .items
+                    .iter()
+                    .find(|entry| entry.params.id == *item_id)
+            });
+
+            if let Some(entry) = entry {
+                entry
+                    .params
+                    .sprite
+                    .draw(vec2(x + size / 2.0, position.y + size / 2.0), 0.0);
+
+                let count = if entry.params.quantity > 1 {
+                    entry.params.quantity
+                } else {
+                    entry.params.charges
+                };
+                if count > 1 {
+                    draw_text(
+                        &count.to_string(),
+                        vec2(x + size - 4.0, position.y + size - 4.0),
+                        HorizontalAlignment::Right,
+                        VerticalAlignment::Bottom,
+                        Default::default(),
+                    );
+                }
+
+                let cooldown = actor.get_item_cooldown(&entry.params);
+                if cooldown > 0.0 && entry.params.cooldown > 0.0 {
+                    let height = size * (cooldown / entry.params.cooldown).min(1.0);
+                    draw_rectangle(
+                        x,
+                        position.y + size - height,
+                        size,
+                        height,
+                        Color::new(0.0, 0.0, 0.0, 0.6),
+                    );
+                }
+            }
+
+            draw_text(
+                &(i + 1).to_string(),
+                vec2(x + 4.0, position.y + 4.0),
+                HorizontalAlignment::Left,
+                VerticalAlignment::Top,
+                Default::default(),
+            );
+        }
+    }
+}
+
 impl Node for Hud {
     fn draw(_: RefMut<Self>) {
         let game_state = scene::find_node_by_type::<GameState>().unwrap();
@@ -132,7 +205,7 @@ impl Node for Hud {
             let len = actor.active_missions.len();
             if len > 0 {
                 draw_text(
-                    "Active missions:",
+                    &tr("Active missions:"),
                     vec2(get_screen_width() - 50.0, 250.0),
                     HorizontalAlignment::Right,
                     VerticalAlignment::Center,
@@ -142,8 +215,13 @@ impl Node for Hud {
 
             for i in 0..len {
                 let mission = actor.active_missions.get(i).unwrap();
+                let context = TextContext {
+                    variables: Some(&game_state.variables),
+                    mission: Some(mission),
+                    ..TextContext::from_player(&actor)
+                };
                 draw_text(
-                    &mission.title,
+                    &interpolate(&tr(&mission.title), &context),
                     vec2(get_screen_width() - 50.0, (300.0) + i as f32 * (50.0)),
                     HorizontalAlignment::Right,
                     VerticalAlignment::Center,
@@ -154,7 +232,7 @@ impl Node for Hud {
             let len = actor.completed_missions.len();
             if len > 0 {
                 draw_text(
-                    "Completed missions:",
+                    &tr("Completed missions:"),
                     vec2(get_screen_width() - 50.0, 400.0),
                     HorizontalAlignment::Right,
                     VerticalAlignment::Center,
@@ -164,8 +242,13 @@ impl Node for Hud {
 
             for i in 0..len {
                 let mission = actor.completed_missions.get(i).unwrap();
+                let context = TextContext {
+                    variables: Some(&game_state.variables),
+                    mission: Some(mission),
+                    ..TextContext::from_player(&actor)
+                };
                 draw_text(
-                    &mission.title,
+                    &interpolate(&tr(&mission.title), &context),
                     vec2(get_screen_width() - 50.0, 450.0 + i as f32 * (50.0)),
                     HorizontalAlignment::Right,
                     VerticalAlignment::Center,
@@ -224,6 +307,8 @@ impl Node for Hud {
                 None, // Some(&format!("{}/{}", self.stats.current_energy.round(), self.stats.max_energy.round())),
                 None,
             );
+
+            Self::draw_hotbar(&actor);
         }
 
         pop_camera_state();
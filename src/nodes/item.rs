@@ -5,12 +5,54 @@ use crate::prelude::*;
 pub enum ItemKind {
     OneHandedWeapon,
     TwoHandedWeapon,
+    // Consumables can be used from the inventory, or the hotbar, which will activate their ability
+    // and apply their effects to the actor that used them, once for every charge
+    Consumable,
+    // Armor and accessories are equipped to the slot that accepts their `slot` type
+    Armor,
+    Accessory,
     Misc,
 }
 
+// Requirements that must be met by an actor, for it to be able to equip an item
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemRequirements {
+    // If not empty, only actors of one of these classes can equip the item
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub classes: Vec<String>,
+    // Minimum values of attributes, including custom attributes. This is checked against the
+    // modified values, so bonuses from other items and status effects count.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub attributes: HashMap<String, u32>,
+}
+
+impl ItemRequirements {
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty() && self.attributes.is_empty()
+    }
+
+    pub fn is_met(&self, class_id: Option<&str>, stats: &ActorStats) -> bool {
+        if !self.classes.is_empty()
+            && !class_id
+                .map(|class_id| self.classes.iter().any(|id| id == class_id))
+                .unwrap_or(false)
+        {
+            return false;
+        }
+
+        self.attributes
+            .iter()
+            .all(|(id, min_value)| stats.get_stat(id).unwrap_or(0.0) >= *min_value as f32)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ItemParams {
     pub id: String,
+    // The id of the prototype that an item was instantiated from, which is used to determine if
+    // two items can be stacked
+    #[serde(default, rename = "prototype", skip_serializing_if = "Option::is_none")]
+    pub prototype_id: Option<String>,
     pub name: String,
     pub description: String,
     #[serde(
@@ -21,25 +63,170 @@ pub struct ItemParams {
     pub position: Option<Vec2>,
     pub kind: ItemKind,
     pub weight: f32,
+    // The base price of the item, when it is traded with vendors
+    #[serde(default)]
+    pub value: u32,
     #[serde(default, rename = "ability", skip_serializing_if = "Option::is_none")]
     pub ability_id: Option<String>,
     pub sprite: Sprite,
     #[serde(default)]
     pub is_quest_item: bool,
+    // Modifiers that are applied to the stats of the actor that has the item equipped
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modifiers: Vec<StatModifier>,
+    // The slot type of armor and accessories, like `head` or `ring`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot: Option<String>,
+    #[serde(default, skip_serializing_if = "ItemRequirements::is_empty")]
+    pub requirements: ItemRequirements,
+    // A texture with the same layout as the texture of an actor's animation player, which is
+    // drawn over the actor, with the same animation, while the item is equipped
+    #[serde(
+        default,
+        rename = "equipped_texture",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub equipped_texture_id: Option<String>,
+    // The number of times a consumable can be used, before it is removed from the inventory
+    #[serde(default = "ItemParams::default_charges")]
+    pub charges: u32,
+    // Consumables of the same category share a cooldown, so that using a potion will prevent the
+    // use of any other potions, until the cooldown has passed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub cooldown: f32,
+    // Effects that are applied to the actor that uses a consumable, regardless of faction
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub effects: Vec<Effect>,
+    // The maximum number of items in a stack. Items with a `max_stack` of `1` can not be stacked.
+    #[serde(default = "ItemParams::default_max_stack")]
+    pub max_stack: u32,
+    // The number of items in a stack
+    #[serde(default = "ItemParams::default_quantity")]
+    pub quantity: u32,
+    // The size of an item in inventories that have a grid capacity, which defaults to one by one
+    #[serde(
+        default,
+        with = "json::opt_uvec2",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub grid_size: Option<UVec2>,
+    // The rarity that was rolled when the item was generated. If this is set on a prototype, the
+    // rarity will not be rolled, but the affixes of the rarity will be.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rarity: Option<String>,
+    // The affixes that were rolled when the item was generated, with their rolled values
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub affixes: Vec<ItemAffix>,
+}
+
+impl ItemParams {
+    pub fn default_charges() -> u32 {
+        1
+    }
+
+    pub fn default_max_stack() -> u32 {
+        1
+    }
+
+    pub fn default_quantity() -> u32 {
+        1
+    }
+
+    // This creates a new instance of an item prototype, with a unique id
+    pub fn instantiate(&self) -> ItemParams {
+        ItemParams {
+            id: generate_id(),
+            prototype_id: Some(self.id.clone()),
+            ..self.clone()
+        }
+    }
+
+    // The id of the cooldown that is shared by consumables of the same category. Consumables that
+    // have no category will only share their cooldown with items of the same prototype.
+    pub fn get_cooldown_id(&self) -> String {
+        self.category
+            .clone()
+            .or_else(|| self.prototype_id.clone())
+            .unwrap_or_else(|| self.id.clone())
+    }
+
+    // Items can be stacked if they are instances of the same stackable prototype and, in the case
+    // of consumables, have the same number of charges left
+    pub fn can_stack_with(&self, other: &ItemParams) -> bool {
+        self.max_stack > 1
+            && self.prototype_id.is_some()
+            && self.prototype_id == other.prototype_id
+            && self.charges == other.charges
+    }
+
+    pub fn get_grid_size(&self) -> UVec2 {
+        self.grid_size.unwrap_or_else(|| uvec2(1, 1))
+    }
+
+    pub fn get_total_weight(&self) -> f32 {
+        self.weight * self.quantity as f32
+    }
+
+    // The modifiers of the item itself, followed by those of its affixes
+    pub fn get_modifiers(&self) -> Vec<StatModifier> {
+        let mut modifiers = self.modifiers.clone();
+        for affix in &self.affixes {
+            modifiers.extend(affix.modifiers.iter().cloned());
+        }
+        modifiers
+    }
+
+    pub fn get_affixed_name(&self) -> String {
+        self.compose_affixed_name(&self.name, |affix_name| affix_name.to_string())
+    }
+
+    // This composes an affixed name from `name`, with the names of the affixes mapped by `f`, so
+    // that the names can be translated before they are composed
+    pub fn compose_affixed_name<F: Fn(&str) -> String>(&self, name: &str, f: F) -> String {
+        let mut name = name.to_string();
+        for affix in self.affixes.iter().rev() {
+            if affix.kind == AffixKind::Prefix {
+                name = format!("{} {}", f(&affix.name), name);
+            }
+        }
+        for affix in &self.affixes {
+            if affix.kind == AffixKind::Suffix {
+                name = format!("{} {}", name, f(&affix.name));
+            }
+        }
+        name
+    }
 }
 
 impl Default for ItemParams {
     fn default() -> Self {
         ItemParams {
             id: "".to_string(),
+            prototype_id: None,
             name: "Unnamed Item".to_string(),
             description: "".to_string(),
             position: Default::default(),
             kind: ItemKind::Misc,
             weight: 0.1,
+            value: 0,
             ability_id: None,
             sprite: Default::default(),
             is_quest_item: false,
+            modifiers: Vec::new(),
+            slot: None,
+            requirements: Default::default(),
+            equipped_texture_id: None,
+            charges: Self::default_charges(),
+            category: None,
+            cooldown: 0.0,
+            effects: Vec::new(),
+            max_stack: Self::default_max_stack(),
+            quantity: Self::default_quantity(),
+            grid_size: None,
+            rarity: None,
+            affixes: Vec::new(),
         }
     }
 }
@@ -47,12 +234,27 @@ impl Default for ItemParams {
 #[derive(Clone)]
 pub struct Item {
     pub id: String,
+    pub prototype_id: Option<String>,
     pub name: String,
     pub description: String,
     pub position: Vec2,
     pub kind: ItemKind,
     pub weight: f32,
+    pub value: u32,
     pub is_quest_item: bool,
+    pub modifiers: Vec<StatModifier>,
+    pub slot: Option<String>,
+    pub requirements: ItemRequirements,
+    pub equipped_texture_id: Option<String>,
+    pub charges: u32,
+    pub category: Option<String>,
+    pub cooldown: f32,
+    pub effects: Vec<Effect>,
+    pub max_stack: u32,
+    pub quantity: u32,
+    pub grid_size: Option<UVec2>,
+    pub rarity: Option<String>,
+    pub affixes: Vec<ItemAffix>,
     ability: Option<AbilityParams>,
     sprite: Sprite,
 }
@@ -68,12 +270,27 @@ impl Item {
 
         Item {
             id: params.id,
+            prototype_id: params.prototype_id,
             position: params.position.unwrap_or_default(),
             kind: params.kind,
             name: params.name,
             description: params.description,
             weight: params.weight,
+            value: params.value,
             is_quest_item: params.is_quest_item,
+            modifiers: params.modifiers,
+            slot: params.slot,
+            requirements: params.requirements,
+            equipped_texture_id: params.equipped_texture_id,
+            charges: params.charges,
+            category: params.category,
+            cooldown: params.cooldown,
+            effects: params.effects,
+            max_stack: params.max_stack,
+            quantity: params.quantity,
+            grid_size: params.grid_size,
+            rarity: params.rarity,
+            affixes: params.affixes,
             ability,
             sprite: params.sprite,
         }
@@ -88,14 +305,29 @@ impl Item {
 
         ItemParams {
             id: self.id.clone(),
+            prototype_id: self.prototype_id.clone(),
             name: self.name.clone(),
             description: self.description.clone(),
             position: Some(self.position),
             kind: self.kind.clone(),
             weight: self.weight,
+            value: self.value,
             ability_id,
             sprite: self.sprite.clone(),
             is_quest_item: self.is_quest_item,
+            modifiers: self.modifiers.clone(),
+            slot: self.slot.clone(),
+            requirements: self.requirements.clone(),
+            equipped_texture_id: self.equipped_texture_id.clone(),
+            charges: self.charges,
+            category: self.category.clone(),
+            cooldown: self.cooldown,
+            effects: self.effects.clone(),
+            max_stack: self.max_stack,
+            quantity: self.quantity,
+            grid_size: self.grid_size,
+            rarity: self.rarity.clone(),
+            affixes: self.affixes.clone(),
         }
     }
 }
@@ -166,3 +398,59 @@ impl Node for Credits {
         draw_buffer.buffered.push(node.handle());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consumable_from_json() {
+        let json = r#"{
+            "id": "health_potion",
+            "kind": "consumable",
+            "name": "Health Potion",
+            "description": "",
+            "weight": 0.5,
+            "charges": 3,
+            "category": "potion",
+            "cooldown": 10.0,
+            "effects": [{ "type": "heal", "amount": 100.0 }],
+            "sprite": { "texture_id": "items", "texture_coords": { "x": 0, "y": 0 }, "tile_size": { "x": 16, "y": 16 } }
+        }"#;
+        let params: ItemParams = serde_json::from_str(json).unwrap();
+        assert_eq!(params.kind, ItemKind::Consumable);
+        assert_eq!(params.charges, 3);
+        assert_eq!(params.effects, vec![Effect::Heal { amount: 100.0 }]);
+        assert_eq!(params.get_cooldown_id(), "potion");
+
+        let params = ItemParams {
+            id: "grenade".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(params.charges, 1);
+        assert_eq!(params.get_cooldown_id(), "grenade");
+    }
+
+    #[test]
+    fn test_requirements() {
+        let mut stats = ActorStats {
+            strength: 10,
+            ..Default::default()
+        };
+        stats.recalculate_derived_with(&StatFormulas::default());
+
+        let json = r#"{ "classes": ["soldier"], "attributes": { "strength": 12 } }"#;
+        let requirements: ItemRequirements = serde_json::from_str(json).unwrap();
+        assert!(!requirements.is_met(Some("soldier"), &stats));
+
+        stats.modifiers.add(
+            "test",
+            &[StatModifier::new("strength", StatModifierKind::Flat, 2.0)],
+        );
+        stats.recalculate_derived_with(&StatFormulas::default());
+        assert!(requirements.is_met(Some("soldier"), &stats));
+        assert!(!requirements.is_met(Some("hacker"), &stats));
+        assert!(!requirements.is_met(None, &stats));
+        assert!(ItemRequirements::default().is_met(None, &stats));
+    }
+}
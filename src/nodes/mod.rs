@@ -1,5 +1,6 @@
 pub use actor::Actor;
 pub use camera_controller::CameraController;
+pub use container::Container;
 pub use continuous_beams::ContinuousBeams;
 pub use draw_buffer::DrawBuffer;
 pub use game_state::GameState;
@@ -16,6 +17,7 @@ pub use map_renderer::MapRenderer;
 
 pub mod actor;
 pub mod camera_controller;
+pub mod container;
 pub mod continuous_beams;
 pub mod draw_buffer;
 pub mod game_state;
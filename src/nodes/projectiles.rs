@@ -11,7 +11,10 @@ pub enum ProjectileKind {
 
 pub struct ProjectileParams {
     pub kind: ProjectileKind,
-    pub effects: Vec<Effect>,
+    pub effects: Vec<AbilityEffect>,
+    pub collision_mask: Vec<String>,
+    // Actors on this layer are passed through, as allies of the source
+    pub friendly_layer: Option<String>,
     pub color: Color,
     pub size: f32,
     pub origin: Vec2,
@@ -26,7 +29,9 @@ pub struct Projectile {
     actor: Handle<Actor>,
     factions: Vec<String>,
     kind: ProjectileKind,
-    effects: Vec<Effect>,
+    effects: Vec<AbilityEffect>,
+    collision_mask: Vec<String>,
+    friendly_layer: Option<String>,
     color: Color,
     size: f32,
     origin: Vec2,
@@ -51,6 +56,8 @@ impl Projectile {
             factions: factions.to_vec(),
             kind: params.kind,
             effects: params.effects,
+            collision_mask: params.collision_mask,
+            friendly_layer: params.friendly_layer,
             color: params.color,
             size: params.size,
             origin: params.origin,
@@ -139,43 +146,52 @@ impl Node for Projectiles {
 
             let collider =
                 Collider::circle(0.0, 0.0, projectile.size / 2.0).with_offset(projectile.position);
-            'outer: for mut other_actor in scene::find_nodes_by_type::<Actor>() {
+            for mut other_actor in scene::find_nodes_by_type::<Actor>() {
+                if other_actor.id == projectile.actor_id
+                    || !can_hit_actor(
+                        &projectile.collision_mask,
+                        projectile.friendly_layer.as_deref(),
+                        &other_actor.body.collision_layer,
+                    )
+                {
+                    continue;
+                }
+
                 if let Some(other_collider) = other_actor.body.get_offset_collider() {
+                    // Projectiles are stopped by any actor in their collision mask, even if none
+                    // of their effects apply to it
                     if collider.overlaps(other_collider) {
-                        let mut is_hit = false;
-
-                        for effect in &projectile.effects {
-                            if other_actor.apply_effect(
-                                &projectile.actor_id,
-                                projectile.actor,
-                                &projectile.factions,
-                                effect.clone(),
-                            ) {
-                                is_hit = true;
-                            } else {
-                                continue 'outer;
-                            }
-                        }
-
-                        if is_hit {
-                            if let Some(sound_effect) = projectile.on_hit_sound_effect {
-                                play_sound(sound_effect, false);
-                            }
+                        other_actor.apply_hit(
+                            &projectile.actor_id,
+                            projectile.actor,
+                            &projectile.factions,
+                            projectile.direction,
+                            &projectile.effects,
+                        );
 
-                            return false;
+                        if let Some(sound_effect) = projectile.on_hit_sound_effect {
+                            play_sound(sound_effect, false);
                         }
+
+                        return false;
                     }
                 }
             }
 
             let map = storage::get::<Map>();
-            for (_, kind) in map.get_collisions(collider) {
-                if kind == CollisionKind::Solid {
-                    if let Some(sound_effect) = projectile.on_hit_sound_effect {
-                        play_sound(sound_effect, false);
-                    }
-                    return false;
+            let is_edge_hit = map
+                .get_masked_edges(collider, &projectile.collision_mask)
+                .into_iter()
+                .any(|(edge, _)| collider.overlaps(edge));
+            if is_edge_hit
+                || !map
+                    .get_masked_collisions(collider, &projectile.collision_mask)
+                    .is_empty()
+            {
+                if let Some(sound_effect) = projectile.on_hit_sound_effect {
+                    play_sound(sound_effect, false);
                 }
+                return false;
             }
             true
         });
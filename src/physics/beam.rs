@@ -13,33 +13,25 @@ pub fn beam_collision_check(
     area.abs() < width * tolerance
 }
 
-pub fn get_beam_end(origin: Vec2, end: Vec2, width: f32, tolerance: f32) -> Vec2 {
-    let map = storage::get::<Map>();
+pub fn get_beam_end(
+    map: &Map,
+    origin: Vec2,
+    end: Vec2,
+    width: f32,
+    tolerance: f32,
+    collision_mask: &[String],
+) -> Vec2 {
     let tile_size = map.tile_size;
-    let collider = {
-        let ((x, w), (y, h)) = (
-            if origin.x > end.x {
-                (end.x, origin.x)
-            } else {
-                (origin.x, end.x)
-            },
-            if origin.y > end.y {
-                (end.y, origin.y)
-            } else {
-                (origin.y, end.y)
-            },
-        );
-        Collider::rect(x, y, w, h)
-    };
+    let min = origin.min(end);
+    let max = origin.max(end);
+    let collider = Collider::rect(min.x, min.y, max.x - min.x, max.y - min.y).with_padding(width);
 
     let mut collisions: Vec<Vec2> = map
-        .get_collisions(collider)
+        .get_masked_collisions(collider, collision_mask)
         .into_iter()
-        .filter_map(|(position, kind)| {
+        .filter_map(|(position, _)| {
             let position = position + tile_size / 2.0;
-            if kind == CollisionKind::Solid
-                && beam_collision_check(position, origin, end, width, tolerance)
-            {
+            if beam_collision_check(position, origin, end, width, tolerance) {
                 Some(position)
             } else {
                 None
@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::math::Circle;
 
+use super::get_penetration;
+
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 #[serde(untagged, rename_all = "snake_case")]
 pub enum Collider {
@@ -25,6 +27,14 @@ pub enum Collider {
         #[serde(alias = "radius")]
         r: f32,
     },
+    // A line segment, that can be used for diagonal walls. Edges only collide with rectangles and
+    // circles, not with other edges.
+    Edge {
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+    },
 }
 
 impl Collider {
@@ -36,6 +46,10 @@ impl Collider {
         Collider::Circle { x, y, r }
     }
 
+    pub fn edge(x1: f32, y1: f32, x2: f32, y2: f32) -> Collider {
+        Collider::Edge { x1, y1, x2, y2 }
+    }
+
     pub fn with_padding(self, padding: f32) -> Collider {
         match self {
             Collider::Rectangle { x, y, w, h } => Collider::Rectangle {
@@ -49,6 +63,7 @@ impl Collider {
                 y,
                 r: r + padding,
             },
+            Collider::Edge { .. } => self,
         }
     }
 
@@ -65,6 +80,12 @@ impl Collider {
                 y: y + offset.y,
                 r,
             },
+            Collider::Edge { x1, y1, x2, y2 } => Collider::Edge {
+                x1: x1 + offset.x,
+                y1: y1 + offset.y,
+                x2: x2 + offset.x,
+                y2: y2 + offset.y,
+            },
         }
     }
 
@@ -72,6 +93,7 @@ impl Collider {
         match self {
             Collider::Rectangle { x, y, w: _, h: _ } => vec2(x, y),
             Collider::Circle { x, y, r: _ } => vec2(x, y),
+            Collider::Edge { x1, y1, .. } => vec2(x1, y1),
         }
     }
 
@@ -79,6 +101,7 @@ impl Collider {
         match self {
             Collider::Rectangle { x, y, w, h } => vec2(x + w / 2.0, y + h / 2.0),
             Collider::Circle { x, y, r: _ } => vec2(x, y),
+            Collider::Edge { x1, y1, x2, y2 } => vec2(x1 + x2, y1 + y2) / 2.0,
         }
     }
 
@@ -89,6 +112,7 @@ impl Collider {
                 match other {
                     Collider::Rectangle { x, y, w, h } => rect.overlaps(&Rect::new(x, y, w, h)),
                     Collider::Circle { x, y, r } => Circle::new(x, y, r).overlaps_rect(&rect),
+                    Collider::Edge { .. } => get_penetration(self, other).is_some(),
                 }
             }
             Collider::Circle { x, y, r } => {
@@ -98,8 +122,10 @@ impl Collider {
                         circle.overlaps_rect(&Rect::new(x, y, w, h))
                     }
                     Collider::Circle { x, y, r } => Circle::new(x, y, r).overlaps(&circle),
+                    Collider::Edge { .. } => get_penetration(self, other).is_some(),
                 }
             }
+            Collider::Edge { .. } => get_penetration(self, other).is_some(),
         }
     }
 
@@ -107,6 +133,7 @@ impl Collider {
         match self {
             Collider::Rectangle { x, y, w, h } => Rect::new(x, y, w, h).overlaps(rect),
             Collider::Circle { x, y, r } => Circle::new(x, y, r).overlaps_rect(rect),
+            Collider::Edge { .. } => self.overlaps(Collider::rect(rect.x, rect.y, rect.w, rect.h)),
         }
     }
 
@@ -114,6 +141,7 @@ impl Collider {
         match self {
             Collider::Rectangle { x, y, w, h } => circle.overlaps_rect(&Rect::new(x, y, w, h)),
             Collider::Circle { x, y, r } => Circle::new(x, y, r).overlaps(circle),
+            Collider::Edge { .. } => self.overlaps(Collider::circle(circle.x, circle.y, circle.r)),
         }
     }
 
@@ -121,15 +149,58 @@ impl Collider {
         match self {
             Collider::Rectangle { x, y, w, h } => Rect::new(x, y, w, h).contains(position),
             Collider::Circle { x, y, r } => Circle::new(x, y, r).contains(&position),
+            Collider::Edge { .. } => false,
         }
     }
 }
 
+// This is used when a collider is defined in data files, along with the collision layer its body
+// belongs to and the layers it collides with. If these are not set, the defaults of the body will
+// be used.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColliderParams {
+    #[serde(flatten)]
+    pub collider: Collider,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collision_layer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collision_mask: Option<Vec<String>>,
+}
+
 impl From<Collider> for Rect {
     fn from(collider: Collider) -> Rect {
         match collider {
             Collider::Rectangle { x, y, w, h } => Rect::new(x, y, w, h),
             Collider::Circle { x, y, r } => Rect::new(x - r, y - r, r * 2.0, r * 2.0),
+            Collider::Edge { x1, y1, x2, y2 } => {
+                Rect::new(x1.min(x2), y1.min(y2), (x2 - x1).abs(), (y2 - y1).abs())
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collider_params_from_json() {
+        let json = r#"{"x": -8.0, "y": -8.0, "width": 16.0, "height": 16.0}"#;
+        let params: ColliderParams = serde_json::from_str(json).unwrap();
+        assert!(matches!(params.collider, Collider::Rectangle { w, .. } if w == 16.0));
+        assert_eq!(params.collision_layer, None);
+        assert_eq!(params.collision_mask, None);
+
+        let json = r#"{"radius": 8.0, "collision_layer": "ghost", "collision_mask": ["solid"]}"#;
+        let params: ColliderParams = serde_json::from_str(json).unwrap();
+        assert!(matches!(params.collider, Collider::Circle { r, .. } if r == 8.0));
+        assert_eq!(params.collision_layer, Some("ghost".to_string()));
+        assert_eq!(params.collision_mask, Some(vec!["solid".to_string()]));
+
+        let json = r#"{"x1": 0.0, "y1": 0.0, "x2": 16.0, "y2": 16.0}"#;
+        let params: ColliderParams = serde_json::from_str(json).unwrap();
+        assert!(
+            matches!(params.collider, Collider::Edge { x2, .. } if (x2 - 16.0).abs() < f32::EPSILON)
+        );
+    }
+}
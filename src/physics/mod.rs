@@ -4,17 +4,68 @@ mod beam;
 mod collider;
 mod physics_body;
 mod raycast;
+mod sweep;
 
-pub use physics_body::PhysicsBody;
+pub use physics_body::{ActorObstacle, PhysicsBody, DEFAULT_DAMPING, DEFAULT_MASS};
 
-pub use collider::Collider;
+pub use collider::{Collider, ColliderParams};
 
 pub use beam::{beam_collision_check, get_beam_end};
 
-pub use raycast::raycast;
+pub use raycast::{raycast, raycast_map};
+
+pub use sweep::{get_penetration, sweep_collider, SweepHit, CONTACT_SKIN};
 
 pub const COLLISION_RESOLUTION: f32 = 0.25;
 
+pub const SOLID_COLLISION_LAYER: &str = "solid";
+pub const BARRIER_COLLISION_LAYER: &str = "barrier";
+pub const ACTOR_COLLISION_LAYER: &str = "actor";
+
+pub fn default_body_collision_mask() -> Vec<String> {
+    vec![
+        SOLID_COLLISION_LAYER.to_string(),
+        BARRIER_COLLISION_LAYER.to_string(),
+        ACTOR_COLLISION_LAYER.to_string(),
+    ]
+}
+
+pub fn default_projectile_collision_mask() -> Vec<String> {
+    vec![
+        SOLID_COLLISION_LAYER.to_string(),
+        ACTOR_COLLISION_LAYER.to_string(),
+    ]
+}
+
+// Layers can be nested, by separating their names with `.`, so that a layer like `actor.guards` is
+// also a part of the `actor` layer, and is in any collision mask that holds `actor`
+pub fn is_layer_in_mask(layer: &str, mask: &[String]) -> bool {
+    mask.iter().any(|name| {
+        layer == name
+            || layer
+                .strip_prefix(name.as_str())
+                .map(|rest| rest.starts_with('.'))
+                .unwrap_or(false)
+    })
+}
+
+// Returns the layer of the actors that projectiles, and beams, fired by an actor on `source_layer`,
+// should pass through, when their ability doesn't define a collision mask. Actors on the shared
+// `actor` layer have no allies, by layer, so their projectiles will hit any actor.
+pub fn get_friendly_collision_layer(source_layer: &str) -> Option<String> {
+    if source_layer == ACTOR_COLLISION_LAYER {
+        None
+    } else {
+        Some(source_layer.to_string())
+    }
+}
+
+// Returns `true` if a projectile, or beam, with `collision_mask` and `friendly_layer`, will hit an
+// actor on `layer`
+pub fn can_hit_actor(collision_mask: &[String], friendly_layer: Option<&str>, layer: &str) -> bool {
+    friendly_layer != Some(layer) && is_layer_in_mask(layer, collision_mask)
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CollisionKind {
     #[serde(rename = "none")]
@@ -31,6 +82,16 @@ impl CollisionKind {
     pub fn is_none(&self) -> bool {
         matches!(self, Self::None)
     }
+
+    // This returns the name of the collision layer that all colliders of this kind belong to
+    pub fn get_collision_layer(&self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Barrier => Some(BARRIER_COLLISION_LAYER),
+            Self::Solid => Some(SOLID_COLLISION_LAYER),
+            Self::Actor => Some(ACTOR_COLLISION_LAYER),
+        }
+    }
 }
 
 impl Default for CollisionKind {
@@ -38,3 +99,46 @@ impl Default for CollisionKind {
         CollisionKind::None
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct Collision {
+    pub collider: Collider,
+    pub kind: CollisionKind,
+    // The surface normal of the collider that was hit, at the point of contact
+    pub normal: Vec2,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_friendly_layers() {
+        let mask = default_projectile_collision_mask();
+        assert!(is_layer_in_mask("actor.guards", &mask));
+        assert!(!is_layer_in_mask("actors", &mask));
+        assert!(!is_layer_in_mask("barrier", &mask));
+
+        // A guard's projectile passes through allied guards, but hits thugs and bystanders
+        let friendly_layer = get_friendly_collision_layer("actor.guards");
+        assert!(!can_hit_actor(
+            &mask,
+            friendly_layer.as_deref(),
+            "actor.guards"
+        ));
+        assert!(can_hit_actor(
+            &mask,
+            friendly_layer.as_deref(),
+            "actor.thugs"
+        ));
+        assert!(can_hit_actor(&mask, friendly_layer.as_deref(), "actor"));
+
+        // Actors on the shared `actor` layer hit each other
+        let friendly_layer = get_friendly_collision_layer(ACTOR_COLLISION_LAYER);
+        assert!(can_hit_actor(
+            &mask,
+            friendly_layer.as_deref(),
+            ACTOR_COLLISION_LAYER
+        ));
+    }
+}
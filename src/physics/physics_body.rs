@@ -1,14 +1,48 @@
 use crate::prelude::*;
 
-use super::COLLISION_RESOLUTION;
+#[cfg(feature = "collision-between-actors")]
+use super::get_penetration;
+use super::{
+    default_body_collision_mask, sweep_collider, Collision, SweepHit, ACTOR_COLLISION_LAYER,
+    CONTACT_SKIN,
+};
+
+const MAX_SWEEP_ITERATIONS: usize = 4;
+
+pub const DEFAULT_MASS: f32 = 1.0;
+pub const DEFAULT_DAMPING: f32 = 8.0;
+
+// External velocity below this will be zeroed, after damping has been applied
+const MIN_EXTERNAL_VELOCITY: f32 = 0.01;
+
+// A snapshot of another body, used when resolving collisions between actors, so that we don't
+// have to hold on to references to other nodes while integrating
+#[derive(Debug, Clone)]
+pub struct ActorObstacle {
+    pub collider: Collider,
+    pub collision_layer: String,
+    pub mass: f32,
+}
 
 #[derive(Clone)]
 pub struct PhysicsBody {
     pub position: Vec2,
     pub rotation: f32,
+    // This is the velocity the body wants to move at, and it is set by its owner every frame
     pub velocity: Vec2,
+    // This is the velocity that has been imparted on the body by impulses and forces. It is added
+    // to `velocity` and it will decay over time, according to `damping`
+    pub external_velocity: Vec2,
     pub collider: Option<Collider>,
-    pub last_collisions: Vec<(Collider, CollisionKind)>,
+    // The collision layer this body belongs to
+    pub collision_layer: String,
+    // The collision layers this body will collide with
+    pub collision_mask: Vec<String>,
+    pub mass: f32,
+    pub damping: f32,
+    pub max_speed: Option<f32>,
+    pub last_collisions: Vec<Collision>,
+    force: Vec2,
 }
 
 impl PhysicsBody {
@@ -17,7 +51,14 @@ impl PhysicsBody {
             position,
             rotation,
             velocity: Vec2::ZERO,
+            external_velocity: Vec2::ZERO,
             collider,
+            collision_layer: ACTOR_COLLISION_LAYER.to_string(),
+            collision_mask: default_body_collision_mask(),
+            mass: DEFAULT_MASS,
+            damping: DEFAULT_DAMPING,
+            max_speed: None,
+            force: Vec2::ZERO,
             last_collisions: Vec::new(),
         }
     }
@@ -30,18 +71,30 @@ impl PhysicsBody {
                         draw_rectangle_lines(x, y, w, h, 2.0, color::RED)
                     }
                     Collider::Circle { x, y, r } => draw_circle_lines(x, y, r, 2.0, color::RED),
+                    Collider::Edge { x1, y1, x2, y2 } => draw_line(x1, y1, x2, y2, 2.0, color::RED),
                 }
             }
 
-            for (collider, kind) in self.last_collisions.clone() {
-                match collider {
+            for collision in self.last_collisions.clone() {
+                match collision.collider {
                     Collider::Rectangle { x, y, w, h } => draw_rectangle(x, y, w, h, color::RED),
                     Collider::Circle { x, y, r } => draw_circle(x, y, r, color::RED),
+                    Collider::Edge { x1, y1, x2, y2 } => draw_line(x1, y1, x2, y2, 4.0, color::RED),
                 }
 
-                let position = collider.get_center();
+                let position = collision.collider.get_center();
+                let normal_end = position + collision.normal * 8.0;
+                draw_line(
+                    position.x,
+                    position.y,
+                    normal_end.x,
+                    normal_end.y,
+                    2.0,
+                    color::YELLOW,
+                );
+
                 draw_text(
-                    match kind {
+                    match collision.kind {
                         CollisionKind::Actor => "A",
                         CollisionKind::Barrier => "B",
                         CollisionKind::Solid => "S",
@@ -76,134 +129,340 @@ impl PhysicsBody {
             .map(|collider| collider.with_offset(self.position))
     }
 
-    pub fn integrate(&mut self) {
-        if let Some(mut collider) = self.get_offset_collider() {
-            let movement = (self.velocity * 50.0) * get_frame_time();
+    pub fn apply_impulse(&mut self, impulse: Vec2) {
+        if self.mass > 0.0 {
+            self.external_velocity += impulse / self.mass;
+        }
+    }
 
-            if movement != Vec2::ZERO {
-                #[cfg(feature = "collision_between_actors")]
-                let nearby_actors = {
-                    let viewport = storage::get::<Viewport>();
-                    scene::find_nodes_by_type::<Actor>()
-                        .into_iter()
-                        .filter(|actor| actor.is_in_frustum(&viewport.get_frustum()))
-                        .collect()
-                };
+    // Forces are accumulated until the next time the body is integrated, so a continuous force has
+    // to be applied every frame
+    pub fn apply_force(&mut self, force: Vec2) {
+        self.force += force;
+    }
 
-                let increment = vec2(
-                    if movement.x > 0.0 {
-                        COLLISION_RESOLUTION
-                    } else {
-                        -COLLISION_RESOLUTION
-                    },
-                    if movement.y > 0.0 {
-                        COLLISION_RESOLUTION
-                    } else {
-                        -COLLISION_RESOLUTION
-                    },
-                );
+    // This moves the body up to `distance` along `direction`, instantly, stopping before anything
+    // on the map that it would collide with, so that it can not be teleported through walls
+    pub fn teleport(&mut self, map: &Map, direction: Vec2, distance: f32) {
+        let direction = direction.normalize_or_zero();
+        if direction == Vec2::ZERO {
+            return;
+        }
 
-                let mut x_collisions = Vec::new();
-                let mut y_collisions = Vec::new();
+        // The collider is swept along the whole teleport, with a single query of the map, and
+        // stops at the first obstacle it would hit, keeping the contact skin away from it. A body
+        // that is touching a wall can still be teleported along it, or away from it.
+        let collider = self
+            .collider
+            .unwrap_or_else(|| Collider::circle(0.0, 0.0, 1.0))
+            .with_offset(self.position);
+        let movement = direction * distance;
+        let first_hit = get_map_obstacles(map, collider, &self.collision_mask, movement)
+            .into_iter()
+            .filter_map(|(other, _)| sweep_collider(collider, movement, other))
+            .map(|hit| hit.time)
+            .reduce(f32::min);
+
+        let traveled = match first_hit {
+            Some(time) => (distance * time - CONTACT_SKIN).max(0.0),
+            None => distance,
+        };
+        self.position += direction * traveled;
+        self.external_velocity = Vec2::ZERO;
+    }
 
-                let mut final_movement = Vec2::ZERO;
+    pub fn integrate(&mut self, map: &Map, dt: f32) {
+        let movement = self.get_movement(dt);
+        self.move_and_collide(map, movement, Vec::new());
+        self.apply_damping(dt);
+    }
 
-                while final_movement.x != movement.x {
-                    x_collisions.clear();
+    // Other actors will only block this body if they are at least as heavy as it is. Lighter actors
+    // are moved through, and both bodies will push each other apart, proportionally to the mass of
+    // the other body, until they no longer overlap.
+    #[cfg(feature = "collision-between-actors")]
+    pub fn integrate_with_actors(&mut self, map: &Map, dt: f32, actors: &[ActorObstacle]) {
+        let mut movement = self.get_movement(dt);
+        let mut obstacles = Vec::new();
+
+        if let Some(collider) = self.get_offset_collider() {
+            let (push_apart, blocking) =
+                get_actor_response(collider, self.mass, &self.collision_mask, movement, actors);
+            movement += push_apart;
+            obstacles = blocking;
+        }
 
-                    let modified_increment =
-                        if final_movement.x.abs() < movement.x.abs() - increment.x.abs() {
-                            vec2(increment.x, 0.0)
-                        } else {
-                            vec2(movement.x - final_movement.x, 0.0)
-                        };
+        self.move_and_collide(map, movement, obstacles);
+        self.apply_damping(dt);
+    }
 
-                    let modified_collider = collider.with_offset(modified_increment);
+    fn get_movement(&mut self, dt: f32) -> Vec2 {
+        if self.mass > 0.0 {
+            self.external_velocity += (self.force / self.mass) * dt;
+        }
 
-                    #[cfg(feature = "collision_between_actors")]
-                    {
-                        let mut actor_collisions =
-                            check_actor_collisions(modified_collider, &nearby_actors);
-                        x_collisions.append(&mut actor_collisions);
-                    }
+        self.force = Vec2::ZERO;
 
-                    let mut map_collisions = check_map_collision(modified_collider);
-                    x_collisions.append(&mut map_collisions);
+        let mut velocity = self.velocity + self.external_velocity;
+        if let Some(max_speed) = self.max_speed {
+            velocity = velocity.clamp_length_max(max_speed);
+        }
 
-                    if !x_collisions.is_empty() {
-                        break;
-                    }
+        (velocity * 50.0) * dt
+    }
 
-                    final_movement += modified_increment;
-                    collider = modified_collider;
-                }
+    fn apply_damping(&mut self, dt: f32) {
+        self.external_velocity *= (-self.damping * dt).exp();
+        if self.external_velocity.length() < MIN_EXTERNAL_VELOCITY {
+            self.external_velocity = Vec2::ZERO;
+        }
+    }
 
-                while final_movement.y != movement.y {
-                    y_collisions.clear();
+    fn move_and_collide(
+        &mut self,
+        map: &Map,
+        movement: Vec2,
+        mut obstacles: Vec<(Collider, CollisionKind)>,
+    ) {
+        self.last_collisions = Vec::new();
 
-                    let modified_increment =
-                        if final_movement.y.abs() < movement.y.abs() - increment.y.abs() {
-                            vec2(0.0, increment.y)
-                        } else {
-                            vec2(0.0, movement.y - final_movement.y)
-                        };
+        if movement == Vec2::ZERO {
+            return;
+        }
 
-                    let modified_collider = collider.with_offset(modified_increment);
+        if let Some(collider) = self.get_offset_collider() {
+            obstacles.append(&mut get_map_obstacles(
+                map,
+                collider,
+                &self.collision_mask,
+                movement,
+            ));
+
+            let (movement, collisions) = resolve_movement(collider, movement, &obstacles);
+
+            // Remove any external velocity going into the surfaces that were hit, so that a body
+            // that is knocked into a wall won't keep pushing against it
+            for collision in &collisions {
+                let into_surface = self.external_velocity.dot(collision.normal);
+                if into_surface < 0.0 {
+                    self.external_velocity -= collision.normal * into_surface;
+                }
+            }
 
-                    #[cfg(feature = "collision_between_actors")]
-                    {
-                        let mut actor_collisions =
-                            check_actor_collisions(modified_collider, &nearby_actors);
-                        y_collisions.append(&mut actor_collisions);
-                    }
+            self.position += movement;
+            self.last_collisions = collisions;
+        } else {
+            self.position += movement;
+        }
+    }
+}
 
-                    let mut map_collisions = check_map_collision(modified_collider);
-                    y_collisions.append(&mut map_collisions);
+// This will sweep the collider along the movement vector, against all the obstacles, and stop at
+// the first point of contact. The remainder of the movement is then projected onto the contact
+// surface, so that the collider slides along it, and the process is repeated until the movement
+// is exhausted, or `MAX_SWEEP_ITERATIONS` is reached.
+fn resolve_movement(
+    mut collider: Collider,
+    mut movement: Vec2,
+    obstacles: &[(Collider, CollisionKind)],
+) -> (Vec2, Vec<Collision>) {
+    let mut final_movement = Vec2::ZERO;
+    let mut collisions = Vec::new();
 
-                    if !y_collisions.is_empty() {
-                        break;
-                    }
+    for _ in 0..MAX_SWEEP_ITERATIONS {
+        let mut first_hit: Option<(SweepHit, usize)> = None;
+        for (i, (other, _)) in obstacles.iter().enumerate() {
+            if let Some(hit) = sweep_collider(collider, movement, *other) {
+                let is_first = match first_hit {
+                    Some((first, _)) => hit.time < first.time,
+                    None => true,
+                };
 
-                    final_movement += modified_increment;
-                    collider = modified_collider;
+                if is_first {
+                    first_hit = Some((hit, i));
                 }
-
-                self.last_collisions = Vec::new();
-                self.last_collisions.append(&mut x_collisions);
-                self.last_collisions.append(&mut y_collisions);
-
-                self.position += final_movement;
             }
         }
+
+        if let Some((hit, i)) = first_hit {
+            let step = movement * hit.time + hit.normal * CONTACT_SKIN;
+            collider = collider.with_offset(step);
+            final_movement += step;
+
+            let (other, kind) = obstacles[i].clone();
+            collisions.push(Collision {
+                collider: other,
+                kind,
+                normal: hit.normal,
+            });
+
+            let remaining = movement * (1.0 - hit.time);
+            movement = remaining - hit.normal * remaining.dot(hit.normal);
+        } else {
+            final_movement += movement;
+            break;
+        }
     }
+
+    (final_movement, collisions)
 }
 
-fn check_map_collision(collider: Collider) -> Vec<(Collider, CollisionKind)> {
-    let map = storage::get::<Map>();
-    let tile_size = map.tile_size;
-    let collisions = map.get_collisions(collider);
+// This gets all the map tiles, and the edges of collider layers, that can be hit by the collider,
+// along the movement vector, in one query, so that we don't have to query the map for every step
+// of the movement
+fn get_map_obstacles(
+    map: &Map,
+    collider: Collider,
+    collision_mask: &[String],
+    movement: Vec2,
+) -> Vec<(Collider, CollisionKind)> {
+    let broad_phase = get_broad_phase(collider, movement);
 
-    collisions
+    let tile_size = map.tile_size;
+    let mut res: Vec<(Collider, CollisionKind)> = map
+        .get_masked_collisions(broad_phase, collision_mask)
         .into_iter()
         .map(|(position, kind)| {
             let collider = Collider::rect(position.x, position.y, tile_size.x, tile_size.y);
             (collider, kind)
         })
-        .collect()
+        .collect();
+
+    res.append(&mut map.get_masked_edges(broad_phase, collision_mask));
+    res
+}
+
+fn get_broad_phase(collider: Collider, movement: Vec2) -> Collider {
+    let rect = Rect::from(collider);
+    let bounds = rect.combine_with(rect.offset(movement));
+    Collider::rect(bounds.x, bounds.y, bounds.w, bounds.h).with_padding(1.0)
 }
 
-#[cfg(feature = "collision_between_actors")]
-fn check_actor_collisions(
+// This returns the displacement needed to push the collider out of any actors it overlaps, as
+// well as the actors, within reach of the movement, that should block it
+#[cfg(feature = "collision-between-actors")]
+fn get_actor_response(
     collider: Collider,
-    actors: &Vec<RefMut<Actor>>,
-) -> Vec<(Collider, CollisionKind)> {
-    let mut collisions = Vec::new();
+    mass: f32,
+    collision_mask: &[String],
+    movement: Vec2,
+    actors: &[ActorObstacle],
+) -> (Vec2, Vec<(Collider, CollisionKind)>) {
+    let broad_phase = get_broad_phase(collider, movement);
+
+    let mut push_apart = Vec2::ZERO;
+    let mut blocking = Vec::new();
     for actor in actors {
-        if let Some(other_collider) = actor.body.get_offset_collider() {
-            if collider.overlaps(other_collider) {
-                collisions.push((other_collider, CollisionKind::Actor));
-            }
+        if !is_layer_in_mask(&actor.collision_layer, collision_mask) {
+            continue;
         }
+
+        if !Rect::from(broad_phase).overlaps(&Rect::from(actor.collider)) {
+            continue;
+        }
+
+        if let Some((normal, depth)) = get_penetration(collider, actor.collider) {
+            let total_mass = mass + actor.mass;
+            let share = if total_mass > 0.0 {
+                actor.mass / total_mass
+            } else {
+                0.5
+            };
+
+            push_apart += normal * depth * share;
+        } else if actor.mass >= mass {
+            blocking.push((actor.collider, CollisionKind::Actor));
+        }
+    }
+
+    (push_apart, blocking)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "collision-between-actors")]
+    use crate::physics::SOLID_COLLISION_LAYER;
+
+    #[test]
+    fn test_resolve_movement_slides_along_actor() {
+        let collider = Collider::rect(0.0, 0.0, 10.0, 10.0);
+        let obstacles = [(
+            Collider::rect(15.0, -20.0, 10.0, 40.0),
+            CollisionKind::Actor,
+        )];
+        let (movement, collisions) = resolve_movement(collider, vec2(10.0, 10.0), &obstacles);
+        assert!((movement.x - 5.0).abs() < 0.1);
+        assert_eq!(movement.y, 10.0);
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].kind, CollisionKind::Actor);
+        assert_eq!(collisions[0].normal, vec2(-1.0, 0.0));
+    }
+
+    #[test]
+    fn test_resolve_movement_slides_along_diagonal_edge() {
+        let collider = Collider::rect(0.0, 0.0, 10.0, 10.0);
+        let obstacles = [(Collider::edge(30.0, 0.0, 0.0, 30.0), CollisionKind::Solid)];
+        let (movement, collisions) = resolve_movement(collider, vec2(20.0, 0.0), &obstacles);
+        assert!((movement.x - 15.0).abs() < 0.1);
+        assert!((movement.y + 5.0).abs() < 0.1);
+        assert_eq!(collisions.len(), 1);
+        assert!((collisions[0].normal - vec2(-1.0, -1.0).normalize()).length() < 0.0001);
+    }
+
+    #[cfg(feature = "collision-between-actors")]
+    #[test]
+    fn test_actor_response_blocking_by_mass() {
+        let collider = Collider::rect(0.0, 0.0, 10.0, 10.0);
+        let mask = default_body_collision_mask();
+        let light = ActorObstacle {
+            collider: Collider::rect(15.0, 0.0, 10.0, 10.0),
+            collision_layer: ACTOR_COLLISION_LAYER.to_string(),
+            mass: 1.0,
+        };
+        let heavy = ActorObstacle {
+            mass: 2.0,
+            ..light.clone()
+        };
+        let far_away = ActorObstacle {
+            collider: Collider::rect(100.0, 0.0, 10.0, 10.0),
+            ..heavy.clone()
+        };
+
+        let (_, blocking) = get_actor_response(collider, 2.0, &mask, vec2(10.0, 0.0), &[light]);
+        assert!(blocking.is_empty());
+
+        let (_, blocking) =
+            get_actor_response(collider, 1.0, &mask, vec2(10.0, 0.0), &[heavy.clone()]);
+        assert_eq!(blocking.len(), 1);
+
+        let (_, blocking) = get_actor_response(collider, 1.0, &mask, vec2(10.0, 0.0), &[far_away]);
+        assert!(blocking.is_empty());
+
+        let ghost_mask = vec![SOLID_COLLISION_LAYER.to_string()];
+        let (_, blocking) =
+            get_actor_response(collider, 1.0, &ghost_mask, vec2(10.0, 0.0), &[heavy]);
+        assert!(blocking.is_empty());
+    }
+
+    #[cfg(feature = "collision-between-actors")]
+    #[test]
+    fn test_actor_response_push_apart() {
+        let collider = Collider::rect(0.0, 0.0, 10.0, 10.0);
+        let mask = default_body_collision_mask();
+        let other = ActorObstacle {
+            collider: Collider::rect(6.0, 0.0, 10.0, 10.0),
+            collision_layer: ACTOR_COLLISION_LAYER.to_string(),
+            mass: 1.0,
+        };
+
+        let (push_apart, blocking) =
+            get_actor_response(collider, 1.0, &mask, Vec2::ZERO, &[other.clone()]);
+        assert!(blocking.is_empty());
+        assert_eq!(push_apart, vec2(-2.0, 0.0));
+
+        let (push_apart, _) = get_actor_response(collider, 3.0, &mask, Vec2::ZERO, &[other]);
+        assert_eq!(push_apart, vec2(-1.0, 0.0));
     }
-    collisions
 }
@@ -7,9 +7,30 @@ pub fn raycast(
     end: Vec2,
     ignore_barriers: bool,
     ignore_actors: bool,
+) -> Option<Vec2> {
+    let map = storage::get::<Map>();
+
+    let actor_colliders: Vec<Collider> = if ignore_actors {
+        Vec::new()
+    } else {
+        scene::find_nodes_by_type::<Actor>()
+            .filter_map(|actor| actor.body.get_offset_collider())
+            .collect()
+    };
+
+    raycast_map(&map, &actor_colliders, origin, end, ignore_barriers)
+}
+
+// This will return the first point, between `origin` and `end`, that collides with the map, or any
+// of the colliders in `actor_colliders`
+pub fn raycast_map(
+    map: &Map,
+    actor_colliders: &[Collider],
+    origin: Vec2,
+    end: Vec2,
+    ignore_barriers: bool,
 ) -> Option<Vec2> {
     if origin.distance(end) > COLLISION_RESOLUTION {
-        let map = storage::get::<Map>();
         let direction = end.sub(origin).normalize_or_zero();
         let collider = Collider::circle(0.0, 0.0, 1.0);
         let change = direction * COLLISION_RESOLUTION;
@@ -21,13 +42,9 @@ pub fn raycast(
                     return Some(current);
                 }
             }
-            if !ignore_actors {
-                for actor in scene::find_nodes_by_type::<Actor>() {
-                    if let Some(other_collider) = actor.body.get_offset_collider() {
-                        if other_collider.contains(current) {
-                            return Some(current);
-                        }
-                    }
+            for other_collider in actor_colliders {
+                if other_collider.contains(current) {
+                    return Some(current);
                 }
             }
             current += change;
@@ -0,0 +1,600 @@
+use std::cmp::Ordering;
+
+use crate::prelude::*;
+
+// This is the distance a body will be kept away from any surface it collides with, so that
+// sliding along a surface won't register as a new collision with the same, or adjacent, surfaces
+pub const CONTACT_SKIN: f32 = 0.01;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SweepHit {
+    // The fraction of the movement at which the two colliders make contact (0.0 to 1.0)
+    pub time: f32,
+    // The surface normal of `other`, at the point of contact
+    pub normal: Vec2,
+}
+
+// This will sweep `collider` along `movement` and return the time of impact and contact normal,
+// if it hits `other`. If the colliders are already overlapping, a hit with a time of `0.0` and
+// the normal of the shortest separation will be returned, unless `movement` points away from
+// `other`, in which case the collider is free to move out of it.
+pub fn sweep_collider(collider: Collider, movement: Vec2, other: Collider) -> Option<SweepHit> {
+    if let Some((normal, _)) = get_penetration(collider, other) {
+        if movement.dot(normal) < 0.0 {
+            return Some(SweepHit { time: 0.0, normal });
+        }
+        return None;
+    }
+
+    if movement == Vec2::ZERO {
+        return None;
+    }
+
+    match collider {
+        Collider::Rectangle { x, y, w, h } => {
+            let half_size = vec2(w, h) / 2.0;
+            let center = vec2(x, y) + half_size;
+            match other {
+                Collider::Rectangle { x, y, w, h } => {
+                    let min = vec2(x, y) - half_size;
+                    let max = vec2(x + w, y + h) + half_size;
+                    ray_vs_rect(center, movement, min, max)
+                }
+                Collider::Circle { x, y, r } => {
+                    // Sweeping a rectangle against a circle is the same as sweeping the
+                    // circle, in the opposite direction, against the rectangle
+                    ray_vs_rounded_rect(
+                        vec2(x, y),
+                        -movement,
+                        center - half_size,
+                        center + half_size,
+                        r,
+                    )
+                    .map(|hit| SweepHit {
+                        time: hit.time,
+                        normal: -hit.normal,
+                    })
+                }
+                Collider::Edge { x1, y1, x2, y2 } => {
+                    // The Minkowski sum of the rectangle and the edge is the convex hull of the
+                    // rectangle, placed at both ends of the edge
+                    let mut points = Vec::with_capacity(8);
+                    for point in [vec2(x1, y1), vec2(x2, y2)] {
+                        points.push(point - half_size);
+                        points.push(point + vec2(half_size.x, -half_size.y));
+                        points.push(point + half_size);
+                        points.push(point + vec2(-half_size.x, half_size.y));
+                    }
+                    ray_vs_convex_polygon(center, movement, &get_convex_hull(points))
+                }
+            }
+        }
+        Collider::Circle { x, y, r } => {
+            let center = vec2(x, y);
+            match other {
+                Collider::Rectangle { x, y, w, h } => {
+                    ray_vs_rounded_rect(center, movement, vec2(x, y), vec2(x + w, y + h), r)
+                }
+                Collider::Circle {
+                    x: other_x,
+                    y: other_y,
+                    r: other_r,
+                } => ray_vs_circle(center, movement, vec2(other_x, other_y), r + other_r),
+                Collider::Edge { x1, y1, x2, y2 } => {
+                    ray_vs_capsule(center, movement, vec2(x1, y1), vec2(x2, y2), r)
+                }
+            }
+        }
+        Collider::Edge { .. } => match other {
+            Collider::Edge { .. } => None,
+            // Sweeping an edge against another collider is the same as sweeping the other
+            // collider, in the opposite direction, against the edge
+            _ => sweep_collider(other, -movement, collider).map(|hit| SweepHit {
+                time: hit.time,
+                normal: -hit.normal,
+            }),
+        },
+    }
+}
+
+// This returns the normal, pointing away from `other`, and the depth of the shortest separation
+// between two overlapping colliders, or `None` if they don't overlap
+pub fn get_penetration(collider: Collider, other: Collider) -> Option<(Vec2, f32)> {
+    match collider {
+        Collider::Rectangle { x, y, w, h } => match other {
+            Collider::Rectangle {
+                x: other_x,
+                y: other_y,
+                w: other_w,
+                h: other_h,
+            } => {
+                let left = (x + w) - other_x;
+                let right = (other_x + other_w) - x;
+                let top = (y + h) - other_y;
+                let bottom = (other_y + other_h) - y;
+                if left <= 0.0 || right <= 0.0 || top <= 0.0 || bottom <= 0.0 {
+                    return None;
+                }
+
+                let mut res = (vec2(-1.0, 0.0), left);
+                for (normal, depth) in [
+                    (vec2(1.0, 0.0), right),
+                    (vec2(0.0, -1.0), top),
+                    (vec2(0.0, 1.0), bottom),
+                ] {
+                    if depth < res.1 {
+                        res = (normal, depth);
+                    }
+                }
+
+                Some(res)
+            }
+            Collider::Circle { x: cx, y: cy, r } => {
+                get_circle_rect_penetration(vec2(cx, cy), r, vec2(x, y), vec2(x + w, y + h))
+                    .map(|(normal, depth)| (-normal, depth))
+            }
+            Collider::Edge { x1, y1, x2, y2 } => get_rect_edge_penetration(
+                vec2(x, y),
+                vec2(x + w, y + h),
+                vec2(x1, y1),
+                vec2(x2, y2),
+            ),
+        },
+        Collider::Circle { x, y, r } => match other {
+            Collider::Rectangle {
+                x: other_x,
+                y: other_y,
+                w,
+                h,
+            } => get_circle_rect_penetration(
+                vec2(x, y),
+                r,
+                vec2(other_x, other_y),
+                vec2(other_x + w, other_y + h),
+            ),
+            Collider::Circle {
+                x: other_x,
+                y: other_y,
+                r: other_r,
+            } => {
+                let delta = vec2(x - other_x, y - other_y);
+                let distance = delta.length();
+                let depth = r + other_r - distance;
+                if depth <= 0.0 {
+                    return None;
+                }
+
+                let normal = if distance > 0.0 {
+                    delta / distance
+                } else {
+                    vec2(0.0, -1.0)
+                };
+
+                Some((normal, depth))
+            }
+            Collider::Edge { x1, y1, x2, y2 } => {
+                get_circle_edge_penetration(vec2(x, y), r, vec2(x1, y1), vec2(x2, y2))
+            }
+        },
+        Collider::Edge { .. } => match other {
+            Collider::Edge { .. } => None,
+            _ => get_penetration(other, collider).map(|(normal, depth)| (-normal, depth)),
+        },
+    }
+}
+
+fn get_circle_rect_penetration(center: Vec2, r: f32, min: Vec2, max: Vec2) -> Option<(Vec2, f32)> {
+    let closest = center.clamp(min, max);
+    let delta = center - closest;
+    let distance = delta.length();
+    if distance > 0.0 {
+        if distance >= r {
+            return None;
+        }
+        return Some((delta / distance, r - distance));
+    }
+
+    // The center is inside the rectangle, so we push out along the closest edge
+    let mut res = (vec2(-1.0, 0.0), center.x - min.x + r);
+    for (normal, depth) in [
+        (vec2(1.0, 0.0), max.x - center.x + r),
+        (vec2(0.0, -1.0), center.y - min.y + r),
+        (vec2(0.0, 1.0), max.y - center.y + r),
+    ] {
+        if depth < res.1 {
+            res = (normal, depth);
+        }
+    }
+
+    Some(res)
+}
+
+// Separating axis test between a rectangle and an edge
+fn get_rect_edge_penetration(min: Vec2, max: Vec2, start: Vec2, end: Vec2) -> Option<(Vec2, f32)> {
+    let center = (min + max) / 2.0;
+    let half_size = (max - min) / 2.0;
+
+    let mut axes = vec![vec2(1.0, 0.0), vec2(0.0, 1.0)];
+    let edge_normal = get_edge_normal(start, end);
+    if edge_normal != Vec2::ZERO {
+        axes.push(edge_normal);
+    }
+
+    let mut res: Option<(Vec2, f32)> = None;
+    for axis in axes {
+        let rect_center = axis.dot(center);
+        let rect_extent = axis.x.abs() * half_size.x + axis.y.abs() * half_size.y;
+        let edge_min = axis.dot(start).min(axis.dot(end));
+        let edge_max = axis.dot(start).max(axis.dot(end));
+
+        let positive_depth = edge_max - (rect_center - rect_extent);
+        let negative_depth = (rect_center + rect_extent) - edge_min;
+        if positive_depth <= 0.0 || negative_depth <= 0.0 {
+            return None;
+        }
+
+        let separation = if positive_depth < negative_depth {
+            (axis, positive_depth)
+        } else {
+            (-axis, negative_depth)
+        };
+
+        if res.map_or(true, |(_, depth)| separation.1 < depth) {
+            res = Some(separation);
+        }
+    }
+
+    res
+}
+
+fn get_circle_edge_penetration(
+    center: Vec2,
+    r: f32,
+    start: Vec2,
+    end: Vec2,
+) -> Option<(Vec2, f32)> {
+    let delta = center - get_closest_point_on_edge(center, start, end);
+    let distance = delta.length();
+    if distance >= r {
+        return None;
+    }
+
+    let normal = if distance > 0.0 {
+        delta / distance
+    } else {
+        let edge_normal = get_edge_normal(start, end);
+        if edge_normal != Vec2::ZERO {
+            edge_normal
+        } else {
+            vec2(0.0, -1.0)
+        }
+    };
+
+    Some((normal, r - distance))
+}
+
+fn get_closest_point_on_edge(point: Vec2, start: Vec2, end: Vec2) -> Vec2 {
+    let direction = end - start;
+    let length_squared = direction.dot(direction);
+    if length_squared == 0.0 {
+        return start;
+    }
+
+    let t = ((point - start).dot(direction) / length_squared).clamp(0.0, 1.0);
+    start + direction * t
+}
+
+// This returns one of the two unit normals of an edge, or zero, if the edge has no length
+fn get_edge_normal(start: Vec2, end: Vec2) -> Vec2 {
+    let direction = end - start;
+    vec2(-direction.y, direction.x).normalize_or_zero()
+}
+
+// This returns the convex hull of `points`, using the monotone chain algorithm
+fn get_convex_hull(mut points: Vec<Vec2>) -> Vec<Vec2> {
+    points.sort_by(|a, b| {
+        a.x.partial_cmp(&b.x)
+            .unwrap_or(Ordering::Equal)
+            .then(a.y.partial_cmp(&b.y).unwrap_or(Ordering::Equal))
+    });
+
+    let cross = |a: Vec2, b: Vec2, c: Vec2| (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x);
+
+    let mut hull: Vec<Vec2> = Vec::with_capacity(points.len() + 1);
+    for is_upper in [false, true] {
+        let start = hull.len();
+        let iter: Box<dyn Iterator<Item = &Vec2>> = if is_upper {
+            Box::new(points.iter().rev())
+        } else {
+            Box::new(points.iter())
+        };
+
+        for point in iter {
+            while hull.len() >= start + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], *point) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(*point);
+        }
+        hull.pop();
+    }
+
+    hull
+}
+
+// Slab test of a ray against an axis aligned rectangle. A ray that only grazes the rectangle,
+// along one of its edges, will not register as a hit.
+fn ray_vs_rect(origin: Vec2, direction: Vec2, min: Vec2, max: Vec2) -> Option<SweepHit> {
+    let mut t_near = f32::NEG_INFINITY;
+    let mut t_far = f32::INFINITY;
+    let mut normal = Vec2::ZERO;
+
+    for (origin, direction, min, max, axis) in [
+        (origin.x, direction.x, min.x, max.x, vec2(1.0, 0.0)),
+        (origin.y, direction.y, min.y, max.y, vec2(0.0, 1.0)),
+    ] {
+        if direction == 0.0 {
+            if origin <= min || origin >= max {
+                return None;
+            }
+        } else {
+            let t1 = (min - origin) / direction;
+            let t2 = (max - origin) / direction;
+            let (t1, t2) = if t1 > t2 { (t2, t1) } else { (t1, t2) };
+            if t1 > t_near {
+                t_near = t1;
+                normal = if direction > 0.0 { -axis } else { axis };
+            }
+            if t2 < t_far {
+                t_far = t2;
+            }
+        }
+    }
+
+    if t_near >= t_far || t_far <= 0.0 || !(0.0..=1.0).contains(&t_near) {
+        return None;
+    }
+
+    Some(SweepHit {
+        time: t_near,
+        normal,
+    })
+}
+
+fn ray_vs_circle(origin: Vec2, direction: Vec2, center: Vec2, r: f32) -> Option<SweepHit> {
+    let a = direction.dot(direction);
+    if a == 0.0 {
+        return None;
+    }
+
+    let offset = origin - center;
+    let b = offset.dot(direction);
+    let c = offset.dot(offset) - r * r;
+    let discriminant = b * b - a * c;
+    if b >= 0.0 || discriminant <= 0.0 {
+        return None;
+    }
+
+    let time = (-b - discriminant.sqrt()) / a;
+    if !(0.0..=1.0).contains(&time) {
+        return None;
+    }
+
+    let normal = (origin + direction * time - center).normalize_or_zero();
+    Some(SweepHit { time, normal })
+}
+
+// Cyrus-Beck clipping of a ray against a convex polygon. As with `ray_vs_rect`, a ray that only
+// grazes the polygon, along one of its edges, will not register as a hit.
+fn ray_vs_convex_polygon(origin: Vec2, direction: Vec2, vertices: &[Vec2]) -> Option<SweepHit> {
+    if vertices.len() < 3 {
+        return None;
+    }
+
+    let centroid = vertices
+        .iter()
+        .fold(Vec2::ZERO, |sum, vertex| sum + *vertex)
+        / vertices.len() as f32;
+
+    let mut t_near = f32::NEG_INFINITY;
+    let mut t_far = f32::INFINITY;
+    let mut normal = Vec2::ZERO;
+
+    for (i, start) in vertices.iter().enumerate() {
+        let end = vertices[(i + 1) % vertices.len()];
+        let mut edge_normal = get_edge_normal(*start, end);
+        if edge_normal == Vec2::ZERO {
+            continue;
+        }
+
+        if edge_normal.dot(*start - centroid) < 0.0 {
+            edge_normal = -edge_normal;
+        }
+
+        let distance = edge_normal.dot(origin - *start);
+        let speed = edge_normal.dot(direction);
+        if speed == 0.0 {
+            if distance >= 0.0 {
+                return None;
+            }
+        } else {
+            let t = -distance / speed;
+            if speed < 0.0 {
+                if t > t_near {
+                    t_near = t;
+                    normal = edge_normal;
+                }
+            } else if t < t_far {
+                t_far = t;
+            }
+        }
+    }
+
+    if t_near >= t_far || t_far <= 0.0 || !(0.0..=1.0).contains(&t_near) {
+        return None;
+    }
+
+    Some(SweepHit {
+        time: t_near,
+        normal,
+    })
+}
+
+// This tests a ray against the Minkowski sum of an edge and a circle of radius `r`, which is a
+// capsule, made up of a circle at each end of the edge and a rectangle along it
+fn ray_vs_capsule(
+    origin: Vec2,
+    direction: Vec2,
+    start: Vec2,
+    end: Vec2,
+    r: f32,
+) -> Option<SweepHit> {
+    let mut hits = vec![
+        ray_vs_circle(origin, direction, start, r),
+        ray_vs_circle(origin, direction, end, r),
+    ];
+
+    let edge_normal = get_edge_normal(start, end);
+    if edge_normal != Vec2::ZERO {
+        let offset = edge_normal * r;
+        let vertices = [start + offset, end + offset, end - offset, start - offset];
+        hits.push(ray_vs_convex_polygon(origin, direction, &vertices));
+    }
+
+    hits.into_iter()
+        .flatten()
+        .fold(None, |first: Option<SweepHit>, hit| match first {
+            Some(first) if first.time <= hit.time => Some(first),
+            _ => Some(hit),
+        })
+}
+
+// This tests a ray against a rectangle that is expanded by `r` and has rounded corners, which is
+// the Minkowski sum of a rectangle and a circle of radius `r`
+fn ray_vs_rounded_rect(
+    origin: Vec2,
+    direction: Vec2,
+    min: Vec2,
+    max: Vec2,
+    r: f32,
+) -> Option<SweepHit> {
+    let hit = ray_vs_rect(
+        origin,
+        direction,
+        min - Vec2::splat(r),
+        max + Vec2::splat(r),
+    )?;
+    let point = origin + direction * hit.time;
+
+    let corner_x = if point.x < min.x {
+        Some(min.x)
+    } else if point.x > max.x {
+        Some(max.x)
+    } else {
+        None
+    };
+
+    let corner_y = if point.y < min.y {
+        Some(min.y)
+    } else if point.y > max.y {
+        Some(max.y)
+    } else {
+        None
+    };
+
+    if let (Some(x), Some(y)) = (corner_x, corner_y) {
+        return ray_vs_circle(origin, direction, vec2(x, y), r);
+    }
+
+    Some(hit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sweep_rect_hits_rect() {
+        let collider = Collider::rect(0.0, 0.0, 10.0, 10.0);
+        let other = Collider::rect(20.0, 0.0, 10.0, 10.0);
+        let hit = sweep_collider(collider, vec2(20.0, 0.0), other).unwrap();
+        assert_eq!(hit.time, 0.5);
+        assert_eq!(hit.normal, vec2(-1.0, 0.0));
+    }
+
+    #[test]
+    fn test_sweep_rect_grazing_rect() {
+        let collider = Collider::rect(0.0, 0.0, 10.0, 10.0);
+        let other = Collider::rect(0.0, 10.0, 10.0, 10.0);
+        assert_eq!(sweep_collider(collider, vec2(20.0, 0.0), other), None);
+    }
+
+    #[test]
+    fn test_sweep_circle_hits_rect_corner() {
+        let collider = Collider::circle(0.0, 0.0, 5.0);
+        let other = Collider::rect(10.0, 10.0, 10.0, 10.0);
+        let hit = sweep_collider(collider, vec2(20.0, 20.0), other).unwrap();
+        let expected = vec2(1.0, 1.0).normalize();
+        assert!((hit.normal + expected).length() < 0.0001);
+    }
+
+    #[test]
+    fn test_sweep_overlapping_moving_away() {
+        let collider = Collider::circle(0.0, 0.0, 5.0);
+        let other = Collider::circle(6.0, 0.0, 5.0);
+        assert_eq!(sweep_collider(collider, vec2(-1.0, 0.0), other), None);
+        let hit = sweep_collider(collider, vec2(1.0, 0.0), other).unwrap();
+        assert_eq!(hit.time, 0.0);
+        assert_eq!(hit.normal, vec2(-1.0, 0.0));
+    }
+
+    #[test]
+    fn test_sweep_against_diagonal_edge() {
+        let edge = Collider::edge(20.0, 0.0, 0.0, 20.0);
+        let expected = vec2(-1.0, -1.0).normalize();
+
+        let collider = Collider::rect(-10.0, -10.0, 10.0, 10.0);
+        let hit = sweep_collider(collider, vec2(20.0, 20.0), edge).unwrap();
+        assert!((hit.time - 0.5).abs() < 0.0001);
+        assert!((hit.normal - expected).length() < 0.0001);
+
+        let collider = Collider::circle(0.0, 0.0, 2.0);
+        let hit = sweep_collider(collider, vec2(10.0, 10.0), edge).unwrap();
+        assert!((hit.normal - expected).length() < 0.0001);
+        let contact = vec2(10.0, 10.0) * hit.time;
+        assert!((contact.x + contact.y - 20.0 + 2.0 * 2.0_f32.sqrt()).abs() < 0.0001);
+
+        // Past the end of the edge
+        let collider = Collider::circle(30.0, 30.0, 2.0);
+        assert_eq!(sweep_collider(collider, vec2(10.0, 0.0), edge), None);
+
+        // An edge sweeping into a rectangle
+        let rect = Collider::rect(30.0, -10.0, 10.0, 20.0);
+        let hit = sweep_collider(edge, vec2(20.0, 0.0), rect).unwrap();
+        assert!((hit.time - 0.5).abs() < 0.0001);
+        assert_eq!(hit.normal, vec2(-1.0, 0.0));
+    }
+
+    #[test]
+    fn test_penetration_with_edge() {
+        let edge = Collider::edge(0.0, 0.0, 20.0, 20.0);
+        let expected = vec2(1.0, -1.0).normalize();
+
+        let collider = Collider::circle(11.0, 9.0, 2.0);
+        let (normal, depth) = get_penetration(collider, edge).unwrap();
+        assert!((normal - expected).length() < 0.0001);
+        assert!((depth - (2.0 - 2.0_f32.sqrt())).abs() < 0.0001);
+
+        let collider = Collider::rect(9.0, 6.0, 4.0, 4.0);
+        let (normal, _) = get_penetration(collider, edge).unwrap();
+        assert!((normal - expected).length() < 0.0001);
+        let (normal, _) = get_penetration(edge, collider).unwrap();
+        assert!((normal + expected).length() < 0.0001);
+
+        assert_eq!(
+            get_penetration(Collider::rect(14.0, 2.0, 4.0, 4.0), edge),
+            None
+        );
+    }
+}
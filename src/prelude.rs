@@ -45,7 +45,9 @@ pub use mode::{self, Automaton, Mode};
 pub(crate) use crate::{gui::draw_gui, modules::load_modules, scene::load_scene};
 
 pub use crate::{
-    ability::{Ability, AbilityDelivery, AbilityParams, DamageType, Effect},
+    ability::{
+        Ability, AbilityDelivery, AbilityEffect, AbilityParams, DamageType, Effect, EffectTarget,
+    },
     audio::{get_volume, load_sound, load_sound_from_bytes, play_sound, Sound, VolumeCategory},
     behavior_sets::{
         self, get_behavior_set, register_behavior_set, ActorBehaviorConstructor,
@@ -57,7 +59,14 @@ pub use crate::{
         Character,
     },
     config::Config,
-    dialogue::{Dialogue, DialogueAction, DialogueRequirement},
+    damage::{
+        get_evasion_chance, roll_critical_hit, roll_evasion, DamageResult,
+        DEFAULT_CRITICAL_MULTIPLIER, MAX_EVASION_CHANCE, MAX_RESISTANCE, MIN_RESISTANCE,
+    },
+    dialogue::{
+        import_dialogue_script, Dialogue, DialogueAction, DialogueCheck, DialogueContext,
+        DialogueRequirement, DialogueScriptFormat,
+    },
     error::{Error, ErrorKind, Result},
     events::{dispatch_event, get_next_event, handle_event, handle_queued_events, Event},
     file_io::{load_file, load_file_to_string},
@@ -65,8 +74,18 @@ pub use crate::{
     gui::{self, show_main_menu, GuiSkins, GuiState, MenuBuilder, WindowBuilder},
     helpers::{self, generate_id, get_timestamp, remove_filename, sort_by_distance},
     input::{self, apply_input, get_mouse_in_world_space, get_mouse_position},
-    inventory::{EquipmentSlot, EquippedItems, Inventory, InventoryEntry, InventoryParams},
+    interpolation::{interpolate, TextContext},
+    inventory::{
+        AffixKind, AffixTables, EquipmentSlot, EquipmentSlotParams, EquippedItems, Inventory,
+        InventoryCapacity, InventoryEntry, InventoryParams, ItemAffix, RarityParams,
+    },
     json,
+    leveling::{AbilityUnlock, ClassPerk, ExperienceCurve, STARTING_LEVEL},
+    localization::{tr, tr_args, Locale, LocaleParams, Localization},
+    loot::{
+        drop_loot_table, drop_loot_table_with_rng, roll_loot_table, roll_loot_table_with_rng, Loot,
+        LootCondition, LootContext, LootEntry, LootEntryKind, LootRange, LootRng, LootTableParams,
+    },
     map::{
         Map, MapLayer, MapLayerKind, MapObject, MapProperty, MapTile, MapTileset, NavigationPath,
         ObjectLayerKind,
@@ -78,6 +97,7 @@ pub use crate::{
             Actor, ActorAggression, ActorBehavior, ActorBehaviorFamily, ActorBehaviorParams,
             ActorController, ActorControllerKind, ActorParams, ActorStats,
         },
+        container::{Container, ContainerParams, ContainerState},
         draw_buffer::{Bounds, BufferedDraw, DrawBuffer},
         item::{Item, ItemKind, ItemParams},
         light_source::LightSource,
@@ -85,14 +105,25 @@ pub use crate::{
         CameraController, ContinuousBeams, Credits, GameState, Hud, MapRenderer, PostProcessing,
     },
     noise_level::NoiseLevel,
-    physics::{raycast, Collider, CollisionKind, PhysicsBody},
+    physics::{
+        can_hit_actor, default_body_collision_mask, default_projectile_collision_mask,
+        get_friendly_collision_layer, is_layer_in_mask, raycast, raycast_map, ActorObstacle,
+        Collider, ColliderParams, Collision, CollisionKind, PhysicsBody, ACTOR_COLLISION_LAYER,
+        BARRIER_COLLISION_LAYER, DEFAULT_MASS, SOLID_COLLISION_LAYER,
+    },
     player::{get_player_actor, LocalPlayer},
     render::{
         color_from_hex_string, draw_progress_bar, draw_text, draw_texture, use_default_material,
         use_material, HorizontalAlignment, Material, Sprite, SpriteAnimationParams,
         SpriteAnimationPlayer, Texture, VerticalAlignment, Viewport, COLOR_NONE,
     },
-    resources::Resources,
+    resources::{CharacterClass, Resources},
     scene::{DrawStage, SceneBuilder, SceneBuilderFunc},
+    stats::{
+        AttributeParams, DerivedStatParams, StatExpression, StatFormulas, StatFunction,
+        StatModifier, StatModifierKind, StatModifiers, StatOperator,
+    },
+    status_effects::{StatusEffect, StatusEffectKind, StatusEffectParams, StatusEffectStacking},
+    vendor::{BuybackItem, Vendor, VendorParams, VendorRestock},
     versions::{check_version, get_toolkit_version, to_int_version},
 };
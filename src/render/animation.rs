@@ -102,12 +102,29 @@ impl SpriteAnimationPlayer {
     }
 
     pub fn draw(&mut self, position: Vec2, rotation: f32) {
+        self.draw_with_color(position, rotation, None);
+    }
+
+    pub fn draw_with_color(&mut self, position: Vec2, rotation: f32, color: Option<Color>) {
+        let texture_id = self.texture_id.clone();
+        self.draw_layer(&texture_id, position, rotation, color);
+    }
+
+    // This draws the current frame of the animation from another texture, with the same layout as
+    // the player's own texture, like the equipment that is drawn over an actor
+    pub fn draw_layer(
+        &mut self,
+        texture_id: &str,
+        position: Vec2,
+        rotation: f32,
+        color: Option<Color>,
+    ) {
         let resources = storage::get::<Resources>();
-        let texture = resources.textures.get(&self.texture_id).unwrap();
+        let texture = resources.textures.get(texture_id).unwrap();
         draw_texture(
             texture,
             position + self.offset,
-            None,
+            color,
             DrawTextureParams {
                 source: Some(self.animated_sprite.frame().source_rect),
                 dest_size: Some(self.animated_sprite.frame().dest_size),
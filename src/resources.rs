@@ -1,4 +1,5 @@
 use crate::file_io::deserialize_file;
+use crate::inventory::{default_equipment_slots, extend_equipment_slots};
 use crate::prelude::*;
 
 use crate::macroquad::texture::{load_texture, Texture2D};
@@ -10,6 +11,41 @@ pub struct CharacterClass {
     pub prototype_id: String,
     pub name: String,
     pub description: String,
+    #[serde(default)]
+    pub experience_curve: ExperienceCurve,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_level: Option<u32>,
+    #[serde(default = "CharacterClass::default_attribute_points_per_level")]
+    pub attribute_points_per_level: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ability_unlocks: Vec<AbilityUnlock>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub perks: Vec<ClassPerk>,
+}
+
+impl CharacterClass {
+    pub const DEFAULT_ATTRIBUTE_POINTS_PER_LEVEL: u32 = 2;
+
+    pub fn default_attribute_points_per_level() -> u32 {
+        Self::DEFAULT_ATTRIBUTE_POINTS_PER_LEVEL
+    }
+
+    pub fn get_level(&self, experience: u32) -> u32 {
+        self.experience_curve.get_level(experience, self.max_level)
+    }
+
+    // Returns the total experience required for the next level, or `None` if the max level has
+    // been reached
+    pub fn get_next_level_threshold(&self, level: u32) -> Option<u32> {
+        if self
+            .max_level
+            .map(|max_level| level >= max_level)
+            .unwrap_or(false)
+        {
+            return None;
+        }
+        self.experience_curve.get_threshold(level + 1)
+    }
 }
 
 fn default_filter_mode() -> FilterMode {
@@ -100,6 +136,12 @@ pub struct Resources {
     pub missions: HashMap<String, MissionParams>,
     pub dialogue: HashMap<String, Dialogue>,
     pub chapters: Vec<Chapter>,
+    pub stat_formulas: StatFormulas,
+    pub equipment_slots: Vec<EquipmentSlotParams>,
+    pub affixes: AffixTables,
+    pub loot_tables: HashMap<String, LootTableParams>,
+    pub containers: HashMap<String, ContainerParams>,
+    pub vendors: HashMap<String, VendorParams>,
     pub materials: HashMap<String, Material>,
     pub textures: HashMap<String, Texture>,
     pub images: HashMap<String, Image>,
@@ -116,6 +158,12 @@ impl Resources {
     const DIALOGUE_FILE_NAME: &'static str = "dialogue.json";
     const ABILITIES_FILE_NAME: &'static str = "abilities.json";
     const SCENARIO_FILE_NAME: &'static str = "scenario.json";
+    const STATS_FILE_NAME: &'static str = "stats.json";
+    const EQUIPMENT_SLOTS_FILE_NAME: &'static str = "equipment_slots.json";
+    const AFFIXES_FILE_NAME: &'static str = "affixes.json";
+    const LOOT_TABLES_FILE_NAME: &'static str = "loot_tables.json";
+    const CONTAINERS_FILE_NAME: &'static str = "containers.json";
+    const VENDORS_FILE_NAME: &'static str = "vendors.json";
 
     const MATERIALS_FILE_NAME: &'static str = "materials.json";
     const TEXTURES_FILE_NAME: &'static str = "textures.json";
@@ -201,6 +249,79 @@ impl Resources {
             chapters.push(chapter);
         }
 
+        // The stats file is optional and any formulas it defines will replace the defaults
+        #[cfg(debug_assertions)]
+        println!("Resources: Loading stats");
+        let stats_path = data_path.join(Self::STATS_FILE_NAME);
+        let mut stat_formulas = StatFormulas::default();
+        if let Ok(bytes) = load_file(&stats_path).await {
+            let params: StatFormulas = serde_json::from_slice(&bytes)
+                .map_err(|err| json::Error::new(&stats_path.to_string_helper(), err))?;
+            stat_formulas.extend(params);
+        }
+        stat_formulas.validate()?;
+
+        // The equipment slots file is optional and its slots will be added to the defaults
+        #[cfg(debug_assertions)]
+        println!("Resources: Loading equipment slots");
+        let equipment_slots_path = data_path.join(Self::EQUIPMENT_SLOTS_FILE_NAME);
+        let mut equipment_slots = default_equipment_slots();
+        if let Ok(bytes) = load_file(&equipment_slots_path).await {
+            let params: Vec<EquipmentSlotParams> = serde_json::from_slice(&bytes)
+                .map_err(|err| json::Error::new(&equipment_slots_path.to_string_helper(), err))?;
+            extend_equipment_slots(&mut equipment_slots, params);
+        }
+
+        // The affixes file is optional and, without it, items will be generated without rarity
+        #[cfg(debug_assertions)]
+        println!("Resources: Loading affixes");
+        let affixes_path = data_path.join(Self::AFFIXES_FILE_NAME);
+        let mut affixes = AffixTables::default();
+        if let Ok(bytes) = load_file(&affixes_path).await {
+            let params: AffixTables = serde_json::from_slice(&bytes)
+                .map_err(|err| json::Error::new(&affixes_path.to_string_helper(), err))?;
+            affixes.extend(params);
+        }
+
+        // The loot tables file is optional
+        #[cfg(debug_assertions)]
+        println!("Resources: Loading loot tables");
+        let loot_tables_path = data_path.join(Self::LOOT_TABLES_FILE_NAME);
+        let mut loot_tables = HashMap::new();
+        if let Ok(bytes) = load_file(&loot_tables_path).await {
+            let params: Vec<LootTableParams> = serde_json::from_slice(&bytes)
+                .map_err(|err| json::Error::new(&loot_tables_path.to_string_helper(), err))?;
+            for table in params {
+                loot_tables.insert(table.id.clone(), table);
+            }
+        }
+
+        // The containers file is optional
+        #[cfg(debug_assertions)]
+        println!("Resources: Loading containers");
+        let containers_path = data_path.join(Self::CONTAINERS_FILE_NAME);
+        let mut containers = HashMap::new();
+        if let Ok(bytes) = load_file(&containers_path).await {
+            let params: Vec<ContainerParams> = serde_json::from_slice(&bytes)
+                .map_err(|err| json::Error::new(&containers_path.to_string_helper(), err))?;
+            for container in params {
+                containers.insert(container.id.clone(), container);
+            }
+        }
+
+        // The vendors file is optional
+        #[cfg(debug_assertions)]
+        println!("Resources: Loading vendors");
+        let vendors_path = data_path.join(Self::VENDORS_FILE_NAME);
+        let mut vendors = HashMap::new();
+        if let Ok(bytes) = load_file(&vendors_path).await {
+            let params: Vec<VendorParams> = serde_json::from_slice(&bytes)
+                .map_err(|err| json::Error::new(&vendors_path.to_string_helper(), err))?;
+            for vendor in params {
+                vendors.insert(vendor.id.clone(), vendor);
+            }
+        }
+
         let materials_file_path = assets_path.join(Self::MATERIALS_FILE_NAME);
         let material_assets: Vec<MaterialAssetParams> = deserialize_file(&materials_file_path).await?;
 
@@ -315,6 +436,12 @@ impl Resources {
             missions,
             dialogue,
             chapters,
+            stat_formulas,
+            equipment_slots,
+            affixes,
+            loot_tables,
+            containers,
+            vendors,
             materials,
             textures,
             images,
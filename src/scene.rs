@@ -140,6 +140,7 @@ impl SceneBuilder {
         }
 
         DrawBuffer::<Item>::add_node();
+        DrawBuffer::<Container>::add_node();
         DrawBuffer::<Credits>::add_node();
 
         for constructor in self.draw_stages.get(&DrawStage::Projectiles).unwrap() {
@@ -167,27 +168,33 @@ impl SceneBuilder {
 
         Hud::add_node();
 
+        let loot_context = LootContext::from_character(&character);
         for (_, layer) in &map.layers {
             if let MapLayerKind::ObjectLayer(kind) = layer.kind.clone() {
                 match kind {
                     ObjectLayerKind::Items => {
                         for map_object in &layer.objects {
-                            spawn_item(map_object);
+                            spawn_item(map_object, map_id, &loot_context);
                         }
                     }
                     ObjectLayerKind::SpawnPoints => {
                         for map_object in &layer.objects {
                             if map_object.name != Map::PLAYER_SPAWN_POINT_NAME {
-                                spawn_actor(game_state, map_object);
+                                spawn_actor(game_state, map_object, &loot_context);
                             }
                         }
                     }
+                    ObjectLayerKind::Containers => {
+                        for map_object in &layer.objects {
+                            spawn_container(map_object, map_id, &character);
+                        }
+                    }
                     ObjectLayerKind::LightSources => {
                         for map_object in &layer.objects {
                             spawn_light_source(map_object);
                         }
                     }
-                    ObjectLayerKind::None => {}
+                    ObjectLayerKind::Colliders | ObjectLayerKind::None => {}
                 }
             }
         }
@@ -202,7 +209,23 @@ impl SceneBuilder {
     }
 }
 
-fn spawn_item(map_object: &MapObject) {
+// Item objects can have a `loot_table` property instead of a `prototype_id`, in which case the
+// loot table will be rolled when the map is loaded. The roll is seeded from the map and the item,
+// so that the same loot is dropped every time the map is loaded and it can't be farmed.
+fn spawn_item(map_object: &MapObject, map_id: &str, loot_context: &LootContext) {
+    if let Some(MapProperty::String { value }) = map_object.properties.get("loot_table") {
+        let id = match map_object.properties.get("instance_id") {
+            Some(MapProperty::String { value }) => value.clone(),
+            _ => format!(
+                "{}_{}_{}",
+                value, map_object.position.x, map_object.position.y
+            ),
+        };
+        let mut rng = LootRng::from_id(&format!("{}_{}", map_id, id));
+        drop_loot_table_with_rng(value, loot_context, map_object.position, &mut rng);
+        return;
+    }
+
     if let Some(prop) = map_object.properties.get("prototype_id").cloned() {
         if let MapProperty::String {
             value: prototype_id,
@@ -224,8 +247,12 @@ fn spawn_item(map_object: &MapObject) {
                     }
                 }
 
+                let mut params = resources.affixes.generate(&params);
+                if let Some(instance_id) = instance_id {
+                    params.id = instance_id;
+                }
+
                 Item::add_node(ItemParams {
-                    id: instance_id.unwrap_or(generate_id()),
                     position: Some(map_object.position),
                     ..params
                 });
@@ -234,7 +261,7 @@ fn spawn_item(map_object: &MapObject) {
     }
 }
 
-fn spawn_actor(game_state: Handle<GameState>, map_object: &MapObject) {
+fn spawn_actor(game_state: Handle<GameState>, map_object: &MapObject, loot_context: &LootContext) {
     if let Some(prop) = map_object.properties.get("prototype_id") {
         if let MapProperty::String {
             value: prototype_id,
@@ -249,6 +276,22 @@ fn spawn_actor(game_state: Handle<GameState>, map_object: &MapObject) {
 
             let resources = storage::get::<Resources>();
             let params = resources.actors.get(prototype_id).cloned().unwrap();
+
+            // Vendors that restock on every visit are restocked when their map is loaded
+            if let Some(vendor_params) = params
+                .vendor_id
+                .as_ref()
+                .and_then(|vendor_id| resources.vendors.get(vendor_id))
+            {
+                if vendor_params.restock == VendorRestock::OnVisit {
+                    let mut game_state = scene::get_node(game_state);
+                    game_state.vendors.insert(
+                        vendor_params.id.clone(),
+                        Vendor::new(vendor_params, loot_context),
+                    );
+                }
+            }
+
             let mut actor = Actor::new(
                 game_state,
                 ActorControllerKind::Computer,
@@ -267,6 +310,51 @@ fn spawn_actor(game_state: Handle<GameState>, map_object: &MapObject) {
     }
 }
 
+// Containers that have no `instance_id` are given an id from their prototype and position, so that
+// their saved contents can be found when the map is loaded again
+fn spawn_container(map_object: &MapObject, map_id: &str, character: &Character) {
+    if let Some(MapProperty::String {
+        value: prototype_id,
+    }) = map_object.properties.get("prototype_id")
+    {
+        let resources = storage::get::<Resources>();
+        let mut params = resources
+            .containers
+            .get(prototype_id)
+            .cloned()
+            .unwrap_or_else(|| panic!("Unable to find container with id '{}'", prototype_id));
+
+        if let Some(MapProperty::String { value }) = map_object.properties.get("loot_table") {
+            params.loot_table_id = Some(value.clone());
+        }
+        if let Some(MapProperty::String { value }) = map_object.properties.get("key") {
+            params.key_id = Some(value.clone());
+        }
+        if let Some(MapProperty::Bool { value }) = map_object.properties.get("is_locked") {
+            params.is_locked = *value;
+        }
+
+        let id = match map_object.properties.get("instance_id") {
+            Some(MapProperty::String { value }) => value.clone(),
+            _ => format!(
+                "{}_{}_{}",
+                prototype_id, map_object.position.x, map_object.position.y
+            ),
+        };
+
+        let mut container = Container::new(&id, map_object.position, params);
+        let state = character
+            .containers
+            .get(map_id)
+            .and_then(|states| states.iter().find(|state| state.id == id));
+        if let Some(state) = state {
+            container.apply_state(state);
+        }
+
+        Container::add_node(container);
+    }
+}
+
 fn spawn_light_source(map_object: &MapObject) {
     let size = map_object.size.unwrap_or(LightSource::DEFAULT_SIZE);
 
@@ -0,0 +1,848 @@
+use std::{convert::TryFrom, fmt, iter::Peekable, str::Chars};
+
+use crate::prelude::*;
+
+// The primary attributes that every actor has. Games can add more in `stats.json`.
+pub const ATTRIBUTES: &[&str] = &[
+    "strength",
+    "dexterity",
+    "constitution",
+    "intelligence",
+    "willpower",
+    "perception",
+    "charisma",
+];
+
+// The derived stats that are used by the toolkit itself. Games can add more in `stats.json`.
+pub const DERIVED_STATS: &[&str] = &[
+    "max_health",
+    "max_stamina",
+    "max_energy",
+    "health_regen",
+    "stamina_regen",
+    "energy_regen",
+    "move_speed",
+    "view_distance",
+    "carry_capacity",
+    "evasion_chance",
+    "critical_chance",
+    "critical_multiplier",
+];
+
+// These are the formulas that are used for any of the toolkit's derived stats that are not
+// defined by a game
+const DEFAULT_FORMULAS: &[(&str, &str)] = &[
+    (
+        "max_health",
+        "(constitution + floor(strength / 4) + floor(willpower / 4)) * 100",
+    ),
+    (
+        "max_stamina",
+        "(constitution + floor(dexterity / 4) + floor(willpower / 4)) * 100",
+    ),
+    ("max_energy", "(willpower + floor(constitution / 2)) * 100"),
+    (
+        "health_regen",
+        "(constitution + floor(strength / 4) + floor(willpower / 4)) * 0.1",
+    ),
+    (
+        "stamina_regen",
+        "(constitution + floor(dexterity / 4) + floor(willpower / 4)) * 8",
+    ),
+    (
+        "energy_regen",
+        "(willpower + floor(constitution / 2)) * 0.5",
+    ),
+    (
+        "move_speed",
+        "(dexterity + floor(strength / 4) + floor(willpower / 4)) * 0.1",
+    ),
+    (
+        "view_distance",
+        "(perception + floor(intelligence / 2)) * 20",
+    ),
+    (
+        "carry_capacity",
+        "(strength + floor(constitution / 4) + floor(willpower / 4)) * 50",
+    ),
+    ("evasion_chance", "dexterity * 0.5"),
+    ("critical_chance", "perception * 0.5"),
+    ("critical_multiplier", "1.5"),
+];
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StatOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl StatOperator {
+    fn as_str(&self) -> &'static str {
+        match self {
+            StatOperator::Add => "+",
+            StatOperator::Subtract => "-",
+            StatOperator::Multiply => "*",
+            StatOperator::Divide => "/",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StatFunction {
+    Min,
+    Max,
+    Floor,
+    Ceil,
+    Round,
+    Abs,
+}
+
+impl StatFunction {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "min" => Some(StatFunction::Min),
+            "max" => Some(StatFunction::Max),
+            "floor" => Some(StatFunction::Floor),
+            "ceil" => Some(StatFunction::Ceil),
+            "round" => Some(StatFunction::Round),
+            "abs" => Some(StatFunction::Abs),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            StatFunction::Min => "min",
+            StatFunction::Max => "max",
+            StatFunction::Floor => "floor",
+            StatFunction::Ceil => "ceil",
+            StatFunction::Round => "round",
+            StatFunction::Abs => "abs",
+        }
+    }
+
+    // `min` and `max` take one or more arguments, the rest take exactly one
+    fn is_valid_arg_cnt(&self, cnt: usize) -> bool {
+        match self {
+            StatFunction::Min | StatFunction::Max => cnt > 0,
+            _ => cnt == 1,
+        }
+    }
+
+    fn apply(&self, args: &[f32]) -> f32 {
+        match self {
+            StatFunction::Min => args.iter().cloned().fold(f32::INFINITY, f32::min),
+            StatFunction::Max => args.iter().cloned().fold(f32::NEG_INFINITY, f32::max),
+            StatFunction::Floor => args[0].floor(),
+            StatFunction::Ceil => args[0].ceil(),
+            StatFunction::Round => args[0].round(),
+            StatFunction::Abs => args[0].abs(),
+        }
+    }
+}
+
+// A formula for a derived stat, parsed from a string like `(constitution + strength / 4) * 100`.
+// Supported are numbers, attribute and stat ids, the operators `+`, `-`, `*` and `/`,
+// parentheses and the functions `min`, `max`, `floor`, `ceil`, `round` and `abs`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum StatExpression {
+    Constant(f32),
+    Variable(String),
+    Negate(Box<StatExpression>),
+    Binary(StatOperator, Box<StatExpression>, Box<StatExpression>),
+    Function(StatFunction, Vec<StatExpression>),
+}
+
+impl StatExpression {
+    pub fn parse(source: &str) -> std::result::Result<Self, String> {
+        let mut parser = ExpressionParser {
+            chars: source.chars().peekable(),
+        };
+
+        let expression = parser.parse_expression()?;
+        parser.skip_whitespace();
+        if let Some(c) = parser.chars.peek() {
+            return Err(format!("Unexpected '{}' in stat formula '{}'", c, source));
+        }
+
+        Ok(expression)
+    }
+
+    // Variables that are not found by `get_variable` will evaluate to zero
+    pub fn evaluate<F>(&self, get_variable: &F) -> f32
+    where
+        F: Fn(&str) -> Option<f32>,
+    {
+        match self {
+            StatExpression::Constant(value) => *value,
+            StatExpression::Variable(id) => get_variable(id).unwrap_or(0.0),
+            StatExpression::Negate(expression) => -expression.evaluate(get_variable),
+            StatExpression::Binary(operator, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.evaluate(get_variable), rhs.evaluate(get_variable));
+                match operator {
+                    StatOperator::Add => lhs + rhs,
+                    StatOperator::Subtract => lhs - rhs,
+                    StatOperator::Multiply => lhs * rhs,
+                    StatOperator::Divide => {
+                        if rhs != 0.0 {
+                            lhs / rhs
+                        } else {
+                            0.0
+                        }
+                    }
+                }
+            }
+            StatExpression::Function(function, args) => {
+                let args: Vec<f32> = args.iter().map(|arg| arg.evaluate(get_variable)).collect();
+                function.apply(&args)
+            }
+        }
+    }
+
+    pub fn get_variables(&self) -> Vec<String> {
+        let mut res = Vec::new();
+        self.collect_variables(&mut res);
+        res
+    }
+
+    fn collect_variables(&self, res: &mut Vec<String>) {
+        match self {
+            StatExpression::Constant(_) => {}
+            StatExpression::Variable(id) => {
+                if !res.contains(id) {
+                    res.push(id.clone());
+                }
+            }
+            StatExpression::Negate(expression) => expression.collect_variables(res),
+            StatExpression::Binary(_, lhs, rhs) => {
+                lhs.collect_variables(res);
+                rhs.collect_variables(res);
+            }
+            StatExpression::Function(_, args) => {
+                for arg in args {
+                    arg.collect_variables(res);
+                }
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for StatExpression {
+    type Error = String;
+
+    fn try_from(source: String) -> std::result::Result<Self, Self::Error> {
+        StatExpression::parse(&source)
+    }
+}
+
+impl From<StatExpression> for String {
+    fn from(expression: StatExpression) -> String {
+        expression.to_string()
+    }
+}
+
+impl fmt::Display for StatExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatExpression::Constant(value) => write!(f, "{}", value),
+            StatExpression::Variable(id) => write!(f, "{}", id),
+            StatExpression::Negate(expression) => write!(f, "-({})", expression),
+            StatExpression::Binary(operator, lhs, rhs) => {
+                write!(f, "({} {} {})", lhs, operator.as_str(), rhs)
+            }
+            StatExpression::Function(function, args) => {
+                let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+                write!(f, "{}({})", function.as_str(), args.join(", "))
+            }
+        }
+    }
+}
+
+struct ExpressionParser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> ExpressionParser<'a> {
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.chars.peek() {
+            if c.is_whitespace() {
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn next_if(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&expected) {
+            self.chars.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    // expression = term, { ("+" | "-"), term }
+    fn parse_expression(&mut self) -> std::result::Result<StatExpression, String> {
+        let mut res = self.parse_term()?;
+        loop {
+            let operator = if self.next_if('+') {
+                StatOperator::Add
+            } else if self.next_if('-') {
+                StatOperator::Subtract
+            } else {
+                return Ok(res);
+            };
+
+            let rhs = self.parse_term()?;
+            res = StatExpression::Binary(operator, Box::new(res), Box::new(rhs));
+        }
+    }
+
+    // term = factor, { ("*" | "/"), factor }
+    fn parse_term(&mut self) -> std::result::Result<StatExpression, String> {
+        let mut res = self.parse_factor()?;
+        loop {
+            let operator = if self.next_if('*') {
+                StatOperator::Multiply
+            } else if self.next_if('/') {
+                StatOperator::Divide
+            } else {
+                return Ok(res);
+            };
+
+            let rhs = self.parse_factor()?;
+            res = StatExpression::Binary(operator, Box::new(res), Box::new(rhs));
+        }
+    }
+
+    // factor = "-", factor | number | id | id, "(", arguments, ")" | "(", expression, ")"
+    fn parse_factor(&mut self) -> std::result::Result<StatExpression, String> {
+        self.skip_whitespace();
+
+        if self.next_if('-') {
+            let expression = self.parse_factor()?;
+            return Ok(StatExpression::Negate(Box::new(expression)));
+        }
+
+        if self.next_if('(') {
+            let expression = self.parse_expression()?;
+            if !self.next_if(')') {
+                return Err("Missing closing parenthesis in stat formula".to_string());
+            }
+            return Ok(expression);
+        }
+
+        match self.chars.peek().cloned() {
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let mut number = String::new();
+                while let Some(c) = self.chars.peek().cloned() {
+                    if c.is_ascii_digit() || c == '.' {
+                        number.push(c);
+                        self.chars.next();
+                    } else {
+                        break;
+                    }
+                }
+
+                number
+                    .parse()
+                    .map(StatExpression::Constant)
+                    .map_err(|_| format!("Invalid number '{}' in stat formula", number))
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let mut id = String::new();
+                while let Some(c) = self.chars.peek().cloned() {
+                    if c.is_alphanumeric() || c == '_' {
+                        id.push(c);
+                        self.chars.next();
+                    } else {
+                        break;
+                    }
+                }
+
+                if self.next_if('(') {
+                    let function = StatFunction::from_name(&id)
+                        .ok_or_else(|| format!("Unknown function '{}' in stat formula", id))?;
+
+                    let mut args = vec![self.parse_expression()?];
+                    while self.next_if(',') {
+                        args.push(self.parse_expression()?);
+                    }
+
+                    if !self.next_if(')') {
+                        return Err(format!(
+                            "Missing closing parenthesis after arguments to '{}' in stat formula",
+                            id
+                        ));
+                    }
+
+                    if !function.is_valid_arg_cnt(args.len()) {
+                        return Err(format!(
+                            "Wrong number of arguments to '{}' in stat formula",
+                            id
+                        ));
+                    }
+
+                    Ok(StatExpression::Function(function, args))
+                } else {
+                    Ok(StatExpression::Variable(id))
+                }
+            }
+            Some(c) => Err(format!("Unexpected '{}' in stat formula", c)),
+            None => Err("Unexpected end of stat formula".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttributeParams {
+    pub id: String,
+    #[serde(default, rename = "default")]
+    pub default_value: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DerivedStatParams {
+    pub id: String,
+    pub formula: StatExpression,
+}
+
+// This holds the custom attributes and the formulas for derived stats, as defined in a game's,
+// or a module's, `stats.json`. Derived stats are calculated in the order they are defined, so a
+// formula can reference any attribute, as well as any derived stat that is defined before it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatFormulas {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<AttributeParams>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub derived: Vec<DerivedStatParams>,
+}
+
+impl Default for StatFormulas {
+    fn default() -> Self {
+        let derived = DEFAULT_FORMULAS
+            .iter()
+            .map(|(id, formula)| DerivedStatParams {
+                id: id.to_string(),
+                formula: StatExpression::parse(formula).unwrap(),
+            })
+            .collect();
+
+        StatFormulas {
+            attributes: Vec::new(),
+            derived,
+        }
+    }
+}
+
+impl StatFormulas {
+    // Attributes and derived stats from `other` will replace the ones with the same id, keeping
+    // their position in the order of calculation, or be appended if they are new
+    pub fn extend(&mut self, other: StatFormulas) {
+        for attribute in other.attributes {
+            if let Some(existing) = self
+                .attributes
+                .iter_mut()
+                .find(|existing| existing.id == attribute.id)
+            {
+                *existing = attribute;
+            } else {
+                self.attributes.push(attribute);
+            }
+        }
+
+        for stat in other.derived {
+            if let Some(existing) = self
+                .derived
+                .iter_mut()
+                .find(|existing| existing.id == stat.id)
+            {
+                *existing = stat;
+            } else {
+                self.derived.push(stat);
+            }
+        }
+    }
+
+    pub fn get_default_attribute(&self, id: &str) -> Option<u32> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.id == id)
+            .map(|attribute| attribute.default_value)
+    }
+
+    // This checks that all the variables referenced by formulas are either attributes, or derived
+    // stats that are calculated before the formula in which they are referenced
+    pub fn validate(&self) -> Result<()> {
+        let mut known: Vec<String> = ATTRIBUTES.iter().map(|id| id.to_string()).collect();
+        for attribute in &self.attributes {
+            if known.contains(&attribute.id) || DERIVED_STATS.contains(&attribute.id.as_str()) {
+                let msg = format!("Attribute '{}' is already defined", attribute.id);
+                return Err(Error::new(ErrorKind::Parse, msg));
+            }
+            known.push(attribute.id.clone());
+        }
+
+        for stat in &self.derived {
+            if ATTRIBUTES.contains(&stat.id.as_str())
+                || self.get_default_attribute(&stat.id).is_some()
+            {
+                let msg = format!("Derived stat '{}' has the same id as an attribute", stat.id);
+                return Err(Error::new(ErrorKind::Parse, msg));
+            }
+
+            for variable in stat.formula.get_variables() {
+                if !known.contains(&variable) {
+                    let msg = format!(
+                        "The formula for '{}' references '{}', which is not an attribute, nor a stat that is calculated before it",
+                        stat.id, variable
+                    );
+                    return Err(Error::new(ErrorKind::Parse, msg));
+                }
+            }
+
+            known.push(stat.id.clone());
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatModifierKind {
+    // Added to the base value
+    Flat,
+    // Added together and applied as a percentage of the base value, after flat modifiers
+    Percent,
+    // Applied last, with each multiplier multiplying the result of the previous one
+    Multiplier,
+}
+
+impl Default for StatModifierKind {
+    fn default() -> Self {
+        StatModifierKind::Flat
+    }
+}
+
+// A modifier for an attribute or a derived stat. Modifiers for stats that are neither an
+// attribute nor have a formula, will be applied to a base value of zero and added to the actor's
+// derived stats, so that items can grant stats like `armor`, without a formula.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatModifier {
+    pub stat: String,
+    #[serde(default)]
+    pub kind: StatModifierKind,
+    pub value: f32,
+}
+
+impl StatModifier {
+    pub fn new(stat: &str, kind: StatModifierKind, value: f32) -> Self {
+        StatModifier {
+            stat: stat.to_string(),
+            kind,
+            value,
+        }
+    }
+}
+
+// This holds the modifiers that are applied to an actor's stats, grouped by the id of their
+// source, which can be an equipped item, a status effect or a class perk
+#[derive(Debug, Default, Clone)]
+pub struct StatModifiers {
+    sources: Vec<(String, Vec<StatModifier>)>,
+}
+
+impl StatModifiers {
+    // This will replace any modifiers previously added by the same source
+    pub fn add(&mut self, source: &str, modifiers: &[StatModifier]) {
+        self.remove(source);
+        if !modifiers.is_empty() {
+            self.sources.push((source.to_string(), modifiers.to_vec()));
+        }
+    }
+
+    // Returns `true` if any modifiers were removed
+    pub fn remove(&mut self, source: &str) -> bool {
+        let len = self.sources.len();
+        self.sources.retain(|(id, _)| id != source);
+        self.sources.len() != len
+    }
+
+    pub fn has_source(&self, source: &str) -> bool {
+        self.sources.iter().any(|(id, _)| id == source)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    pub fn is_modified(&self, stat: &str) -> bool {
+        self.iter().any(|modifier| modifier.stat == stat)
+    }
+
+    // Returns the ids of all the modified stats, without duplicates
+    pub fn get_stat_ids(&self) -> Vec<String> {
+        let mut res: Vec<String> = Vec::new();
+        for modifier in self.iter() {
+            if !res.contains(&modifier.stat) {
+                res.push(modifier.stat.clone());
+            }
+        }
+        res
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &StatModifier> {
+        self.sources
+            .iter()
+            .flat_map(|(_, modifiers)| modifiers.iter())
+    }
+
+    // Applies all the modifiers for `stat` to `value`, in the order flat, percent, multiplier
+    pub fn apply(&self, stat: &str, value: f32) -> f32 {
+        let mut flat = 0.0;
+        let mut percent = 0.0;
+        let mut multiplier = 1.0;
+        for modifier in self.iter().filter(|modifier| modifier.stat == stat) {
+            match modifier.kind {
+                StatModifierKind::Flat => flat += modifier.value,
+                StatModifierKind::Percent => percent += modifier.value,
+                StatModifierKind::Multiplier => multiplier *= modifier.value,
+            }
+        }
+        (value + flat) * (1.0 + percent / 100.0) * multiplier
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate(source: &str) -> f32 {
+        let get_variable = |id: &str| match id {
+            "strength" => Some(10.0),
+            "willpower" => Some(6.0),
+            _ => None,
+        };
+        StatExpression::parse(source)
+            .unwrap()
+            .evaluate(&get_variable)
+    }
+
+    #[test]
+    fn test_operator_precedence() {
+        assert_eq!(evaluate("1 + 2 * 3"), 7.0);
+        assert_eq!(evaluate("(1 + 2) * 3"), 9.0);
+        assert_eq!(evaluate("10 - 4 - 3"), 3.0);
+        assert_eq!(evaluate("-2 * -3"), 6.0);
+        assert_eq!(evaluate("1 / 0"), 0.0);
+    }
+
+    #[test]
+    fn test_variables_and_functions() {
+        assert_eq!(evaluate("strength * 0.5 + willpower"), 11.0);
+        assert_eq!(evaluate("floor(strength / 4)"), 2.0);
+        assert_eq!(evaluate("max(strength, willpower, 20)"), 20.0);
+        assert_eq!(evaluate("min(strength, willpower)"), 6.0);
+        assert_eq!(evaluate("unknown_stat + 1"), 1.0);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(StatExpression::parse("1 +").is_err());
+        assert!(StatExpression::parse("(1 + 2").is_err());
+        assert!(StatExpression::parse("1 2").is_err());
+        assert!(StatExpression::parse("sqrt(4)").is_err());
+        assert!(StatExpression::parse("floor(1, 2)").is_err());
+    }
+
+    #[test]
+    fn test_expression_round_trip() {
+        let expression = StatExpression::parse("-(a + 2) * max(b, 3.5) / c").unwrap();
+        let json = serde_json::to_string(&expression).unwrap();
+        let res: StatExpression = serde_json::from_str(&json).unwrap();
+        assert_eq!(res, expression);
+    }
+
+    #[test]
+    fn test_default_formulas() {
+        let mut stats = ActorStats {
+            strength: 9,
+            dexterity: 8,
+            constitution: 10,
+            intelligence: 6,
+            willpower: 7,
+            perception: 8,
+            charisma: 6,
+            ..Default::default()
+        };
+        stats.recalculate_derived_with(&StatFormulas::default());
+        assert_eq!(stats.max_health, 1300.0);
+        assert_eq!(stats.max_stamina, 1300.0);
+        assert_eq!(stats.max_energy, 1200.0);
+        assert_eq!(stats.view_distance, 220.0);
+        assert_eq!(stats.carry_capacity, 600.0);
+    }
+
+    #[test]
+    fn test_custom_stats() {
+        let json = r#"{
+            "attributes": [{ "id": "luck", "default": 5 }],
+            "derived": [
+                { "id": "max_health", "formula": "constitution * 10" },
+                { "id": "luck_bonus", "formula": "luck * 2" }
+            ]
+        }"#;
+        let mut formulas = StatFormulas::default();
+        formulas.extend(serde_json::from_str(json).unwrap());
+
+        let mut stats = ActorStats {
+            constitution: 10,
+            ..Default::default()
+        };
+        stats.recalculate_derived_with(&formulas);
+        assert_eq!(stats.max_health, 100.0);
+        assert_eq!(stats.get_stat("luck_bonus"), Some(10.0));
+
+        stats.attributes.insert("luck".to_string(), 20);
+        stats.recalculate_derived_with(&formulas);
+        assert_eq!(stats.get_stat("luck_bonus"), Some(40.0));
+    }
+
+    #[test]
+    fn test_set_attribute() {
+        let json = r#"{
+            "attributes": [{ "id": "luck", "default": 5 }],
+            "derived": [{ "id": "luck_bonus", "formula": "luck * 2" }]
+        }"#;
+        let mut formulas = StatFormulas::default();
+        formulas.extend(serde_json::from_str(json).unwrap());
+
+        let mut stats = ActorStats {
+            constitution: 10,
+            ..Default::default()
+        };
+        stats.recalculate_derived_with(&formulas);
+        let max_health = stats.max_health;
+
+        stats.set_attribute("constitution", 11);
+        stats.set_attribute("luck", 6);
+        assert_eq!(stats.constitution, 11);
+        assert_eq!(stats.get_base_stat("luck"), Some(6.0));
+        // Derived stats are only updated when they are recalculated
+        assert!((stats.max_health - max_health).abs() < f32::EPSILON);
+
+        stats.recalculate_derived_with(&formulas);
+        assert!(stats.max_health > max_health);
+        assert_eq!(stats.get_stat("luck_bonus"), Some(12.0));
+    }
+
+    #[test]
+    fn test_recalculate_derived_clamps_vitals() {
+        let mut stats = ActorStats {
+            constitution: 10,
+            ..Default::default()
+        };
+        stats.recalculate_derived_with(&StatFormulas::default());
+        stats.restore_vitals();
+        let max_health = stats.max_health;
+
+        stats.set_attribute("constitution", 5);
+        stats.recalculate_derived_with(&StatFormulas::default());
+        assert!(stats.max_health < max_health);
+        assert!((stats.current_health - stats.max_health).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut formulas = StatFormulas::default();
+        assert!(formulas.validate().is_ok());
+
+        let json = r#"{
+            "attributes": [{ "id": "luck", "default": 5 }],
+            "derived": [
+                { "id": "crit_chance", "formula": "luck * 0.01 + dodge_chance" },
+                { "id": "dodge_chance", "formula": "dexterity * 0.01" }
+            ]
+        }"#;
+        formulas.extend(serde_json::from_str(json).unwrap());
+        assert!(formulas.validate().is_err());
+
+        let json = r#"{
+            "derived": [{ "id": "crit_chance", "formula": "luck * 0.01 + max_health / 1000" }]
+        }"#;
+        formulas.extend(serde_json::from_str(json).unwrap());
+        assert!(formulas.validate().is_ok());
+        assert_eq!(formulas.get_default_attribute("luck"), Some(5));
+    }
+
+    #[test]
+    fn test_modifiers() {
+        let mut modifiers = StatModifiers::default();
+        modifiers.add(
+            "sword",
+            &[
+                StatModifier::new("strength", StatModifierKind::Flat, 2.0),
+                StatModifier::new("max_health", StatModifierKind::Percent, 10.0),
+            ],
+        );
+        modifiers.add(
+            "perk",
+            &[
+                StatModifier::new("strength", StatModifierKind::Flat, 3.0),
+                StatModifier::new("max_health", StatModifierKind::Percent, 40.0),
+                StatModifier::new("max_health", StatModifierKind::Multiplier, 2.0),
+            ],
+        );
+        assert_eq!(modifiers.apply("strength", 10.0), 15.0);
+        assert_eq!(modifiers.apply("max_health", 100.0), 300.0);
+        assert_eq!(modifiers.apply("dexterity", 10.0), 10.0);
+
+        modifiers.add(
+            "perk",
+            &[StatModifier::new(
+                "strength",
+                StatModifierKind::Multiplier,
+                2.0,
+            )],
+        );
+        assert_eq!(modifiers.apply("strength", 10.0), 24.0);
+
+        assert!(modifiers.remove("sword"));
+        assert!(!modifiers.remove("sword"));
+        assert_eq!(modifiers.get_stat_ids(), vec!["strength".to_string()]);
+    }
+
+    #[test]
+    fn test_modified_stats() {
+        let mut stats = ActorStats {
+            strength: 10,
+            constitution: 10,
+            ..Default::default()
+        };
+        stats.modifiers.add(
+            "ring",
+            &[
+                StatModifier::new("constitution", StatModifierKind::Flat, 5.0),
+                StatModifier::new("carry_capacity", StatModifierKind::Flat, 50.0),
+                StatModifier::new("armor", StatModifierKind::Flat, 4.0),
+            ],
+        );
+        stats.recalculate_derived_with(&StatFormulas::default());
+        assert_eq!(stats.get_stat("constitution"), Some(15.0));
+        assert_eq!(stats.get_base_stat("constitution"), Some(10.0));
+        assert_eq!(stats.max_health, 1700.0);
+        assert_eq!(stats.carry_capacity, 700.0);
+        assert_eq!(stats.get_stat("armor"), Some(4.0));
+
+        stats.modifiers.remove("ring");
+        stats.recalculate_derived_with(&StatFormulas::default());
+        assert_eq!(stats.max_health, 1200.0);
+        assert_eq!(stats.get_stat("armor"), None);
+    }
+}
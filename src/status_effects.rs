@@ -0,0 +1,242 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StatusEffectKind {
+    // Deals `amount` damage every tick
+    DamageOverTime {
+        damage_type: DamageType,
+        amount: f32,
+    },
+    // Restores `amount` health every tick
+    HealOverTime {
+        amount: f32,
+    },
+    // Reduces move speed by `percent`
+    Slow {
+        percent: f32,
+    },
+    // Prevents the actor from moving, attacking and using abilities
+    Stun,
+    // Prevents the actor from using its selected ability, but not its weapons
+    Silence,
+    // Applies stat modifiers for the duration of the effect, for buffs and debuffs
+    Modifiers {
+        modifiers: Vec<StatModifier>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusEffectStacking {
+    // Applying an effect that is already active will reset its duration
+    Refresh,
+    // Applying an effect that is already active will add a stack, up to `max_stacks`, and reset
+    // its duration. The amounts of the effect are multiplied by the number of stacks.
+    Stack,
+    // Applying an effect that is already active will do nothing
+    Ignore,
+}
+
+impl Default for StatusEffectStacking {
+    fn default() -> Self {
+        StatusEffectStacking::Refresh
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusEffectParams {
+    pub id: String,
+    pub kind: StatusEffectKind,
+    pub duration: f32,
+    // The time between ticks of damage and healing over time
+    #[serde(default = "StatusEffectParams::default_interval")]
+    pub interval: f32,
+    #[serde(default)]
+    pub stacking: StatusEffectStacking,
+    #[serde(default = "StatusEffectParams::default_max_stacks")]
+    pub max_stacks: u32,
+    // The color that affected actors will be tinted with, while the effect is active
+    #[serde(
+        default,
+        with = "json::opt_color",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub color: Option<Color>,
+}
+
+impl StatusEffectParams {
+    pub const DEFAULT_INTERVAL: f32 = 1.0;
+
+    pub fn default_interval() -> f32 {
+        Self::DEFAULT_INTERVAL
+    }
+
+    pub fn default_max_stacks() -> u32 {
+        1
+    }
+}
+
+// An active status effect. Only the id of the actor that applied the effect is saved, so effects
+// that are loaded from a save will not register their source as an attacker.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusEffect {
+    pub params: StatusEffectParams,
+    #[serde(default, rename = "source", skip_serializing_if = "String::is_empty")]
+    pub source_id: String,
+    #[serde(skip)]
+    pub source: Option<Handle<Actor>>,
+    pub time_remaining: f32,
+    #[serde(default)]
+    pub tick_timer: f32,
+    #[serde(default = "StatusEffectParams::default_max_stacks")]
+    pub stacks: u32,
+}
+
+impl StatusEffect {
+    pub fn new(source_id: &str, source: Option<Handle<Actor>>, params: StatusEffectParams) -> Self {
+        StatusEffect {
+            source_id: source_id.to_string(),
+            source,
+            time_remaining: params.duration,
+            tick_timer: 0.0,
+            stacks: 1,
+            params,
+        }
+    }
+
+    // The id used as the source of the effect's stat modifiers
+    pub fn get_modifier_source(&self) -> String {
+        format!("status_effect:{}", self.params.id)
+    }
+
+    // Returns the stat modifiers of the effect, scaled by the number of stacks
+    pub fn get_modifiers(&self) -> Vec<StatModifier> {
+        let stacks = self.stacks as f32;
+        match &self.params.kind {
+            StatusEffectKind::Slow { percent } => {
+                let percent = (percent * stacks).min(100.0);
+                vec![StatModifier::new(
+                    "move_speed",
+                    StatModifierKind::Percent,
+                    -percent,
+                )]
+            }
+            StatusEffectKind::Modifiers { modifiers } => modifiers
+                .iter()
+                .map(|modifier| {
+                    let value = match modifier.kind {
+                        StatModifierKind::Multiplier => modifier.value.powf(stacks),
+                        _ => modifier.value * stacks,
+                    };
+                    StatModifier::new(&modifier.stat, modifier.kind, value)
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    // This advances the timers of the effect and returns the number of ticks that occurred
+    pub fn update(&mut self, dt: f32) -> u32 {
+        let dt = dt.min(self.time_remaining.max(0.0));
+        self.time_remaining -= dt;
+
+        let mut ticks = 0;
+        if self.params.interval > 0.0 {
+            self.tick_timer += dt;
+            while self.tick_timer >= self.params.interval {
+                self.tick_timer -= self.params.interval;
+                ticks += 1;
+            }
+        }
+
+        ticks
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.time_remaining <= 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn poison() -> StatusEffectParams {
+        StatusEffectParams {
+            id: "poison".to_string(),
+            kind: StatusEffectKind::DamageOverTime {
+                damage_type: DamageType::Heat,
+                amount: 5.0,
+            },
+            duration: 2.0,
+            interval: 0.5,
+            stacking: StatusEffectStacking::Stack,
+            max_stacks: 3,
+            color: None,
+        }
+    }
+
+    #[test]
+    fn test_ticks() {
+        let mut status_effect = StatusEffect::new("enemy", None, poison());
+        assert_eq!(status_effect.update(0.25), 0);
+        assert_eq!(status_effect.update(0.25), 1);
+        assert_eq!(status_effect.update(1.0), 2);
+        assert!(!status_effect.is_expired());
+        // The effect can not tick beyond its duration
+        assert_eq!(status_effect.update(5.0), 1);
+        assert!(status_effect.is_expired());
+    }
+
+    #[test]
+    fn test_stacked_modifiers() {
+        let mut status_effect = StatusEffect::new(
+            "enemy",
+            None,
+            StatusEffectParams {
+                kind: StatusEffectKind::Slow { percent: 40.0 },
+                ..poison()
+            },
+        );
+        assert_eq!(status_effect.get_modifiers()[0].value, -40.0);
+        status_effect.stacks = 3;
+        assert_eq!(status_effect.get_modifiers()[0].value, -100.0);
+
+        status_effect.params.kind = StatusEffectKind::Modifiers {
+            modifiers: vec![
+                StatModifier::new("strength", StatModifierKind::Flat, 2.0),
+                StatModifier::new("max_health", StatModifierKind::Multiplier, 2.0),
+            ],
+        };
+        let modifiers = status_effect.get_modifiers();
+        assert_eq!(modifiers[0].value, 6.0);
+        assert_eq!(modifiers[1].value, 8.0);
+    }
+
+    #[test]
+    fn test_status_effect_from_json() {
+        let json = r#"{
+            "type": "apply_status",
+            "status": {
+                "id": "poison",
+                "kind": { "type": "damage_over_time", "damage_type": "heat", "amount": 5.0 },
+                "duration": 2.0,
+                "interval": 0.5,
+                "stacking": "stack",
+                "max_stacks": 3
+            }
+        }"#;
+        let effect: Effect = serde_json::from_str(json).unwrap();
+        assert_eq!(effect, Effect::ApplyStatus { status: poison() });
+
+        let mut status_effect = StatusEffect::new("enemy", None, poison());
+        status_effect.update(0.75);
+        let json = serde_json::to_string(&status_effect).unwrap();
+        let res: StatusEffect = serde_json::from_str(&json).unwrap();
+        assert_eq!(res.params, status_effect.params);
+        assert_eq!(res.source_id, "enemy");
+        assert_eq!(res.time_remaining, 1.25);
+        assert_eq!(res.tick_timer, 0.25);
+    }
+}
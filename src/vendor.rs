@@ -0,0 +1,340 @@
+use crate::prelude::*;
+
+// Determines when the stock of a vendor is replaced with a new one
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VendorRestock {
+    Never,
+    // The vendor is restocked every time `interval` seconds of play time have passed
+    Interval { interval: f32 },
+    // The vendor is restocked every time the map it is on is loaded
+    OnVisit,
+}
+
+impl Default for VendorRestock {
+    fn default() -> Self {
+        VendorRestock::Never
+    }
+}
+
+// A vendor prototype, which is referenced by the `vendor` field of an actor. These are defined in
+// the optional `vendors.json` data file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VendorParams {
+    pub id: String,
+    // The prototype ids of the items that the vendor is stocked with
+    #[serde(default)]
+    pub items: Vec<String>,
+    // A loot table that is rolled, and added to the stock, every time the vendor is restocked
+    #[serde(
+        default,
+        rename = "loot_table",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub loot_table_id: Option<String>,
+    // The credits that the vendor has to buy items with, after being restocked
+    #[serde(default)]
+    pub credits: u32,
+    // The value of items is multiplied by this, when an actor buys them from the vendor
+    #[serde(default = "VendorParams::default_sell_rate")]
+    pub sell_rate: f32,
+    // The value of items is multiplied by this, when an actor sells them to the vendor
+    #[serde(default = "VendorParams::default_buy_rate")]
+    pub buy_rate: f32,
+    // Price modifiers that are applied when the trading actor is a member of a faction, by
+    // faction id. A modifier of `0.8` will give a 20% discount, for example.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub faction_modifiers: HashMap<String, f32>,
+    #[serde(default)]
+    pub restock: VendorRestock,
+    // The number of sold items that can be bought back
+    #[serde(default = "VendorParams::default_buyback_size")]
+    pub buyback_size: usize,
+}
+
+impl VendorParams {
+    // Every point of charisma above, or below, this changes prices by `CHARISMA_PRICE_FACTOR`
+    pub const CHARISMA_BASELINE: f32 = 10.0;
+    pub const CHARISMA_PRICE_FACTOR: f32 = 0.02;
+
+    pub const MIN_PRICE_MODIFIER: f32 = 0.5;
+    pub const MAX_PRICE_MODIFIER: f32 = 1.5;
+
+    pub fn default_sell_rate() -> f32 {
+        1.0
+    }
+
+    pub fn default_buy_rate() -> f32 {
+        0.5
+    }
+
+    pub fn default_buyback_size() -> usize {
+        10
+    }
+
+    pub fn get_price_modifier(&self, charisma: f32, factions: &[String]) -> f32 {
+        let mut modifier = 1.0 - (charisma - Self::CHARISMA_BASELINE) * Self::CHARISMA_PRICE_FACTOR;
+        for faction_id in factions {
+            if let Some(faction_modifier) = self.faction_modifiers.get(faction_id) {
+                modifier *= faction_modifier;
+            }
+        }
+
+        modifier.clamp(Self::MIN_PRICE_MODIFIER, Self::MAX_PRICE_MODIFIER)
+    }
+
+    pub fn get_actor_price_modifier(&self, actor: &Actor) -> f32 {
+        let charisma = actor
+            .stats
+            .get_stat("charisma")
+            .unwrap_or(Self::CHARISMA_BASELINE);
+        self.get_price_modifier(charisma, &actor.factions)
+    }
+
+    // The price that an actor pays for an item, or a stack of items
+    pub fn get_buy_price(&self, params: &ItemParams, price_modifier: f32) -> u32 {
+        let value = params.value as f32 * params.quantity as f32;
+        (value * self.sell_rate * price_modifier).ceil() as u32
+    }
+
+    // The price that the vendor pays for an item, or a stack of items. This is never higher than
+    // the price of buying the item, so that items can not be traded back and forth for profit.
+    pub fn get_sell_price(&self, params: &ItemParams, price_modifier: f32) -> u32 {
+        let value = params.value as f32 * params.quantity as f32;
+        let price = (value * self.buy_rate / price_modifier).floor() as u32;
+        price.min(self.get_buy_price(params, price_modifier))
+    }
+}
+
+// An item that has been sold to a vendor, and the price that it can be bought back for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuybackItem {
+    pub item: ItemParams,
+    pub price: u32,
+}
+
+// The current stock of a vendor, which is saved with the character
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Vendor {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<ItemParams>,
+    #[serde(default)]
+    pub credits: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub buyback: Vec<BuybackItem>,
+    #[serde(default)]
+    pub time_since_restock: f32,
+}
+
+impl Vendor {
+    pub fn new(params: &VendorParams, context: &LootContext) -> Self {
+        let mut vendor = Vendor {
+            id: params.id.clone(),
+            items: Vec::new(),
+            credits: 0,
+            buyback: Vec::new(),
+            time_since_restock: 0.0,
+        };
+
+        vendor.restock(params, context);
+        vendor
+    }
+
+    // This replaces the stock and credits of the vendor and clears the items that can be bought
+    // back
+    pub fn restock(&mut self, params: &VendorParams, context: &LootContext) {
+        let resources = storage::get::<Resources>();
+        self.items = params
+            .items
+            .iter()
+            .map(|prototype_id| {
+                let prototype = resources
+                    .items
+                    .get(prototype_id)
+                    .unwrap_or_else(|| panic!("Unable to find item with id '{}'", prototype_id));
+                resources.affixes.generate(prototype)
+            })
+            .collect();
+
+        if let Some(loot_table_id) = &params.loot_table_id {
+            let (mut items, _) = roll_loot_table(loot_table_id, context);
+            self.items.append(&mut items);
+        }
+
+        self.credits = params.credits;
+        self.buyback = Vec::new();
+        self.time_since_restock = 0.0;
+    }
+
+    pub fn update(&mut self, params: &VendorParams, dt: f32, context: &LootContext) {
+        if let VendorRestock::Interval { interval } = params.restock {
+            self.time_since_restock += dt;
+            if self.time_since_restock >= interval {
+                self.restock(params, context);
+            }
+        }
+    }
+
+    // Returns `false` if the item can not be afforded, or if there is no room for it
+    pub fn buy(
+        &mut self,
+        params: &VendorParams,
+        item_id: &str,
+        inventory: &mut Inventory,
+        price_modifier: f32,
+    ) -> bool {
+        let i = match self.items.iter().position(|item| item.id == item_id) {
+            Some(i) => i,
+            None => return false,
+        };
+
+        let price = params.get_buy_price(&self.items[i], price_modifier);
+        if inventory.credits < price || !inventory.has_room_for(&self.items[i]) {
+            return false;
+        }
+
+        let item = self.items.remove(i);
+        inventory.credits -= price;
+        self.credits += price;
+        inventory.add(item, false);
+        true
+    }
+
+    // Returns `false` if the vendor can not afford the item, or if the item is equipped or is a
+    // quest item. Sold items can be bought back, for the same price, until the vendor is restocked.
+    pub fn sell(
+        &mut self,
+        params: &VendorParams,
+        item_id: &str,
+        inventory: &mut Inventory,
+        price_modifier: f32,
+    ) -> bool {
+        let price = match inventory
+            .items
+            .iter()
+            .find(|entry| entry.params.id == item_id)
+        {
+            Some(entry) => {
+                if entry.equipped_to != EquipmentSlot::None || entry.params.is_quest_item {
+                    return false;
+                }
+                params.get_sell_price(&entry.params, price_modifier)
+            }
+            None => return false,
+        };
+
+        if self.credits < price {
+            return false;
+        }
+
+        if let Some(item) = inventory.remove_item(item_id) {
+            inventory.add_credits(price);
+            self.credits -= price;
+            self.buyback.insert(0, BuybackItem { item, price });
+            self.buyback.truncate(params.buyback_size);
+            return true;
+        }
+
+        false
+    }
+
+    pub fn buy_back(&mut self, item_id: &str, inventory: &mut Inventory) -> bool {
+        let i = match self
+            .buyback
+            .iter()
+            .position(|buyback| buyback.item.id == item_id)
+        {
+            Some(i) => i,
+            None => return false,
+        };
+
+        let price = self.buyback[i].price;
+        if inventory.credits < price || !inventory.has_room_for(&self.buyback[i].item) {
+            return false;
+        }
+
+        let buyback = self.buyback.remove(i);
+        inventory.credits -= price;
+        self.credits += price;
+        inventory.add(buyback.item, false);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_params() -> VendorParams {
+        let json = r#"{
+            "id": "merchant",
+            "credits": 100,
+            "faction_modifiers": { "merchants": 0.5 },
+            "buyback_size": 1
+        }"#;
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn get_item(id: &str, value: u32) -> ItemParams {
+        ItemParams {
+            id: id.to_string(),
+            value,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_prices() {
+        let params = get_params();
+        let item = get_item("pistol", 100);
+
+        let modifier = params.get_price_modifier(10.0, &[]);
+        assert_eq!(params.get_buy_price(&item, modifier), 100);
+        assert_eq!(params.get_sell_price(&item, modifier), 50);
+
+        let modifier = params.get_price_modifier(15.0, &[]);
+        assert_eq!(params.get_buy_price(&item, modifier), 90);
+        assert_eq!(params.get_sell_price(&item, modifier), 55);
+
+        // The modifier is clamped, and selling never pays more than buying costs
+        let modifier = params.get_price_modifier(20.0, &["merchants".to_string()]);
+        assert!((modifier - VendorParams::MIN_PRICE_MODIFIER).abs() < f32::EPSILON);
+        assert_eq!(params.get_buy_price(&item, modifier), 50);
+        assert_eq!(params.get_sell_price(&item, modifier), 50);
+    }
+
+    #[test]
+    fn test_trade() {
+        let params = get_params();
+        let mut vendor = Vendor {
+            id: params.id.clone(),
+            items: vec![get_item("pistol", 100)],
+            credits: params.credits,
+            buyback: Vec::new(),
+            time_since_restock: 0.0,
+        };
+
+        let mut inventory = Inventory::new();
+        inventory.add_credits(90);
+        assert!(!vendor.buy(&params, "pistol", &mut inventory, 1.0));
+
+        inventory.add_credits(10);
+        assert!(vendor.buy(&params, "pistol", &mut inventory, 1.0));
+        assert_eq!(inventory.credits, 0);
+        assert_eq!(vendor.credits, 200);
+        assert!(vendor.items.is_empty());
+
+        inventory.add_item(get_item("rifle", 100));
+        assert!(vendor.sell(&params, "pistol", &mut inventory, 1.0));
+        assert!(vendor.sell(&params, "rifle", &mut inventory, 1.0));
+        assert_eq!(inventory.credits, 100);
+        assert_eq!(vendor.credits, 100);
+        // The buyback size of the vendor is one, so only the last sold item can be bought back
+        assert_eq!(vendor.buyback.len(), 1);
+        assert!(!vendor.buy_back("pistol", &mut inventory));
+        assert!(vendor.buy_back("rifle", &mut inventory));
+        assert_eq!(inventory.credits, 50);
+        assert_eq!(inventory.items.len(), 1);
+    }
+}
@@ -0,0 +1,120 @@
+#![allow(dead_code)]
+
+use rust_rpg_toolkit::prelude::*;
+use rust_rpg_toolkit::serde_json::{self, json};
+
+pub const TILE_SIZE: f32 = 16.0;
+
+// A fixed frame time, so that tests don't depend on a running game loop
+pub const FRAME_TIME: f32 = 1.0 / 60.0;
+
+pub const WATER_COLLISION_LAYER: &str = "water";
+
+// This builds a map from rows of characters, where `#` is a solid wall and `~` is water, which is
+// a barrier that is also on the `water` collision layer. Any other character is an empty tile.
+// The map is built from JSON, so it is loaded the same way as the maps of a game.
+pub fn fixture_map(rows: &[&str]) -> Map {
+    fixture_map_with_walls(rows, &[])
+}
+
+// This builds a map like `fixture_map`, with an additional collider layer of solid walls, where
+// each wall is a polyline through the points, in pixels
+pub fn fixture_map_with_walls(rows: &[&str], walls: &[&[(f32, f32)]]) -> Map {
+    let width = rows[0].len();
+    let height = rows.len();
+
+    let mut solids = Vec::new();
+    let mut water = Vec::new();
+    for row in rows {
+        assert_eq!(
+            row.len(),
+            width,
+            "All fixture map rows must be of equal length!"
+        );
+        for c in row.chars() {
+            solids.push(if c == '#' { 1 } else { 0 });
+            water.push(if c == '~' { 1 } else { 0 });
+        }
+    }
+
+    let mut json = json!({
+        "grid_size": { "x": width, "y": height },
+        "tile_size": { "x": TILE_SIZE, "y": TILE_SIZE },
+        "layers": [
+            {
+                "id": "water",
+                "kind": "tile_layer",
+                "collision": "barrier",
+                "collision_layers": [WATER_COLLISION_LAYER],
+                "tiles": water,
+                "is_visible": true,
+            },
+            {
+                "id": "solids",
+                "kind": "tile_layer",
+                "collision": "solid",
+                "tiles": solids,
+                "is_visible": true,
+            },
+        ],
+        "tilesets": [
+            {
+                "id": "tiles",
+                "texture_id": "tiles",
+                "texture_size": { "x": 16, "y": 16 },
+                "tile_size": { "x": 16, "y": 16 },
+                "grid_size": { "x": 1, "y": 1 },
+                "first_tile_id": 1,
+                "tile_cnt": 1,
+            },
+        ],
+    });
+
+    if !walls.is_empty() {
+        let objects: Vec<serde_json::Value> = walls
+            .iter()
+            .map(|points| {
+                let points: Vec<serde_json::Value> = points
+                    .iter()
+                    .map(|(x, y)| json!({ "x": x, "y": y }))
+                    .collect();
+                json!({
+                    "name": "wall",
+                    "position": { "x": 0.0, "y": 0.0 },
+                    "points": points,
+                })
+            })
+            .collect();
+
+        json["layers"].as_array_mut().unwrap().push(json!({
+            "id": "walls",
+            "kind": { "object_layer": "colliders" },
+            "collision": "solid",
+            "objects": objects,
+            "is_visible": true,
+        }));
+    }
+
+    serde_json::from_value(json).unwrap()
+}
+
+// The position of the top left corner of a tile
+pub fn tile_position(x: u32, y: u32) -> Vec2 {
+    vec2(x as f32 * TILE_SIZE, y as f32 * TILE_SIZE)
+}
+
+pub fn tile_center(x: u32, y: u32) -> Vec2 {
+    tile_position(x, y) + Vec2::splat(TILE_SIZE / 2.0)
+}
+
+// A body with the same collider as the actors in the example project
+pub fn test_body(position: Vec2) -> PhysicsBody {
+    PhysicsBody::new(position, 0.0, Some(Collider::rect(-8.0, -8.0, 16.0, 16.0)))
+}
+
+// Integrates the body for the specified number of frames
+pub fn integrate_frames(body: &mut PhysicsBody, map: &Map, frames: u32) {
+    for _ in 0..frames {
+        body.integrate(map, FRAME_TIME);
+    }
+}
@@ -0,0 +1,205 @@
+mod common;
+
+use rust_rpg_toolkit::prelude::*;
+
+use common::*;
+
+const ROOM: &[&str] = &[
+    "##########",
+    "#........#",
+    "#...#....#",
+    "#...#.~~.#",
+    "#...#.~~.#",
+    "#........#",
+    "##########",
+];
+
+#[test]
+fn test_get_collisions_empty_tile() {
+    let map = fixture_map(ROOM);
+    let collider = Collider::rect(-4.0, -4.0, 8.0, 8.0).with_offset(tile_center(2, 2));
+    assert!(map.get_collisions(collider).is_empty());
+}
+
+#[test]
+fn test_get_collisions_solid() {
+    let map = fixture_map(ROOM);
+    let collider =
+        Collider::rect(-8.0, -4.0, 16.0, 8.0).with_offset(tile_center(4, 2) - vec2(10.0, 0.0));
+    let collisions = map.get_collisions(collider);
+    assert_eq!(collisions.len(), 1);
+    assert_eq!(collisions[0], (tile_position(4, 2), CollisionKind::Solid));
+}
+
+#[test]
+fn test_get_collisions_barrier() {
+    let map = fixture_map(ROOM);
+    let collider = Collider::circle(0.0, 0.0, 4.0).with_offset(tile_center(6, 3));
+    let collisions = map.get_collisions(collider);
+    assert_eq!(
+        collisions,
+        vec![(tile_position(6, 3), CollisionKind::Barrier)]
+    );
+}
+
+#[test]
+fn test_get_collisions_circle_overlapping_corner() {
+    let map = fixture_map(ROOM);
+    let corner = tile_position(4, 2);
+
+    let touching = Collider::circle(corner.x - 2.0, corner.y - 2.0, 4.0);
+    assert_eq!(map.get_collisions(touching).len(), 1);
+
+    let not_touching = Collider::circle(corner.x - 4.0, corner.y - 4.0, 4.0);
+    assert!(map.get_collisions(not_touching).is_empty());
+}
+
+#[test]
+fn test_get_collisions_ignores_hidden_layers() {
+    let mut map = fixture_map(ROOM);
+    map.layers.get_mut("water").unwrap().is_visible = false;
+    let collider = Collider::circle(0.0, 0.0, 4.0).with_offset(tile_center(6, 3));
+    assert!(map.get_collisions(collider).is_empty());
+}
+
+#[test]
+fn test_get_collisions_at_map_edge() {
+    let map = fixture_map(ROOM);
+    let collider = Collider::rect(-4.0, -4.0, 8.0, 8.0).with_offset(tile_center(9, 6));
+    assert_eq!(
+        map.get_collisions(collider),
+        vec![(tile_position(9, 6), CollisionKind::Solid)]
+    );
+}
+
+#[test]
+fn test_get_masked_collisions() {
+    let map = fixture_map(ROOM);
+    let collider = Collider::rect(0.0, 0.0, TILE_SIZE * 4.0, TILE_SIZE)
+        .with_offset(tile_position(4, 3))
+        .with_padding(-1.0);
+
+    let solid_only = vec![SOLID_COLLISION_LAYER.to_string()];
+    let collisions = map.get_masked_collisions(collider, &solid_only);
+    assert_eq!(
+        collisions,
+        vec![(tile_position(4, 3), CollisionKind::Solid)]
+    );
+
+    let water_only = vec![WATER_COLLISION_LAYER.to_string()];
+    assert_eq!(map.get_masked_collisions(collider, &water_only).len(), 2);
+
+    let barriers = vec![BARRIER_COLLISION_LAYER.to_string()];
+    assert_eq!(map.get_masked_collisions(collider, &barriers).len(), 2);
+
+    assert_eq!(map.get_collisions(collider).len(), 3);
+    assert!(map.get_masked_collisions(collider, &[]).is_empty());
+}
+
+#[test]
+fn test_get_masked_edges() {
+    let map = fixture_map_with_walls(ROOM, &[&[(16.0, 16.0), (48.0, 48.0), (48.0, 80.0)]]);
+
+    let near = Collider::rect(26.0, 28.0, 8.0, 8.0);
+    let edges = map.get_masked_edges(near, &default_body_collision_mask());
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0].1, CollisionKind::Solid);
+    assert!(near.overlaps(edges[0].0));
+
+    // Collider layers are only collided with if they are in the collision mask
+    let mask = vec![BARRIER_COLLISION_LAYER.to_string()];
+    assert!(map.get_masked_edges(near, &mask).is_empty());
+
+    let far = Collider::rect(100.0, 16.0, 8.0, 8.0);
+    assert!(map
+        .get_masked_edges(far, &default_body_collision_mask())
+        .is_empty());
+}
+
+#[test]
+fn test_map_object_edges() {
+    let polygon = MapObject {
+        name: "rock".to_string(),
+        position: vec2(10.0, 10.0),
+        size: None,
+        points: vec![vec2(0.0, 0.0), vec2(10.0, 0.0), vec2(0.0, 10.0)],
+        is_closed: true,
+        properties: HashMap::new(),
+    };
+    let edges = polygon.get_edges();
+    assert_eq!(edges.len(), 3);
+    match edges[2] {
+        // The last point is connected to the first
+        Collider::Edge { x1, y1, x2, y2 } => {
+            assert_eq!(vec2(x1, y1), vec2(10.0, 20.0));
+            assert_eq!(vec2(x2, y2), vec2(10.0, 10.0));
+        }
+        _ => panic!("Expected an edge"),
+    }
+
+    let polyline = MapObject {
+        is_closed: false,
+        ..polygon.clone()
+    };
+    assert_eq!(polyline.get_edges().len(), 2);
+
+    // Rectangles without any points are made into closed polygons
+    let rect = MapObject {
+        points: Vec::new(),
+        size: Some(vec2(16.0, 8.0)),
+        ..polyline
+    };
+    assert_eq!(rect.get_edges().len(), 4);
+}
+
+#[test]
+fn test_get_path_around_wall() {
+    let map = fixture_map(ROOM);
+    let start = tile_center(2, 3);
+    let end = tile_center(5, 3);
+
+    let path = map.get_path(start, end).unwrap();
+    assert_eq!(path.destination, tile_position(5, 3));
+    assert_eq!(*path.nodes.last().unwrap(), end);
+
+    for node in &path.nodes {
+        let collider = Collider::circle(node.x, node.y, 1.0);
+        assert!(
+            map.get_collisions(collider).is_empty(),
+            "Path goes through a wall at {}",
+            node
+        );
+    }
+
+    // The path must go around the end of the wall, at either the top or the bottom of the room
+    assert!(path
+        .nodes
+        .iter()
+        .any(|node| node.y < tile_position(4, 2).y || node.y > tile_position(4, 5).y));
+}
+
+#[test]
+fn test_get_path_blocked() {
+    let map = fixture_map(&["#######", "#..#..#", "#..#..#", "#######"]);
+    assert!(map.get_path(tile_center(1, 1), tile_center(5, 1)).is_none());
+}
+
+#[test]
+fn test_get_path_does_not_wrap_around_edges() {
+    let map = fixture_map(&[".#...", ".#...", ".#..."]);
+    assert!(map.get_path(tile_center(0, 1), tile_center(3, 1)).is_none());
+}
+
+#[test]
+fn test_get_path_does_not_cut_corners() {
+    let map = fixture_map(&["#####", "#..##", "##..#", "#####"]);
+    let path = map.get_path(tile_center(2, 1), tile_center(2, 2)).unwrap();
+    assert_eq!(path.nodes, vec![tile_center(2, 2)]);
+
+    // Moving diagonally from (1, 1) to (2, 2) would cut the corner of the wall at (1, 2)
+    let path = map.get_path(tile_center(1, 1), tile_center(3, 2)).unwrap();
+    assert_eq!(
+        path.nodes,
+        vec![tile_center(2, 1), tile_center(2, 2), tile_center(3, 2)]
+    );
+}
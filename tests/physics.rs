@@ -0,0 +1,339 @@
+mod common;
+
+use rust_rpg_toolkit::physics::get_beam_end;
+use rust_rpg_toolkit::prelude::*;
+
+use common::*;
+
+const CORRIDOR: &[&str] = &[
+    "############",
+    "#..........#",
+    "#......~...#",
+    "#......~...#",
+    "#..........#",
+    "############",
+];
+
+const ROOM: &[&str] = &[
+    "############",
+    "#..........#",
+    "#..........#",
+    "#..........#",
+    "#..........#",
+    "#..........#",
+    "#..........#",
+    "############",
+];
+
+const BEAM_TOLERANCE: f32 = 350.0;
+
+fn assert_near(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 0.1,
+        "Expected {}, got {}",
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn test_circle_overlaps_rect() {
+    let rect = Collider::rect(10.0, 10.0, 10.0, 10.0);
+    assert!(Collider::circle(15.0, 15.0, 1.0).overlaps(rect));
+    assert!(Collider::circle(8.0, 15.0, 3.0).overlaps(rect));
+    assert!(!Collider::circle(5.0, 15.0, 3.0).overlaps(rect));
+    assert!(!Collider::circle(7.0, 7.0, 3.0).overlaps(rect));
+}
+
+#[test]
+fn test_raycast_hits_wall() {
+    let map = fixture_map(CORRIDOR);
+    let origin = tile_center(2, 1);
+    let end = tile_center(2, 1) + vec2(0.0, -TILE_SIZE * 2.0);
+
+    let hit = raycast_map(&map, &[], origin, end, false).unwrap();
+    assert_near(hit.x, origin.x);
+    assert!(hit.y > TILE_SIZE - 2.0 && hit.y <= TILE_SIZE + 1.0);
+}
+
+#[test]
+fn test_raycast_clear() {
+    let map = fixture_map(CORRIDOR);
+    assert_eq!(
+        raycast_map(&map, &[], tile_center(1, 1), tile_center(10, 1), false),
+        None
+    );
+}
+
+#[test]
+fn test_raycast_barriers() {
+    let map = fixture_map(CORRIDOR);
+    let origin = tile_center(5, 2);
+    let end = tile_center(9, 2);
+
+    let hit = raycast_map(&map, &[], origin, end, false).unwrap();
+    assert!(hit.x < tile_position(7, 2).x);
+
+    assert_eq!(raycast_map(&map, &[], origin, end, true), None);
+}
+
+#[test]
+fn test_raycast_actors() {
+    let map = fixture_map(CORRIDOR);
+    let origin = tile_center(1, 4);
+    let end = tile_center(10, 4);
+    let actor = Collider::rect(-8.0, -8.0, 16.0, 16.0).with_offset(tile_center(5, 4));
+
+    let hit = raycast_map(&map, &[actor], origin, end, true).unwrap();
+    assert_near(hit.x, tile_position(5, 4).x);
+}
+
+#[test]
+fn test_beam_end_at_wall() {
+    let map = fixture_map(CORRIDOR);
+    let mask = default_projectile_collision_mask();
+    let origin = tile_center(1, 1);
+    let end = origin + vec2(TILE_SIZE * 20.0, 0.0);
+
+    let beam_end = get_beam_end(&map, origin, end, 4.0, BEAM_TOLERANCE, &mask);
+    assert_eq!(beam_end, tile_center(11, 1));
+}
+
+#[test]
+fn test_beam_end_unobstructed() {
+    let map = fixture_map(CORRIDOR);
+    let mask = default_projectile_collision_mask();
+    let origin = tile_center(1, 1);
+    let end = tile_center(5, 1);
+
+    assert_eq!(
+        get_beam_end(&map, origin, end, 4.0, BEAM_TOLERANCE, &mask),
+        end
+    );
+}
+
+#[test]
+fn test_beam_end_collision_mask() {
+    let map = fixture_map(CORRIDOR);
+    let origin = tile_center(1, 2);
+    let end = tile_center(10, 2);
+
+    let mask = default_projectile_collision_mask();
+    assert_eq!(
+        get_beam_end(&map, origin, end, 4.0, BEAM_TOLERANCE, &mask),
+        end
+    );
+
+    let mask = vec![WATER_COLLISION_LAYER.to_string()];
+    assert_eq!(
+        get_beam_end(&map, origin, end, 4.0, BEAM_TOLERANCE, &mask),
+        tile_center(7, 2)
+    );
+}
+
+#[test]
+fn test_integrate_free_movement() {
+    let map = fixture_map(CORRIDOR);
+    let start = tile_center(2, 1);
+    let mut body = test_body(start);
+    body.velocity = vec2(1.0, 0.0);
+
+    integrate_frames(&mut body, &map, 60);
+    assert_near(body.position.x, start.x + 50.0);
+    assert_near(body.position.y, start.y);
+    assert!(body.last_collisions.is_empty());
+}
+
+#[test]
+fn test_integrate_stops_at_wall() {
+    let map = fixture_map(CORRIDOR);
+    let mut body = test_body(tile_center(2, 1));
+    body.velocity = vec2(0.0, -2.0);
+
+    body.integrate(&map, FRAME_TIME);
+    assert_near(body.position.y, TILE_SIZE + 8.0);
+
+    let collision = &body.last_collisions[0];
+    assert_eq!(collision.kind, CollisionKind::Solid);
+    assert_eq!(collision.normal, vec2(0.0, 1.0));
+}
+
+#[test]
+fn test_integrate_never_tunnels() {
+    let map = fixture_map(CORRIDOR);
+    let mut body = test_body(tile_center(2, 1));
+
+    // This would move the body more than a full tile per frame
+    body.velocity = vec2(-30.0, 0.0);
+    integrate_frames(&mut body, &map, 10);
+    assert_near(body.position.x, TILE_SIZE + 8.0);
+}
+
+#[test]
+fn test_integrate_slides_along_wall() {
+    let map = fixture_map(CORRIDOR);
+    let start = tile_center(3, 1);
+    let mut body = test_body(start);
+    body.velocity = vec2(1.0, -1.0);
+
+    integrate_frames(&mut body, &map, 30);
+    assert_near(body.position.y, TILE_SIZE + 8.0);
+    assert_near(body.position.x, start.x + 25.0);
+}
+
+#[test]
+fn test_integrate_into_corner() {
+    let map = fixture_map(CORRIDOR);
+    let mut body = test_body(tile_center(2, 2));
+    body.velocity = vec2(-2.0, -2.0);
+
+    integrate_frames(&mut body, &map, 60);
+    assert_near(body.position.x, TILE_SIZE + 8.0);
+    assert_near(body.position.y, TILE_SIZE + 8.0);
+}
+
+#[test]
+fn test_integrate_collision_mask() {
+    let map = fixture_map(CORRIDOR);
+    let start = tile_center(5, 2);
+
+    let mut body = test_body(start);
+    body.velocity = vec2(2.0, 0.0);
+    integrate_frames(&mut body, &map, 30);
+    assert_near(body.position.x, tile_position(7, 2).x - 8.0);
+    assert_eq!(body.last_collisions[0].kind, CollisionKind::Barrier);
+
+    let mut ghost = test_body(start);
+    ghost.collision_mask = vec![SOLID_COLLISION_LAYER.to_string()];
+    ghost.velocity = vec2(2.0, 0.0);
+    integrate_frames(&mut ghost, &map, 30);
+    assert!(ghost.position.x > tile_position(8, 2).x);
+}
+
+#[test]
+fn test_integrate_without_collider() {
+    let map = fixture_map(CORRIDOR);
+    let start = tile_center(2, 1);
+    let mut body = PhysicsBody::new(start, 0.0, None);
+    body.velocity = vec2(0.0, -2.0);
+
+    integrate_frames(&mut body, &map, 30);
+    assert_near(body.position.y, start.y - 50.0);
+}
+
+#[test]
+fn test_integrate_slides_along_diagonal_wall() {
+    // A wall that runs diagonally, from the top of the room, down and to the right
+    let map = fixture_map_with_walls(ROOM, &[&[(64.0, 16.0), (144.0, 96.0)]]);
+    let start = tile_center(2, 3);
+    let mut body = test_body(start);
+    body.velocity = vec2(1.0, 0.0);
+
+    integrate_frames(&mut body, &map, 120);
+
+    // The body is deflected downwards, along the wall, and its top right corner never crosses it
+    assert!(body.position.y > start.y + 10.0);
+    let corner = body.position + vec2(8.0, -8.0);
+    assert!(corner.x - corner.y <= 48.0 + 0.1);
+    assert!(!body.last_collisions.is_empty());
+}
+
+#[test]
+fn test_teleport_stops_before_diagonal_wall() {
+    let map = fixture_map_with_walls(ROOM, &[&[(64.0, 16.0), (144.0, 96.0)]]);
+    let mut body = test_body(tile_center(2, 3));
+
+    body.teleport(&map, vec2(1.0, 0.0), TILE_SIZE * 6.0);
+    let corner = body.position + vec2(8.0, -8.0);
+    assert!(corner.x - corner.y <= 48.0 + 0.1);
+    assert!(corner.x - corner.y > 48.0 - 1.0);
+}
+
+#[test]
+fn test_teleport() {
+    let map = fixture_map(CORRIDOR);
+    let start = tile_center(2, 1);
+    let mut body = test_body(start);
+
+    body.teleport(&map, vec2(1.0, 0.0), TILE_SIZE * 3.0);
+    assert_near(body.position.x, start.x + TILE_SIZE * 3.0);
+    assert_near(body.position.y, start.y);
+
+    // The body starts out touching the wall above it, and is teleported along it
+    body.teleport(&map, vec2(1.0, 0.0), TILE_SIZE * 2.0);
+    assert_near(body.position.x, start.x + TILE_SIZE * 5.0);
+}
+
+#[test]
+fn test_teleport_stops_before_wall() {
+    let map = fixture_map(CORRIDOR);
+    let mut body = test_body(tile_center(3, 4));
+
+    body.teleport(&map, vec2(0.0, -1.0), TILE_SIZE * 10.0);
+    assert!(body.position.y >= TILE_SIZE + 8.0 && body.position.y < TILE_SIZE + 9.0);
+
+    // Water is a barrier, so the body can not be teleported across it
+    let mut body = test_body(tile_center(5, 2));
+    body.teleport(&map, vec2(1.0, 0.0), TILE_SIZE * 4.0);
+    assert!(body.position.x <= tile_position(7, 2).x - 8.0);
+}
+
+#[test]
+fn test_impulse_is_damped() {
+    let map = fixture_map(CORRIDOR);
+    let start = tile_center(2, 3);
+    let mut body = test_body(start);
+
+    body.apply_impulse(vec2(4.0, 0.0));
+    assert_eq!(body.external_velocity, vec2(4.0, 0.0));
+
+    integrate_frames(&mut body, &map, 60);
+    assert!(body.position.x > start.x);
+    assert_eq!(body.external_velocity, Vec2::ZERO);
+}
+
+#[test]
+fn test_impulse_is_divided_by_mass() {
+    let mut body = test_body(Vec2::ZERO);
+    body.mass = 2.0;
+    body.apply_impulse(vec2(4.0, 0.0));
+    assert_eq!(body.external_velocity, vec2(2.0, 0.0));
+}
+
+#[test]
+fn test_impulse_into_wall_is_cancelled() {
+    let map = fixture_map(CORRIDOR);
+    let mut body = test_body(tile_center(1, 2));
+    body.apply_impulse(vec2(-10.0, -5.0));
+
+    body.integrate(&map, FRAME_TIME);
+    assert_eq!(body.external_velocity.x, 0.0);
+    assert!(body.external_velocity.y < 0.0);
+}
+
+#[test]
+fn test_force_is_applied_once() {
+    let map = fixture_map(CORRIDOR);
+    let mut body = test_body(tile_center(2, 3));
+    body.damping = 0.0;
+
+    body.apply_force(vec2(60.0, 0.0));
+    body.integrate(&map, FRAME_TIME);
+    assert_near(body.external_velocity.x, 1.0);
+
+    body.integrate(&map, FRAME_TIME);
+    assert_near(body.external_velocity.x, 1.0);
+}
+
+#[test]
+fn test_max_speed() {
+    let map = fixture_map(CORRIDOR);
+    let start = tile_center(2, 3);
+    let mut body = test_body(start);
+    body.max_speed = Some(1.0);
+    body.velocity = vec2(1.0, 0.0);
+    body.apply_impulse(vec2(10.0, 0.0));
+
+    body.integrate(&map, FRAME_TIME);
+    assert_near(body.position.x, start.x + 50.0 * FRAME_TIME);
+}